@@ -44,6 +44,20 @@ impl super::Api {
         Ok(version)
     }
 
+    /// Major version of the application, e.g. `5` for `v5.1.0`
+    ///
+    /// Requested once, then cached for the lifetime of the client.
+    pub(crate) async fn major_version(&self) -> Result<u32, Error> {
+        if let Some(major) = *self.major_version.read().await {
+            return Ok(major);
+        }
+
+        let major = parse_major_version(&self.version().await?)?;
+        *self.major_version.write().await = Some(major);
+
+        Ok(major)
+    }
+
     /// Get WebAPI version
     ///
     /// The response is a string with the WebAPI version, e.g. `2.11.4`
@@ -376,3 +390,26 @@ impl super::Api {
             .await?)
     }
 }
+
+/// Parses the major version out of an application version like `v5.1.0`.
+fn parse_major_version(version: &str) -> Result<u32, Error> {
+    version
+        .trim()
+        .trim_start_matches('v')
+        .split('.')
+        .next()
+        .and_then(|major| major.parse().ok())
+        .ok_or_else(|| Error::InvalidResponse(format!("Invalid version: {:?}", version)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn major_version() {
+        assert_eq!(parse_major_version("v5.1.0").unwrap(), 5);
+        assert_eq!(parse_major_version("v4.6.7\n").unwrap(), 4);
+        assert!(parse_major_version("unknown").is_err());
+    }
+}
@@ -0,0 +1,261 @@
+use std::future::Future;
+
+use futures_util::{StreamExt, stream};
+use reqwest::multipart;
+
+use crate::{
+    error::{BulkError, ChunkFailure, Error},
+    models::{InfoHash, Torrent},
+    parameters::{BulkOptions, TorrentListParams, TorrentSelector},
+};
+
+impl super::Api {
+    /// Set how bulk operations are split into requests
+    ///
+    /// Methods acting on several torrents send the selected hashes joined in
+    /// a single field. Large selections are split into chunks of
+    /// `chunk_size` hashes which are sent with at most `concurrency` requests
+    /// in flight. [`TorrentSelector::All`] is always sent as one request.
+    ///
+    /// When a selection needs several requests and some of them fail, the
+    /// operation returns [`Error::BulkFailed`] listing the failed chunks.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - The chunk size and concurrency to use.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use qbit::{Api, Credentials};
+    /// use qbit::parameters::BulkOptionsBuilder;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let credentials = Credentials::new("username", "password");
+    ///     let mut client = Api::new_login("url", credentials)
+    ///         .await
+    ///         .unwrap();
+    ///
+    ///     let options = BulkOptionsBuilder::default()
+    ///         .chunk_size(100)
+    ///         .concurrency(2)
+    ///         .build()
+    ///         .unwrap();
+    ///     client.set_bulk_options(options);
+    /// }
+    /// ```
+    pub fn set_bulk_options(&mut self, options: BulkOptions) {
+        self.bulk_options = options;
+    }
+
+    /// The current bulk options
+    pub fn bulk_options(&self) -> BulkOptions {
+        self.bulk_options
+    }
+
+    /// Resolves a selector to the chunks a bulk operation is sent for.
+    ///
+    /// Empty if the selector matches no torrent.
+    pub(crate) async fn selected_chunks(
+        &self,
+        selector: TorrentSelector,
+    ) -> Result<Vec<TorrentSelector>, Error> {
+        let hashes = match selector {
+            TorrentSelector::All => return Ok(vec![TorrentSelector::All]),
+            TorrentSelector::Hashes(hashes) => hashes,
+            TorrentSelector::Filter(params) => self
+                .torrents(Some(params))
+                .await?
+                .into_iter()
+                .map(|torrent| torrent.hash)
+                .collect(),
+        };
+
+        Ok(chunk(hashes, self.bulk_options.chunk_size))
+    }
+
+    /// Resolves a selector to the torrents it matches, with their state.
+    pub(crate) async fn selected_torrents(
+        &self,
+        selector: TorrentSelector,
+    ) -> Result<Vec<Torrent>, Error> {
+        let params = match selector {
+            TorrentSelector::All => None,
+            TorrentSelector::Hashes(hashes) if hashes.is_empty() => return Ok(Vec::new()),
+            TorrentSelector::Hashes(hashes) => Some(TorrentListParams {
+                hashes: Some(hashes),
+                ..Default::default()
+            }),
+            TorrentSelector::Filter(params) => Some(params),
+        };
+
+        self.torrents(params).await
+    }
+
+    /// Runs `request` with the `hashes` value of every chunk of `selector`.
+    ///
+    /// Returns the result of every request, or the error of the only request
+    /// if the selection fits in a single one.
+    pub(crate) async fn for_each_chunk<T, F, Fut>(
+        &self,
+        selector: TorrentSelector,
+        request: F,
+    ) -> Result<Vec<T>, Error>
+    where
+        F: Fn(String) -> Fut,
+        Fut: Future<Output = Result<T, Error>>,
+    {
+        let chunks = self.selected_chunks(selector).await?;
+        let count = chunks.len();
+
+        let results = stream::iter(chunks)
+            .map(|chunk| {
+                let hashes = hashes_field(&chunk);
+                let response = request(hashes);
+                async move { (chunk, response.await) }
+            })
+            .buffered(self.bulk_options.concurrency.max(1))
+            .collect::<Vec<_>>()
+            .await;
+
+        let mut values = Vec::with_capacity(count);
+        let mut failures = Vec::new();
+
+        for (selector, result) in results {
+            match result {
+                Ok(value) => values.push(value),
+                Err(error) if count == 1 => return Err(error),
+                Err(error) => failures.push(ChunkFailure { selector, error }),
+            }
+        }
+
+        if failures.is_empty() {
+            Ok(values)
+        } else {
+            Err(Error::BulkFailed(BulkError {
+                chunks: count,
+                failures,
+            }))
+        }
+    }
+
+    /// Posts the form built by `form` to `endpoint`, with the `hashes` field
+    /// set to the selected torrents.
+    pub(crate) async fn post_selected(
+        &self,
+        endpoint: &str,
+        selector: TorrentSelector,
+        form: impl Fn(multipart::Form) -> multipart::Form,
+    ) -> Result<(), Error> {
+        let form = &form;
+
+        self.for_each_chunk(selector, |hashes| async move {
+            self._post(endpoint)
+                .await?
+                .multipart(form(multipart::Form::new().text("hashes", hashes)))
+                .send()
+                .await?
+                .error_for_status()?;
+
+            Ok(())
+        })
+        .await?;
+
+        Ok(())
+    }
+}
+
+/// Splits `hashes` into selectors of at most `size` hashes.
+fn chunk(hashes: Vec<InfoHash>, size: usize) -> Vec<TorrentSelector> {
+    hashes
+        .chunks(size.max(1))
+        .map(|chunk| TorrentSelector::Hashes(chunk.to_vec()))
+        .collect()
+}
+
+/// Value of the `hashes` field for a chunk.
+fn hashes_field(selector: &TorrentSelector) -> String {
+    match selector {
+        TorrentSelector::Hashes(hashes) => InfoHash::join(hashes),
+        _ => String::from("all"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hashes(count: usize) -> Vec<InfoHash> {
+        (0..count)
+            .map(|i| InfoHash::new(&format!("{:040x}", i)).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn chunk_splits_hashes() {
+        let chunks = chunk(hashes(5), 2);
+
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks[2], TorrentSelector::Hashes(hashes(5)[4..].to_vec()));
+    }
+
+    #[test]
+    fn chunk_empty() {
+        assert!(chunk(Vec::new(), 2).is_empty());
+    }
+
+    #[test]
+    fn hashes_field_joins() {
+        let selector = TorrentSelector::Hashes(hashes(2));
+
+        assert_eq!(hashes_field(&selector), format!("{:040x}|{:040x}", 0, 1));
+        assert_eq!(hashes_field(&TorrentSelector::All), "all");
+    }
+
+    #[tokio::test]
+    async fn for_each_chunk_reports_failed_chunks() {
+        let mut client = crate::Api::new("http://127.0.0.1/").unwrap();
+        client.set_bulk_options(BulkOptions {
+            chunk_size: 2,
+            concurrency: 2,
+        });
+
+        let failing = format!("{:040x}", 2);
+        let result = client
+            .for_each_chunk(TorrentSelector::Hashes(hashes(5)), |field| {
+                let failing = failing.clone();
+                async move {
+                    if field.starts_with(&failing) {
+                        Err(Error::InvalidRequest(field))
+                    } else {
+                        Ok(field)
+                    }
+                }
+            })
+            .await;
+
+        let Err(Error::BulkFailed(report)) = result else {
+            panic!("Expected a bulk failure");
+        };
+        assert_eq!(report.chunks, 3);
+        assert_eq!(report.failures.len(), 1);
+        assert_eq!(
+            report.failures[0].selector,
+            TorrentSelector::Hashes(hashes(5)[2..4].to_vec())
+        );
+    }
+
+    #[tokio::test]
+    async fn for_each_chunk_single_request_error() {
+        let client = crate::Api::new("http://127.0.0.1/").unwrap();
+
+        let result: Result<Vec<()>, Error> = client
+            .for_each_chunk(TorrentSelector::All, |field| async move {
+                Err(Error::InvalidRequest(field))
+            })
+            .await;
+
+        assert!(matches!(result, Err(Error::InvalidRequest(field)) if field == "all"));
+    }
+}
@@ -1,8 +1,17 @@
-use std::collections::HashMap;
+use std::{
+    collections::{HashMap, VecDeque},
+    future::Future,
+    path::PathBuf,
+    sync::Arc,
+};
+
+use futures_util::{Stream, StreamExt, stream};
+use regex::Regex;
 
 use crate::{
     error::Error,
-    models::{LogItem, LogPeers, LogType},
+    models::{LogCursor, LogItem, LogPeers, LogType, LogTypes},
+    parameters::{LogFilter, LogStreamParams},
 };
 
 impl super::Api {
@@ -14,14 +23,14 @@ impl super::Api {
     ///
     /// # Arguments
     ///
-    /// * `last_known_id` - Exclude messages with "message id" <= `last_known_id` (default: `-1`)
-    /// * `log_types` - List of desierd log types. (default: all)
+    /// * `filter` - Filter parameter object. (default: all messages)
     ///
     /// # Example
     ///
     /// ```no_run
     /// use qbit::{Api, Credentials};
     /// use qbit::models::LogType;
+    /// use qbit::parameters::LogFilterBuilder;
     ///
     /// #[tokio::main]
     /// async fn main() {
@@ -30,27 +39,52 @@ impl super::Api {
     ///         .await
     ///         .unwrap();
     ///
-    ///     let types = vec![LogType::Critical, LogType::Warning];
-    ///     let log = client.log(None, Some(types)).await.unwrap();
+    ///     let filter = LogFilterBuilder::default()
+    ///         .log_types(vec![LogType::Critical, LogType::Warning])
+    ///         .message("tracker")
+    ///         .build()
+    ///         .unwrap();
+    ///     let log = client.log(Some(filter)).await.unwrap();
     ///
     ///     for item in log {
     ///         println!("{:?}", item);
     ///     }
     /// }
     /// ```
-    pub async fn log(
+    pub async fn log(&self, filter: Option<LogFilter>) -> Result<Vec<LogItem>, Error> {
+        let filter = filter.unwrap_or_default();
+        let matcher = LogMatcher::new(&filter)?;
+
+        let log = self
+            .raw_log(filter.last_known_id, filter.log_types)
+            .await?
+            .into_iter()
+            .filter(|item| matcher.matches(item))
+            .collect();
+
+        Ok(log)
+    }
+
+    /// Main log with only the server side filters applied.
+    async fn raw_log(
         &self,
         last_known_id: Option<i64>,
-        log_types: Option<Vec<LogType>>,
+        log_types: LogTypes,
     ) -> Result<Vec<LogItem>, Error> {
         let mut query = HashMap::new();
         if let Some(last_known_id) = last_known_id {
             query.insert("last_known_id".to_string(), last_known_id.to_string());
         }
-        if let Some(log_types) = log_types {
-            log_types.iter().for_each(|log_type| {
-                query.insert(log_type.to_string(), "true".to_string());
-            });
+        for log_type in [
+            LogType::Normal,
+            LogType::Info,
+            LogType::Warning,
+            LogType::Critical,
+        ] {
+            query.insert(
+                log_type.to_string(),
+                log_types.contains_type(&log_type).to_string(),
+            );
         }
 
         let log = self
@@ -113,4 +147,459 @@ impl super::Api {
 
         Ok(log)
     }
+
+    /// Tail the main log
+    ///
+    /// Polls [`Api::log`](super::Api::log) every `params.interval` and yields
+    /// every new message exactly once. The `last_known_id` is advanced
+    /// automatically, and a restart of qBittorrent (which resets the message
+    /// ids) is detected and the new log is streamed from the start.
+    ///
+    /// When `params.cursor_file` is set the cursor is loaded from and saved to
+    /// that file, so a restarted consumer resumes where it stopped. The cursor
+    /// is saved once a whole batch has been consumed, which means that after a
+    /// crash the last batch may be delivered a second time.
+    ///
+    /// Request errors are yielded and the stream keeps polling, waiting
+    /// `params.interval` before the next attempt. An invalid
+    /// `filter.message` pattern is yielded as the only item.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - Filter parameter object. `last_known_id` is ignored, use
+    ///   `params.cursor` instead. (default: all messages)
+    /// * `params` - Stream parameter object
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use futures_util::StreamExt;
+    /// use qbit::{Api, Credentials};
+    /// use qbit::parameters::LogStreamParamsBuilder;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let credentials = Credentials::new("username", "password");
+    ///     let client = Api::new_login("url", credentials)
+    ///         .await
+    ///         .unwrap();
+    ///
+    ///     let params = LogStreamParamsBuilder::default()
+    ///         .cursor_file("qbit-log.cursor")
+    ///         .build()
+    ///         .unwrap();
+    ///     let mut stream = Box::pin(client.log_stream(None, params));
+    ///
+    ///     while let Some(item) = stream.next().await {
+    ///         println!("{:?}", item);
+    ///     }
+    /// }
+    /// ```
+    pub fn log_stream(
+        &self,
+        filter: Option<LogFilter>,
+        params: LogStreamParams,
+    ) -> impl Stream<Item = Result<LogItem, Error>> + '_ {
+        let filter = filter.unwrap_or_default();
+        let matcher = match LogMatcher::new(&filter) {
+            Ok(matcher) => matcher,
+            Err(e) => return stream::once(async { Err(e) }).left_stream(),
+        };
+        let log_types = filter.log_types;
+
+        self.tail(
+            params,
+            move |api, last_known_id| api.raw_log(Some(last_known_id), log_types),
+            move |item| matcher.matches(item),
+        )
+        .right_stream()
+    }
+
+    /// Tail the peer log
+    ///
+    /// Same as [`Api::log_stream`](super::Api::log_stream), but for the peer
+    /// log.
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - Stream parameter object
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use futures_util::StreamExt;
+    /// use qbit::{Api, Credentials};
+    /// use qbit::parameters::LogStreamParams;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let credentials = Credentials::new("username", "password");
+    ///     let client = Api::new_login("url", credentials)
+    ///         .await
+    ///         .unwrap();
+    ///
+    ///     let mut stream = Box::pin(client.peer_log_stream(LogStreamParams::default()));
+    ///
+    ///     while let Some(item) = stream.next().await {
+    ///         println!("{:?}", item);
+    ///     }
+    /// }
+    /// ```
+    pub fn peer_log_stream(
+        &self,
+        params: LogStreamParams,
+    ) -> impl Stream<Item = Result<LogPeers, Error>> + '_ {
+        self.tail(
+            params,
+            |api, last_known_id| api.peer_log(Some(last_known_id)),
+            |_| true,
+        )
+    }
+
+    /// Shared polling loop of the log streams.
+    ///
+    /// The cursor advances over every fetched message, but only the ones
+    /// accepted by `keep` are yielded.
+    fn tail<'a, T, F, Fut, K>(
+        &'a self,
+        params: LogStreamParams,
+        fetch: F,
+        keep: K,
+    ) -> impl Stream<Item = Result<T, Error>> + 'a
+    where
+        T: LogEntry + 'a,
+        F: Fn(&'a Self, i64) -> Fut + 'a,
+        Fut: Future<Output = Result<Vec<T>, Error>> + 'a,
+        K: Fn(&T) -> bool + 'a,
+    {
+        let state = TailState {
+            cursor: params.cursor.clone(),
+            pending: VecDeque::new(),
+            dirty: false,
+            started: false,
+            backoff: false,
+            params,
+        };
+
+        let fetch = Arc::new(fetch);
+        let keep = Arc::new(keep);
+
+        stream::unfold(state, move |mut state| {
+            let fetch = fetch.clone();
+            let keep = keep.clone();
+            async move {
+                if !state.started {
+                    state.started = true;
+                    if let Err(e) = state.load().await {
+                        return Some((Err(e), state));
+                    }
+                }
+
+                let mut sleep = std::mem::take(&mut state.backoff);
+                loop {
+                    if let Some(item) = state.pending.pop_front() {
+                        state.advance(&item);
+                        if keep(&item) {
+                            return Some((Ok(item), state));
+                        }
+                        continue;
+                    }
+
+                    if let Err(e) = state.save().await {
+                        state.backoff = true;
+                        return Some((Err(e), state));
+                    }
+
+                    if sleep {
+                        tokio::time::sleep(state.params.interval).await;
+                    }
+                    sleep = true;
+
+                    let cursor = state.cursor.clone().unwrap_or_default();
+                    // Ask for the last seen message as well, so we can check
+                    // it is still the same one.
+                    let anchor = if cursor.last_known_id < 0 {
+                        -1
+                    } else {
+                        cursor.last_known_id - 1
+                    };
+
+                    match fetch(self, anchor).await {
+                        Ok(items) => match reconcile(&cursor, items) {
+                            Some(items) => state.pending = items.into(),
+                            None => {
+                                // The log was reset, start again from the top.
+                                state.cursor = None;
+                                state.dirty = true;
+                                sleep = false;
+                            }
+                        },
+                        Err(e) => {
+                            state.backoff = true;
+                            return Some((Err(e), state));
+                        }
+                    }
+                }
+            }
+        })
+    }
+}
+
+/// Client side part of a [`LogFilter`].
+struct LogMatcher {
+    since: Option<i64>,
+    until: Option<i64>,
+    message: Option<Regex>,
+}
+
+impl LogMatcher {
+    fn new(filter: &LogFilter) -> Result<Self, Error> {
+        let message = filter
+            .message
+            .as_deref()
+            .map(Regex::new)
+            .transpose()
+            .map_err(|e| Error::InvalidRequest(format!("Invalid log message pattern: {}", e)))?;
+
+        Ok(Self {
+            since: filter.since,
+            until: filter.until,
+            message,
+        })
+    }
+
+    fn matches(&self, item: &LogItem) -> bool {
+        self.since.is_none_or(|since| item.timestamp >= since)
+            && self.until.is_none_or(|until| item.timestamp <= until)
+            && self
+                .message
+                .as_ref()
+                .is_none_or(|message| message.is_match(&item.message))
+    }
+}
+
+/// Common fields of the main and peer log messages.
+trait LogEntry {
+    fn id(&self) -> i64;
+    fn timestamp(&self) -> i64;
+}
+
+impl LogEntry for LogItem {
+    fn id(&self) -> i64 {
+        self.id
+    }
+
+    fn timestamp(&self) -> i64 {
+        self.timestamp
+    }
+}
+
+impl LogEntry for LogPeers {
+    fn id(&self) -> i64 {
+        self.id
+    }
+
+    fn timestamp(&self) -> i64 {
+        self.timestamp
+    }
+}
+
+struct TailState<T> {
+    params: LogStreamParams,
+    cursor: Option<LogCursor>,
+    pending: VecDeque<T>,
+    dirty: bool,
+    started: bool,
+    /// True after an error, so the next poll waits for the interval first
+    backoff: bool,
+}
+
+impl<T: LogEntry> TailState<T> {
+    fn advance(&mut self, item: &T) {
+        self.cursor = Some(LogCursor {
+            last_known_id: item.id(),
+            timestamp: item.timestamp(),
+        });
+        self.dirty = true;
+    }
+
+    fn cursor_file(&self) -> Option<&PathBuf> {
+        self.params.cursor_file.as_ref()
+    }
+
+    async fn load(&mut self) -> Result<(), Error> {
+        let Some(path) = self.cursor_file() else {
+            return Ok(());
+        };
+
+        match tokio::fs::read(path).await {
+            Ok(data) => {
+                self.cursor = Some(serde_json::from_slice(&data)?);
+                Ok(())
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn save(&mut self) -> Result<(), Error> {
+        if !self.dirty {
+            return Ok(());
+        }
+        if let Some(path) = self.cursor_file() {
+            let data = serde_json::to_vec(&self.cursor.clone().unwrap_or_default())?;
+            tokio::fs::write(path, data).await?;
+        }
+        self.dirty = false;
+
+        Ok(())
+    }
+}
+
+/// Drops the already seen messages from a batch fetched with
+/// `last_known_id = cursor.last_known_id - 1`.
+///
+/// Returns `None` if the last seen message is not the first one of the batch
+/// anymore, meaning the server restarted (or dropped old messages) and the
+/// log has to be read from the start.
+fn reconcile<T: LogEntry>(cursor: &LogCursor, items: Vec<T>) -> Option<Vec<T>> {
+    if cursor.last_known_id < 0 {
+        return Some(items);
+    }
+
+    let mut items = items.into_iter();
+    match items.next() {
+        Some(first)
+            if first.id() == cursor.last_known_id && first.timestamp() == cursor.timestamp =>
+        {
+            Some(items.filter(|i| i.id() > cursor.last_known_id).collect())
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(timestamp: i64, message: &str) -> LogItem {
+        LogItem {
+            message: message.to_string(),
+            timestamp,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn log_types_from_list() {
+        let types = LogTypes::from(vec![LogType::Critical, LogType::Warning]);
+
+        assert_eq!(types, LogTypes::CRITICAL | LogTypes::WARNING);
+        assert!(types.contains_type(&LogType::Critical));
+        assert!(!types.contains_type(&LogType::Normal));
+    }
+
+    #[test]
+    fn log_types_default_to_all() {
+        assert_eq!(LogFilter::default().log_types, LogTypes::all());
+    }
+
+    #[test]
+    fn matcher_filters_time_range() {
+        let filter = LogFilter {
+            since: Some(10),
+            until: Some(20),
+            ..Default::default()
+        };
+        let matcher = LogMatcher::new(&filter).unwrap();
+
+        assert!(!matcher.matches(&message(9, "")));
+        assert!(matcher.matches(&message(10, "")));
+        assert!(matcher.matches(&message(20, "")));
+        assert!(!matcher.matches(&message(21, "")));
+    }
+
+    #[test]
+    fn matcher_filters_message() {
+        let filter = LogFilter {
+            message: Some("^Tracker .* error$".to_string()),
+            ..Default::default()
+        };
+        let matcher = LogMatcher::new(&filter).unwrap();
+
+        assert!(matcher.matches(&message(0, "Tracker udp://a error")));
+        assert!(!matcher.matches(&message(0, "Added torrent")));
+    }
+
+    #[test]
+    fn matcher_rejects_invalid_pattern() {
+        let filter = LogFilter {
+            message: Some("(".to_string()),
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            LogMatcher::new(&filter),
+            Err(Error::InvalidRequest(_))
+        ));
+    }
+
+    fn item(id: i64, timestamp: i64) -> LogItem {
+        LogItem {
+            id,
+            timestamp,
+            ..Default::default()
+        }
+    }
+
+    fn cursor(last_known_id: i64, timestamp: i64) -> LogCursor {
+        LogCursor {
+            last_known_id,
+            timestamp,
+        }
+    }
+
+    #[test]
+    fn reconcile_without_cursor_keeps_everything() {
+        let items = vec![item(0, 10), item(1, 11)];
+        let result = reconcile(&LogCursor::default(), items.clone());
+
+        assert_eq!(result, Some(items));
+    }
+
+    #[test]
+    fn reconcile_drops_anchor() {
+        let items = vec![item(4, 10), item(5, 11), item(6, 12)];
+        let result = reconcile(&cursor(4, 10), items);
+
+        assert_eq!(result, Some(vec![item(5, 11), item(6, 12)]));
+    }
+
+    #[test]
+    fn reconcile_nothing_new() {
+        let result = reconcile(&cursor(4, 10), vec![item(4, 10)]);
+
+        assert_eq!(result, Some(vec![]));
+    }
+
+    #[test]
+    fn reconcile_detects_restart_with_fewer_messages() {
+        let result = reconcile::<LogItem>(&cursor(4, 10), vec![]);
+
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn reconcile_detects_restart_with_same_id() {
+        let result = reconcile(&cursor(4, 10), vec![item(4, 50), item(5, 51)]);
+
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn reconcile_detects_dropped_anchor() {
+        let result = reconcile(&cursor(4, 10), vec![item(9, 50)]);
+
+        assert_eq!(result, None);
+    }
 }
@@ -6,10 +6,11 @@ use reqwest::{
     header::{self, HeaderMap},
 };
 
-use crate::{LoginState, error::Error};
+use crate::{LoginState, error::Error, parameters::BulkOptions};
 
 mod application;
 mod authentication;
+mod bulk;
 mod creator;
 mod log;
 mod rss;
@@ -24,6 +25,9 @@ pub struct Api {
     http_client: ReqwestClient,
     base_url: tokio::sync::RwLock<Url>,
     state: tokio::sync::RwLock<LoginState>,
+    bulk_options: BulkOptions,
+    /// Major application version, fetched once by `major_version`
+    major_version: tokio::sync::RwLock<Option<u32>>,
 }
 
 impl Api {
@@ -33,6 +37,8 @@ impl Api {
             http_client: ReqwestClient::new(),
             base_url: tokio::sync::RwLock::new(Url::parse(&url.into())?),
             state: tokio::sync::RwLock::new(LoginState::Unknown),
+            bulk_options: BulkOptions::default(),
+            major_version: tokio::sync::RwLock::new(None),
         })
     }
 
@@ -1,6 +1,6 @@
 use crate::{
     error::Error,
-    models::{MainData, PeersData},
+    models::{InfoHash, MainData, PeersData},
 };
 
 impl super::Api {
@@ -67,6 +67,7 @@ impl super::Api {
     ///
     /// ```no_run
     /// use qbit::{Api, Credentials};
+    /// use qbit::models::InfoHash;
     ///
     /// #[tokio::main]
     /// async fn main() {
@@ -75,12 +76,13 @@ impl super::Api {
     ///         .await
     ///         .unwrap();
     ///
-    ///     let data = client.peers_data("hash", None).await.unwrap();
+    ///     let hash = InfoHash::new("8c212779b4abde7c6bc608063a0d008b7e40ce32").unwrap();
+    ///     let data = client.peers_data(&hash, None).await.unwrap();
     ///
     ///     println!("{:#?}", data);
     /// }
     /// ```
-    pub async fn peers_data(&self, hash: &str, rid: Option<i64>) -> Result<PeersData, Error> {
+    pub async fn peers_data(&self, hash: &InfoHash, rid: Option<i64>) -> Result<PeersData, Error> {
         let mut query = vec![];
         query.push(("hash", hash.to_string()));
         if let Some(rid) = rid {
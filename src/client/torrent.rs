@@ -1,13 +1,24 @@
-use std::collections::HashMap;
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
 
-use reqwest::multipart;
+use bytes::Bytes;
+use reqwest::{StatusCode, multipart};
 
 use crate::{
-    error::Error,
+    error::{CategoryMoveError, Error, RenameError},
+    metainfo::{MagnetLink, PieceVerifier, VerifyReport},
     models::{
-        FilePriority, PiecesState, Torrent, TorrentContent, TorrentProperties, Tracker, WebSeed,
+        AddTorrentOutcome, AddTorrentStatus, Category, CategoryDownloadPath, CategoryStep,
+        CategoryTree, FilePriority, FileSelectionPlan, FileStream, FileTree, FileTreeNode,
+        InfoHash, PieceMap, PiecesState, RenamePlan, SeedingTimeLimit, ShareLimit, SpeedLimit,
+        TagUsage, Torrent, TorrentContent, TorrentProperties, Tracker, WebSeed,
+    },
+    parameters::{
+        AddTorrent, BatchRename, CategoryOptions, FileSelection, MoveParams, TorrentListParams,
+        TorrentSelector, TorrentState, VerifyParams,
     },
-    parameters::{AddTorrent, AddTorrentType, TorrentListParams},
 };
 
 impl super::Api {
@@ -67,7 +78,7 @@ impl super::Api {
             query.push(("offset", offset.to_string()));
         }
         if let Some(hashes) = params.hashes {
-            query.push(("hashes", hashes.join("|")));
+            query.push(("hashes", InfoHash::join(&hashes)));
         }
 
         let torrents = self
@@ -83,6 +94,40 @@ impl super::Api {
         Ok(torrents)
     }
 
+    /// Get the number of torrents
+    ///
+    /// Requires qBittorrent 5.0 or newer.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use qbit::{Api, Credentials};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let credentials = Credentials::new("username", "password");
+    ///     let client = Api::new_login("url", credentials)
+    ///         .await
+    ///         .unwrap();
+    ///
+    ///     let count = client.torrents_count().await.unwrap();
+    ///
+    ///     println!("{} torrents", count);
+    /// }
+    /// ```
+    pub async fn torrents_count(&self) -> Result<i64, Error> {
+        let count = self
+            ._get("torrents/count")
+            .await?
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<i64>()
+            .await?;
+
+        Ok(count)
+    }
+
     /// Gets generic data and statistics about a torrent
     ///
     /// [official documentation](https://github.com/qbittorrent/qBittorrent/wiki/WebUI-API-(qBittorrent-5.0)#get-torrent-generic-properties)
@@ -95,6 +140,7 @@ impl super::Api {
     ///
     /// ```no_run
     /// use qbit::{Api, Credentials};
+    /// use qbit::models::InfoHash;
     ///
     /// #[tokio::main]
     /// async fn main() {
@@ -103,13 +149,14 @@ impl super::Api {
     ///         .await
     ///         .unwrap();
     ///
-    ///     let torrent = client.torrent("hash").await.unwrap();
+    ///     let hash = InfoHash::new("8c212779b4abde7c6bc608063a0d008b7e40ce32").unwrap();
+    ///     let torrent = client.torrent(&hash).await.unwrap();
     ///
     ///     println!("{:?}", torrent);
     /// }
     /// ```
-    pub async fn torrent(&self, hash: &str) -> Result<TorrentProperties, Error> {
-        let query = vec![("hash", hash)];
+    pub async fn torrent(&self, hash: &InfoHash) -> Result<TorrentProperties, Error> {
+        let query = vec![("hash", hash.as_str())];
 
         let torrent = self
             ._get("torrents/properties")
@@ -138,6 +185,7 @@ impl super::Api {
     ///
     /// ```no_run
     /// use qbit::{Api, Credentials};
+    /// use qbit::models::InfoHash;
     ///
     /// #[tokio::main]
     /// async fn main() {
@@ -146,15 +194,16 @@ impl super::Api {
     ///         .await
     ///         .unwrap();
     ///
-    ///     let trackers = client.trackers("hash").await.unwrap();
+    ///     let hash = InfoHash::new("8c212779b4abde7c6bc608063a0d008b7e40ce32").unwrap();
+    ///     let trackers = client.trackers(&hash).await.unwrap();
     ///
     ///     for tracker in trackers {
     ///         println!("{:?}", tracker);
     ///     }
     /// }
     /// ```
-    pub async fn trackers(&self, hash: &str) -> Result<Vec<Tracker>, Error> {
-        let query = vec![("hash", hash)];
+    pub async fn trackers(&self, hash: &InfoHash) -> Result<Vec<Tracker>, Error> {
+        let query = vec![("hash", hash.as_str())];
 
         let trackers = self
             ._get("torrents/trackers")
@@ -183,6 +232,7 @@ impl super::Api {
     ///
     /// ```no_run
     /// use qbit::{Api, Credentials};
+    /// use qbit::models::InfoHash;
     ///
     /// #[tokio::main]
     /// async fn main() {
@@ -191,15 +241,16 @@ impl super::Api {
     ///         .await
     ///         .unwrap();
     ///
-    ///     let webseeds = client.webseeds("hash").await.unwrap();
+    ///     let hash = InfoHash::new("8c212779b4abde7c6bc608063a0d008b7e40ce32").unwrap();
+    ///     let webseeds = client.webseeds(&hash).await.unwrap();
     ///
     ///     for webseed in webseeds {
     ///         println!("{:?}", webseed);
     ///     }
     /// }
     /// ```
-    pub async fn webseeds(&self, hash: &str) -> Result<Vec<WebSeed>, Error> {
-        let query = vec![("hash", hash)];
+    pub async fn webseeds(&self, hash: &InfoHash) -> Result<Vec<WebSeed>, Error> {
+        let query = vec![("hash", hash.as_str())];
 
         let webseeds = self
             ._get("torrents/webseeds")
@@ -214,6 +265,190 @@ impl super::Api {
         Ok(webseeds)
     }
 
+    /// Add torrent web seeds
+    ///
+    /// Requires qBittorrent 5.0 or newer.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash` - The hash of the torrent you want to add the web seeds to.
+    /// * `urls` - The web seed URLs to add.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use qbit::{Api, Credentials};
+    /// use qbit::models::InfoHash;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let credentials = Credentials::new("username", "password");
+    ///     let client = Api::new_login("url", credentials)
+    ///         .await
+    ///         .unwrap();
+    ///
+    ///     let hash = InfoHash::new("8c212779b4abde7c6bc608063a0d008b7e40ce32").unwrap();
+    ///     let urls = vec!["http://example.com/files/"];
+    ///     let result = client.add_webseeds(&hash, urls).await;
+    ///
+    ///     assert!(result.is_ok());
+    /// }
+    /// ```
+    pub async fn add_webseeds(&self, hash: &InfoHash, urls: Vec<&str>) -> Result<(), Error> {
+        let form = multipart::Form::new()
+            .text("hash", hash.to_string())
+            .text("urls", urls.join("|"));
+
+        self._post("torrents/addWebSeeds")
+            .await?
+            .multipart(form)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+
+    /// Edit a torrent web seed
+    ///
+    /// Requires qBittorrent 5.0 or newer.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash` - The hash of the torrent the web seed belongs to.
+    /// * `orig_url` - The web seed URL to replace.
+    /// * `new_url` - The new web seed URL.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use qbit::{Api, Credentials};
+    /// use qbit::models::InfoHash;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let credentials = Credentials::new("username", "password");
+    ///     let client = Api::new_login("url", credentials)
+    ///         .await
+    ///         .unwrap();
+    ///
+    ///     let hash = InfoHash::new("8c212779b4abde7c6bc608063a0d008b7e40ce32").unwrap();
+    ///     let result = client
+    ///         .edit_webseed(&hash, "http://example.com/old/", "http://example.com/new/")
+    ///         .await;
+    ///
+    ///     assert!(result.is_ok());
+    /// }
+    /// ```
+    pub async fn edit_webseed(
+        &self,
+        hash: &InfoHash,
+        orig_url: &str,
+        new_url: &str,
+    ) -> Result<(), Error> {
+        let form = multipart::Form::new()
+            .text("hash", hash.to_string())
+            .text("origUrl", orig_url.to_string())
+            .text("newUrl", new_url.to_string());
+
+        self._post("torrents/editWebSeed")
+            .await?
+            .multipart(form)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+
+    /// Remove torrent web seeds
+    ///
+    /// Requires qBittorrent 5.0 or newer.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash` - The hash of the torrent you want to remove the web seeds from.
+    /// * `urls` - The web seed URLs to remove.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use qbit::{Api, Credentials};
+    /// use qbit::models::InfoHash;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let credentials = Credentials::new("username", "password");
+    ///     let client = Api::new_login("url", credentials)
+    ///         .await
+    ///         .unwrap();
+    ///
+    ///     let hash = InfoHash::new("8c212779b4abde7c6bc608063a0d008b7e40ce32").unwrap();
+    ///     let urls = vec!["http://example.com/files/"];
+    ///     let result = client.remove_webseeds(&hash, urls).await;
+    ///
+    ///     assert!(result.is_ok());
+    /// }
+    /// ```
+    pub async fn remove_webseeds(&self, hash: &InfoHash, urls: Vec<&str>) -> Result<(), Error> {
+        let form = multipart::Form::new()
+            .text("hash", hash.to_string())
+            .text("urls", urls.join("|"));
+
+        self._post("torrents/removeWebSeeds")
+            .await?
+            .multipart(form)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+
+    /// Export a torrent
+    ///
+    /// Downloads the `.torrent` file of a torrent, for example to back up its
+    /// metainfo. Requires qBittorrent 4.5 or newer.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash` - The hash of the torrent you want to export.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use qbit::{Api, Credentials};
+    /// use qbit::models::InfoHash;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let credentials = Credentials::new("username", "password");
+    ///     let client = Api::new_login("url", credentials)
+    ///         .await
+    ///         .unwrap();
+    ///
+    ///     let hash = InfoHash::new("8c212779b4abde7c6bc608063a0d008b7e40ce32").unwrap();
+    ///     let data = client.export(&hash).await.unwrap();
+    ///
+    ///     std::fs::write("backup.torrent", data).unwrap();
+    /// }
+    /// ```
+    pub async fn export(&self, hash: &InfoHash) -> Result<Bytes, Error> {
+        let query = vec![("hash", hash.as_str())];
+
+        let data = self
+            ._get("torrents/export")
+            .await?
+            .query(&query)
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+
+        Ok(data)
+    }
+
     /// Get torrent contents
     ///
     /// Makes a list of all files from the torrent.
@@ -230,6 +465,7 @@ impl super::Api {
     ///
     /// ```no_run
     /// use qbit::{Api, Credentials};
+    /// use qbit::models::InfoHash;
     ///
     /// #[tokio::main]
     /// async fn main() {
@@ -238,7 +474,8 @@ impl super::Api {
     ///         .await
     ///         .unwrap();
     ///
-    ///     let files = client.files("hash", None).await.unwrap();
+    ///     let hash = InfoHash::new("8c212779b4abde7c6bc608063a0d008b7e40ce32").unwrap();
+    ///     let files = client.files(&hash, None).await.unwrap();
     ///
     ///     for file in files {
     ///         println!("{:?}", file);
@@ -247,7 +484,7 @@ impl super::Api {
     /// ```
     pub async fn files(
         &self,
-        hash: &str,
+        hash: &InfoHash,
         indexes: Option<Vec<i64>>,
     ) -> Result<Vec<TorrentContent>, Error> {
         let mut query = vec![];
@@ -290,6 +527,7 @@ impl super::Api {
     ///
     /// ```no_run
     /// use qbit::{Api, Credentials};
+    /// use qbit::models::InfoHash;
     ///
     /// #[tokio::main]
     /// async fn main() {
@@ -298,15 +536,16 @@ impl super::Api {
     ///         .await
     ///         .unwrap();
     ///
-    ///     let states = client.pieces_states("hash").await.unwrap();
+    ///     let hash = InfoHash::new("8c212779b4abde7c6bc608063a0d008b7e40ce32").unwrap();
+    ///     let states = client.pieces_states(&hash).await.unwrap();
     ///
     ///     for state in states {
     ///         println!("{:?}", state);
     ///     }
     /// }
     /// ```
-    pub async fn pieces_states(&self, hash: &str) -> Result<Vec<PiecesState>, Error> {
-        let query = vec![("hash", hash)];
+    pub async fn pieces_states(&self, hash: &InfoHash) -> Result<Vec<PiecesState>, Error> {
+        let query = vec![("hash", hash.as_str())];
 
         let pieces = self
             ._get("torrents/pieceStates")
@@ -321,6 +560,43 @@ impl super::Api {
         Ok(pieces)
     }
 
+    /// Get torrent pieces' states as a [`PieceMap`]
+    ///
+    /// Same as [`Api::pieces_states`](crate::Api::pieces_states), in a compact
+    /// form with counts, ranges and per-file completion.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash` - The hash of the torrent you want to get the piece states of.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use qbit::{Api, Credentials};
+    /// use qbit::models::InfoHash;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let credentials = Credentials::new("username", "password");
+    ///     let client = Api::new_login("url", credentials)
+    ///         .await
+    ///         .unwrap();
+    ///
+    ///     let hash = InfoHash::new("8c212779b4abde7c6bc608063a0d008b7e40ce32").unwrap();
+    ///     let map = client.piece_map(&hash).await.unwrap();
+    ///
+    ///     let bar: String = map
+    ///         .buckets(40)
+    ///         .iter()
+    ///         .map(|bucket| if bucket.progress() == 1.0 { '#' } else { '.' })
+    ///         .collect();
+    ///     println!("[{}] {:.1}%", bar, map.counts().progress() * 100.0);
+    /// }
+    /// ```
+    pub async fn piece_map(&self, hash: &InfoHash) -> Result<PieceMap, Error> {
+        Ok(PieceMap::from(self.pieces_states(hash).await?))
+    }
+
     /// Get torrent pieces' hashes
     ///
     /// Hash of every piece of the torrent.
@@ -335,6 +611,7 @@ impl super::Api {
     ///
     /// ```no_run
     /// use qbit::{Api, Credentials};
+    /// use qbit::models::InfoHash;
     ///
     /// #[tokio::main]
     /// async fn main() {
@@ -343,15 +620,16 @@ impl super::Api {
     ///         .await
     ///         .unwrap();
     ///
-    ///     let hashes = client.pieces_hashes("hash").await.unwrap();
+    ///     let hash = InfoHash::new("8c212779b4abde7c6bc608063a0d008b7e40ce32").unwrap();
+    ///     let hashes = client.pieces_hashes(&hash).await.unwrap();
     ///
     ///     for hash in hashes {
     ///         println!("{}", hash);
     ///     }
     /// }
     /// ```
-    pub async fn pieces_hashes(&self, hash: &str) -> Result<Vec<String>, Error> {
-        let query = vec![("hash", hash)];
+    pub async fn pieces_hashes(&self, hash: &InfoHash) -> Result<Vec<String>, Error> {
+        let query = vec![("hash", hash.as_str())];
 
         let pieces = self
             ._get("torrents/pieceHashes")
@@ -372,12 +650,14 @@ impl super::Api {
     ///
     /// # Arguments
     ///
-    /// * `hashes` - Hashes list of torrents to stop.
+    /// * `selector` - The torrents to stop.
     ///
     /// # Example
     ///
+    /// Stop every torrent in the `linux` category
     /// ```no_run
     /// use qbit::{Api, Credentials};
+    /// use qbit::parameters::{TorrentListParamsBuilder, TorrentSelector};
     ///
     /// #[tokio::main]
     /// async fn main() {
@@ -386,22 +666,18 @@ impl super::Api {
     ///         .await
     ///         .unwrap();
     ///
-    ///     let result = client.stop(vec!["Hash1", "Hash2"]).await;
+    ///     let params = TorrentListParamsBuilder::default()
+    ///         .category("linux")
+    ///         .build()
+    ///         .unwrap();
+    ///     let result = client.stop(TorrentSelector::Filter(params)).await;
     ///
     ///     assert!(result.is_ok());
     /// }
     /// ```
-    pub async fn stop(&self, hashes: Vec<&str>) -> Result<(), Error> {
-        let form = multipart::Form::new().text("hashes", hashes.join("|"));
-
-        self._post("torrents/stop")
-            .await?
-            .multipart(form)
-            .send()
-            .await?
-            .error_for_status()?;
-
-        Ok(())
+    pub async fn stop(&self, selector: impl Into<TorrentSelector>) -> Result<(), Error> {
+        self.post_selected("torrents/stop", selector.into(), |form| form)
+            .await
     }
 
     /// Resume torrents
@@ -410,12 +686,13 @@ impl super::Api {
     ///
     /// # Arguments
     ///
-    /// * `hashes` - Hashes list of torrents to start.
+    /// * `selector` - The torrents to start.
     ///
     /// # Example
     ///
     /// ```no_run
     /// use qbit::{Api, Credentials};
+    /// use qbit::models::InfoHash;
     ///
     /// #[tokio::main]
     /// async fn main() {
@@ -424,22 +701,18 @@ impl super::Api {
     ///         .await
     ///         .unwrap();
     ///
-    ///     let result = client.start(vec!["Hash1", "Hash2"]).await;
+    ///     let hashes = vec![
+    ///         InfoHash::new("8c212779b4abde7c6bc608063a0d008b7e40ce32").unwrap(),
+    ///         InfoHash::new("c9e15763f722f23e98a29decdfae341b98d53056").unwrap(),
+    ///     ];
+    ///     let result = client.start(hashes).await;
     ///
     ///     assert!(result.is_ok());
     /// }
     /// ```
-    pub async fn start(&self, hashes: Vec<&str>) -> Result<(), Error> {
-        let form = multipart::Form::new().text("hashes", hashes.join("|"));
-
-        self._post("torrents/start")
-            .await?
-            .multipart(form)
-            .send()
-            .await?
-            .error_for_status()?;
-
-        Ok(())
+    pub async fn start(&self, selector: impl Into<TorrentSelector>) -> Result<(), Error> {
+        self.post_selected("torrents/start", selector.into(), |form| form)
+            .await
     }
 
     /// Delete torrents
@@ -451,7 +724,7 @@ impl super::Api {
     ///
     /// # Arguments
     ///
-    /// * `hashes` - Hashes list of torrents to delete.
+    /// * `selector` - The torrents to delete.
     /// * `delete_files` - If set to `true`, the downloaded data will also be deleted,
     ///   otherwise has no effect.
     ///
@@ -459,6 +732,7 @@ impl super::Api {
     ///
     /// ```no_run
     /// use qbit::{Api, Credentials};
+    /// use qbit::models::InfoHash;
     ///
     /// #[tokio::main]
     /// async fn main() {
@@ -467,24 +741,24 @@ impl super::Api {
     ///         .await
     ///         .unwrap();
     ///
-    ///     let result = client.delete(vec!["Hash1", "Hash2"], false).await;
+    ///     let hashes = vec![
+    ///         InfoHash::new("8c212779b4abde7c6bc608063a0d008b7e40ce32").unwrap(),
+    ///         InfoHash::new("c9e15763f722f23e98a29decdfae341b98d53056").unwrap(),
+    ///     ];
+    ///     let result = client.delete(hashes, false).await;
     ///
     ///     assert!(result.is_ok());
     /// }
     /// ```
-    pub async fn delete(&self, hashes: Vec<&str>, delete_files: bool) -> Result<(), Error> {
-        let form = multipart::Form::new()
-            .text("hashes", hashes.join("|"))
-            .text("deleteFiles", delete_files.to_string());
-
-        self._post("torrents/delete")
-            .await?
-            .multipart(form)
-            .send()
-            .await?
-            .error_for_status()?;
-
-        Ok(())
+    pub async fn delete(
+        &self,
+        selector: impl Into<TorrentSelector>,
+        delete_files: bool,
+    ) -> Result<(), Error> {
+        self.post_selected("torrents/delete", selector.into(), |form| {
+            form.text("deleteFiles", delete_files.to_string())
+        })
+        .await
     }
 
     /// Recheck torrents
@@ -493,12 +767,13 @@ impl super::Api {
     ///
     /// # Arguments
     ///
-    /// * `hashes` - Hashes list of torrents to recheck.
+    /// * `selector` - The torrents to recheck.
     ///
     /// # Example
     ///
     /// ```no_run
     /// use qbit::{Api, Credentials};
+    /// use qbit::models::InfoHash;
     ///
     /// #[tokio::main]
     /// async fn main() {
@@ -507,22 +782,18 @@ impl super::Api {
     ///         .await
     ///         .unwrap();
     ///
-    ///     let result = client.recheck(vec!["Hash1", "Hash2"]).await;
+    ///     let hashes = vec![
+    ///         InfoHash::new("8c212779b4abde7c6bc608063a0d008b7e40ce32").unwrap(),
+    ///         InfoHash::new("c9e15763f722f23e98a29decdfae341b98d53056").unwrap(),
+    ///     ];
+    ///     let result = client.recheck(hashes).await;
     ///
     ///     assert!(result.is_ok());
     /// }
     /// ```
-    pub async fn recheck(&self, hashes: Vec<&str>) -> Result<(), Error> {
-        let form = multipart::Form::new().text("hashes", hashes.join("|"));
-
-        self._post("torrents/recheck")
-            .await?
-            .multipart(form)
-            .send()
-            .await?
-            .error_for_status()?;
-
-        Ok(())
+    pub async fn recheck(&self, selector: impl Into<TorrentSelector>) -> Result<(), Error> {
+        self.post_selected("torrents/recheck", selector.into(), |form| form)
+            .await
     }
 
     /// Reannounce torrents
@@ -531,12 +802,13 @@ impl super::Api {
     ///
     /// # Arguments
     ///
-    /// * `hashes` - Hashes list of torrents to reannounce.
+    /// * `selector` - The torrents to reannounce.
     ///
     /// # Example
     ///
     /// ```no_run
     /// use qbit::{Api, Credentials};
+    /// use qbit::models::InfoHash;
     ///
     /// #[tokio::main]
     /// async fn main() {
@@ -545,28 +817,41 @@ impl super::Api {
     ///         .await
     ///         .unwrap();
     ///
-    ///     let result = client.reannounce(vec!["Hash1", "Hash2"]).await;
+    ///     let hashes = vec![
+    ///         InfoHash::new("8c212779b4abde7c6bc608063a0d008b7e40ce32").unwrap(),
+    ///         InfoHash::new("c9e15763f722f23e98a29decdfae341b98d53056").unwrap(),
+    ///     ];
+    ///     let result = client.reannounce(hashes).await;
     ///
     ///     assert!(result.is_ok());
     /// }
     /// ```
-    pub async fn reannounce(&self, hashes: Vec<&str>) -> Result<(), Error> {
-        let form = multipart::Form::new().text("hashes", hashes.join("|"));
-
-        self._post("torrents/reannounce")
-            .await?
-            .multipart(form)
-            .send()
-            .await?
-            .error_for_status()?;
-
-        Ok(())
+    pub async fn reannounce(&self, selector: impl Into<TorrentSelector>) -> Result<(), Error> {
+        self.post_selected("torrents/reannounce", selector.into(), |form| form)
+            .await
     }
 
     /// Add new torrent
     ///
     /// [official documentation](https://github.com/qbittorrent/qBittorrent/wiki/WebUI-API-(qBittorrent-5.0)#add-new-torrent)
     ///
+    /// Returns an outcome for every file or link. The hash of a torrent is
+    /// computed locally from the `info` dictionary of a torrent file, or read
+    /// from the `xt` parameter of a magnet link.
+    ///
+    /// The server only reports whether at least one torrent was added. With
+    /// [`AddTorrent::confirm`] set, the torrent list is checked before and
+    /// after the request to tell added, duplicate and failed torrents apart.
+    /// As the server adds torrents in the background, the list is checked
+    /// until every torrent shows up or [`AddTorrent::confirm_timeout`] has
+    /// passed, before reporting the missing ones as failed.
+    ///
+    /// With [`AddTorrent::file_selection`] set, the selection is applied to
+    /// every new torrent with a known hash, waiting for the metadata of
+    /// magnet links. Duplicates are left as they are. The result is reported
+    /// in [`AddTorrentOutcome::file_selection`]; a failed selection does not
+    /// fail the whole call.
+    ///
     /// # Arguments
     ///
     /// * `params` - Torrent parameters
@@ -575,7 +860,7 @@ impl super::Api {
     ///
     /// ```no_run
     /// use qbit::{Api, Credentials};
-    /// use qbit::parameters::AddTorrent;
+    /// use qbit::parameters::AddTorrentBuilder;
     ///
     /// #[tokio::main]
     /// async fn main() {
@@ -584,84 +869,242 @@ impl super::Api {
     ///         .await
     ///         .unwrap();
     ///
-    ///     let params = AddTorrent::default();
-    ///     let result = client.add_torrent(params).await;
+    ///     let params = AddTorrentBuilder::default()
+    ///         .torrents(vec![String::from("magnet:?xt=urn:btih:8c212779b4abde7c6bc608063a0d008b7e40ce32")])
+    ///         .confirm(true)
+    ///         .build()
+    ///         .unwrap();
+    ///     let outcomes = client.add_torrent(params).await.unwrap();
     ///
-    ///     assert!(result.is_ok());
+    ///     for outcome in outcomes {
+    ///         println!("{}: {:?}", outcome.source, outcome.status);
+    ///     }
     /// }
     /// ```
-    pub async fn add_torrent(&self, params: AddTorrent) -> Result<(), Error> {
-        if params.torrents.is_empty() {
-            return Err(Error::InvalidRequest(
-                "Expected urls or torrents to not be empty!".to_string(),
-            ));
-        }
+    pub async fn add_torrent(&self, params: AddTorrent) -> Result<Vec<AddTorrentOutcome>, Error> {
+        params.validate()?;
 
+        let mut outcomes = Vec::new();
         let mut form = multipart::Form::new();
-        match params.torrents {
-            AddTorrentType::Links(items) => {
-                form = form.text("urls", items.join("\n"));
-            }
-            AddTorrentType::Files(torrent_files) => {
-                for file in torrent_files {
-                    let mut filename = file.filename;
-                    if !filename.ends_with(".torrent") {
-                        filename.insert_str(0, ".torrent");
-                    }
-
-                    form = form.part(
-                        "torrents",
-                        multipart::Part::bytes(file.data)
-                            .file_name(filename)
-                            .mime_str("application/x-bittorrent")?,
-                    );
+        let (files, links) = params.torrents.into_parts();
+
+        for file in files {
+            let filename = file.upload_name();
+
+            outcomes.push(AddTorrentOutcome {
+                source: filename.clone(),
+                hash: file.info_hash().ok(),
+                status: AddTorrentStatus::Submitted,
+                file_selection: None,
+            });
+
+            form = form.part(
+                "torrents",
+                multipart::Part::bytes(file.data)
+                    .file_name(filename)
+                    .mime_str("application/x-bittorrent")?,
+            );
+        }
+        if !links.is_empty() {
+            outcomes.extend(links.iter().map(|link| {
+                AddTorrentOutcome {
+                    source: link.clone(),
+                    hash: MagnetLink::parse(link)
+                        .ok()
+                        .map(|magnet| magnet.info_hash()),
+                    status: AddTorrentStatus::Submitted,
+                    file_selection: None,
                 }
-            }
-        };
+            }));
+            form = form.text("urls", links.join("\n"));
+        }
 
         form = form
             .text("skip_checking", params.skip_checking.to_string())
-            .text("paused", params.paused.to_string())
-            .text("autoTMM", params.auto_tmm.to_string())
             .text("sequentialDownload", params.sequential_download.to_string())
             .text("contentLayout", params.content_layout.to_string())
             .text(
                 "firstLastPiecePrio",
                 params.first_last_piece_prio.to_string(),
             );
+        if let Some(stopped) = params.stopped {
+            // qBittorrent 5 renamed `paused` to `stopped` and ignores the old name
+            let field = if self.major_version().await? >= 5 {
+                "stopped"
+            } else {
+                "paused"
+            };
+            form = form.text(field, stopped.to_string());
+        }
+        if let Some(auto_tmm) = params.auto_tmm {
+            form = form.text("autoTMM", auto_tmm.to_string());
+        }
+        if let Some(cookie) = params.cookie {
+            form = form.text("cookie", cookie);
+        }
         if let Some(savepath) = params.savepath {
             form = form.text("savepath", savepath);
         }
+        if let Some(download_path) = params.download_path {
+            form = form.text("downloadPath", download_path);
+        }
+        if let Some(use_download_path) = params.use_download_path {
+            form = form.text("useDownloadPath", use_download_path.to_string());
+        }
         if let Some(category) = params.category {
             form = form.text("category", category);
         }
         if let Some(tags) = params.tags {
             form = form.text("tags", tags.join(","));
         }
-        if let Some(rename) = params.rename {
-            form = form.text("rename", rename);
+        if let Some(stop_condition) = params.stop_condition {
+            form = form.text("stopCondition", stop_condition.to_string());
+        }
+        if let Some(add_to_top_of_queue) = params.add_to_top_of_queue {
+            form = form.text("addToTopOfQueue", add_to_top_of_queue.to_string());
+        }
+        if let Some(forced) = params.forced {
+            form = form.text("forced", forced.to_string());
+        }
+        if let Some(rename) = params.rename {
+            form = form.text("rename", rename);
         }
         if let Some(up_limit) = params.up_limit {
-            form = form.text("upLimit", up_limit.to_string());
+            form = form.text("upLimit", up_limit.to_raw().to_string());
         }
         if let Some(dl_limit) = params.dl_limit {
-            form = form.text("dlLimit", dl_limit.to_string());
+            form = form.text("dlLimit", dl_limit.to_raw().to_string());
         }
         if let Some(ratio_limit) = params.ratio_limit {
-            form = form.text("ratioLimit", ratio_limit.to_string());
+            form = form.text("ratioLimit", ratio_limit.to_raw().to_string());
         }
         if let Some(seeding_time_limit) = params.seeding_time_limit {
-            form = form.text("seedingTimeLimit", seeding_time_limit.to_string());
+            form = form.text("seedingTimeLimit", seeding_time_limit.to_raw().to_string());
+        }
+        if let Some(inactive_seeding_time_limit) = params.inactive_seeding_time_limit {
+            form = form.text(
+                "inactiveSeedingTimeLimit",
+                inactive_seeding_time_limit.to_raw().to_string(),
+            );
+        }
+        if let Some(share_limit_action) = params.share_limit_action {
+            form = form.text("shareLimitAction", share_limit_action.to_string());
+        }
+        if let Some(ssl_certificate) = params.ssl_certificate {
+            form = form.text("ssl_certificate", ssl_certificate);
+        }
+        if let Some(ssl_private_key) = params.ssl_private_key {
+            form = form.text("ssl_private_key", ssl_private_key);
+        }
+        if let Some(ssl_dh_params) = params.ssl_dh_params {
+            form = form.text("ssl_dh_params", ssl_dh_params);
         }
 
-        self._post("torrents/add")
+        let hashes: Vec<InfoHash> = outcomes
+            .iter()
+            .filter_map(|outcome| outcome.hash.clone())
+            .collect();
+        let existing = match params.confirm {
+            true => self.existing_hashes(&hashes).await?,
+            false => Vec::new(),
+        };
+
+        let response = self
+            ._post("torrents/add")
             .await?
             .multipart(form)
             .send()
             .await?
-            .error_for_status()?;
+            .error_for_status()?
+            .text()
+            .await?;
 
-        Ok(())
+        if params.confirm {
+            let present = match response.trim() {
+                "Fails." => existing.clone(),
+                _ => {
+                    let timeout = params.confirm_timeout.unwrap_or(CONFIRM_TIMEOUT);
+                    self.wait_for_hashes(&hashes, timeout).await?
+                }
+            };
+
+            for outcome in &mut outcomes {
+                let Some(hash) = &outcome.hash else {
+                    continue;
+                };
+                outcome.status = if existing.contains(hash) {
+                    AddTorrentStatus::Duplicate
+                } else if present.contains(hash) {
+                    AddTorrentStatus::Added
+                } else {
+                    AddTorrentStatus::Failed
+                };
+            }
+        } else if response.trim() == "Fails." {
+            for outcome in &mut outcomes {
+                outcome.status = AddTorrentStatus::Failed;
+            }
+        }
+
+        if let Some(selection) = &params.file_selection {
+            for outcome in &mut outcomes {
+                let Some(hash) = outcome.hash.clone() else {
+                    continue;
+                };
+                if matches!(
+                    outcome.status,
+                    AddTorrentStatus::Submitted | AddTorrentStatus::Added
+                ) {
+                    outcome.file_selection = Some(
+                        self.select_files(&hash, selection)
+                            .await
+                            .map_err(|e| e.to_string()),
+                    );
+                }
+            }
+        }
+
+        Ok(outcomes)
+    }
+
+    /// The hashes of `hashes` that are in the torrent list, checked every
+    /// [`CONFIRM_INTERVAL`] until all of them are or `timeout` has passed.
+    async fn wait_for_hashes(
+        &self,
+        hashes: &[InfoHash],
+        timeout: Duration,
+    ) -> Result<Vec<InfoHash>, Error> {
+        let deadline = tokio::time::Instant::now() + timeout;
+
+        loop {
+            let present = self.existing_hashes(hashes).await?;
+            if hashes.iter().all(|hash| present.contains(hash))
+                || tokio::time::Instant::now() >= deadline
+            {
+                return Ok(present);
+            }
+
+            tokio::time::sleep(CONFIRM_INTERVAL).await;
+        }
+    }
+
+    /// The hashes of `hashes` that are in the torrent list.
+    async fn existing_hashes(&self, hashes: &[InfoHash]) -> Result<Vec<InfoHash>, Error> {
+        if hashes.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let params = TorrentListParams {
+            hashes: Some(hashes.to_vec()),
+            ..Default::default()
+        };
+
+        Ok(self
+            .torrents(Some(params))
+            .await?
+            .into_iter()
+            .map(|torrent| torrent.hash)
+            .collect())
     }
 
     /// Add trackers to torrent
@@ -677,6 +1120,7 @@ impl super::Api {
     ///
     /// ```no_run
     /// use qbit::{Api, Credentials};
+    /// use qbit::models::InfoHash;
     ///
     /// #[tokio::main]
     /// async fn main() {
@@ -686,12 +1130,17 @@ impl super::Api {
     ///         .unwrap();
     ///
     ///     let urls = vec!["url1", "url2"];
-    ///     let result = client.add_trackers_to_torrent("hash", urls).await;
+    ///     let hash = InfoHash::new("8c212779b4abde7c6bc608063a0d008b7e40ce32").unwrap();
+    ///     let result = client.add_trackers_to_torrent(&hash, urls).await;
     ///
     ///     assert!(result.is_ok());
     /// }
     /// ```
-    pub async fn add_trackers_to_torrent(&self, hash: &str, urls: Vec<&str>) -> Result<(), Error> {
+    pub async fn add_trackers_to_torrent(
+        &self,
+        hash: &InfoHash,
+        urls: Vec<&str>,
+    ) -> Result<(), Error> {
         let form = multipart::Form::new()
             .text("hash", hash.to_string())
             .text("urls", urls.join("%0A"));
@@ -722,6 +1171,7 @@ impl super::Api {
     ///
     /// ```no_run
     /// use qbit::{Api, Credentials};
+    /// use qbit::models::InfoHash;
     ///
     /// #[tokio::main]
     /// async fn main() {
@@ -730,7 +1180,8 @@ impl super::Api {
     ///         .await
     ///         .unwrap();
     ///
-    ///     let result = client.edit_tracker_for_torrent("hash", "old_url", "new_url")
+    ///     let hash = InfoHash::new("8c212779b4abde7c6bc608063a0d008b7e40ce32").unwrap();
+    ///     let result = client.edit_tracker_for_torrent(&hash, "old_url", "new_url")
     ///         .await;
     ///
     ///     assert!(result.is_ok());
@@ -738,7 +1189,7 @@ impl super::Api {
     /// ```
     pub async fn edit_tracker_for_torrent(
         &self,
-        hash: &str,
+        hash: &InfoHash,
         orig_url: &str,
         new_url: &str,
     ) -> Result<(), Error> {
@@ -770,6 +1221,7 @@ impl super::Api {
     ///
     /// ```no_run
     /// use qbit::{Api, Credentials};
+    /// use qbit::models::InfoHash;
     ///
     /// #[tokio::main]
     /// async fn main() {
@@ -779,14 +1231,15 @@ impl super::Api {
     ///         .unwrap();
     ///
     ///     let urls = vec!["url1", "url2"];
-    ///     let result = client.remove_trackers_from_torrent("hash", urls).await;
+    ///     let hash = InfoHash::new("8c212779b4abde7c6bc608063a0d008b7e40ce32").unwrap();
+    ///     let result = client.remove_trackers_from_torrent(&hash, urls).await;
     ///
     ///     assert!(result.is_ok());
     /// }
     /// ```
     pub async fn remove_trackers_from_torrent(
         &self,
-        hash: &str,
+        hash: &InfoHash,
         urls: Vec<&str>,
     ) -> Result<(), Error> {
         let form = multipart::Form::new()
@@ -809,13 +1262,14 @@ impl super::Api {
     ///
     /// # Arguments
     ///
-    /// * `hashes` - Torrent hash.
+    /// * `selector` - The torrents to add the peers to.
     /// * `peers` - The peer to add. Each peer is a colon-separated `host:port`.
     ///
     /// # Example
     ///
     /// ```no_run
     /// use qbit::{Api, Credentials};
+    /// use qbit::models::InfoHash;
     ///
     /// #[tokio::main]
     /// async fn main() {
@@ -824,26 +1278,25 @@ impl super::Api {
     ///         .await
     ///         .unwrap();
     ///
-    ///     let hashes = vec!["hash1", "hash2"];
+    ///     let hashes = vec![
+    ///         InfoHash::new("8c212779b4abde7c6bc608063a0d008b7e40ce32").unwrap(),
+    ///         InfoHash::new("c9e15763f722f23e98a29decdfae341b98d53056").unwrap(),
+    ///     ];
     ///     let peers = vec!["alice", "bob"];
     ///     let result = client.add_peers(hashes, peers).await;
     ///
     ///     assert!(result.is_ok());
     /// }
     /// ```
-    pub async fn add_peers(&self, hashes: Vec<&str>, peers: Vec<&str>) -> Result<(), Error> {
-        let form = multipart::Form::new()
-            .text("hashes", hashes.join("|"))
-            .text("peers", peers.join("|"));
-
-        self._post("torrents/addPeers")
-            .await?
-            .multipart(form)
-            .send()
-            .await?
-            .error_for_status()?;
-
-        Ok(())
+    pub async fn add_peers(
+        &self,
+        selector: impl Into<TorrentSelector>,
+        peers: Vec<&str>,
+    ) -> Result<(), Error> {
+        self.post_selected("torrents/addPeers", selector.into(), |form| {
+            form.text("peers", peers.join("|"))
+        })
+        .await
     }
 
     /// Increase torrent priority
@@ -852,13 +1305,13 @@ impl super::Api {
     ///
     /// # Arguments
     ///
-    /// * `hashes` - The hashes of the torrents you want to increase the priority of.
-    ///   If `None` all torrents are selected.
+    /// * `selector` - The torrents you want to increase the priority of.
     ///
     /// # Example
     ///
     /// ```no_run
     /// use qbit::{Api, Credentials};
+    /// use qbit::parameters::TorrentSelector;
     ///
     /// #[tokio::main]
     /// async fn main() {
@@ -867,22 +1320,17 @@ impl super::Api {
     ///         .await
     ///         .unwrap();
     ///
-    ///     let result = client.increase_priority(None).await;
+    ///     let result = client.increase_priority(TorrentSelector::All).await;
     ///
     ///     assert!(result.is_ok());
     /// }
     /// ```
-    pub async fn increase_priority(&self, hashes: Option<Vec<&str>>) -> Result<(), Error> {
-        let form = multipart::Form::new().text("hashes", hashes.unwrap_or(vec!["all"]).join("|"));
-
-        self._post("torrents/increasePrio")
-            .await?
-            .multipart(form)
-            .send()
-            .await?
-            .error_for_status()?;
-
-        Ok(())
+    pub async fn increase_priority(
+        &self,
+        selector: impl Into<TorrentSelector>,
+    ) -> Result<(), Error> {
+        self.post_selected("torrents/increasePrio", selector.into(), |form| form)
+            .await
     }
 
     /// Decrease torrent priority
@@ -891,13 +1339,13 @@ impl super::Api {
     ///
     /// # Arguments
     ///
-    /// * `hashes` - The hashes of the torrents you want to decrease the priority of.
-    ///   If `None` all torrents are selected.
+    /// * `selector` - The torrents you want to decrease the priority of.
     ///
     /// # Example
     ///
     /// ```no_run
     /// use qbit::{Api, Credentials};
+    /// use qbit::parameters::TorrentSelector;
     ///
     /// #[tokio::main]
     /// async fn main() {
@@ -906,22 +1354,17 @@ impl super::Api {
     ///         .await
     ///         .unwrap();
     ///
-    ///     let result = client.decrease_priority(None).await;
+    ///     let result = client.decrease_priority(TorrentSelector::All).await;
     ///
     ///     assert!(result.is_ok());
     /// }
     /// ```
-    pub async fn decrease_priority(&self, hashes: Option<Vec<&str>>) -> Result<(), Error> {
-        let form = multipart::Form::new().text("hashes", hashes.unwrap_or(vec!["all"]).join("|"));
-
-        self._post("torrents/decreasePrio")
-            .await?
-            .multipart(form)
-            .send()
-            .await?
-            .error_for_status()?;
-
-        Ok(())
+    pub async fn decrease_priority(
+        &self,
+        selector: impl Into<TorrentSelector>,
+    ) -> Result<(), Error> {
+        self.post_selected("torrents/decreasePrio", selector.into(), |form| form)
+            .await
     }
 
     /// Maximal torrent priority
@@ -930,13 +1373,13 @@ impl super::Api {
     ///
     /// # Arguments
     ///
-    /// * `hashes` - The hashes of the torrents you want to max the priority of.
-    ///   If `None` all torrents are selected.
+    /// * `selector` - The torrents you want to max the priority of.
     ///
     /// # Example
     ///
     /// ```no_run
     /// use qbit::{Api, Credentials};
+    /// use qbit::parameters::TorrentSelector;
     ///
     /// #[tokio::main]
     /// async fn main() {
@@ -945,22 +1388,14 @@ impl super::Api {
     ///         .await
     ///         .unwrap();
     ///
-    ///     let result = client.max_priority(None).await;
+    ///     let result = client.max_priority(TorrentSelector::All).await;
     ///
     ///     assert!(result.is_ok());
     /// }
     /// ```
-    pub async fn max_priority(&self, hashes: Option<Vec<&str>>) -> Result<(), Error> {
-        let form = multipart::Form::new().text("hashes", hashes.unwrap_or(vec!["all"]).join("|"));
-
-        self._post("torrents/topPrio")
-            .await?
-            .multipart(form)
-            .send()
-            .await?
-            .error_for_status()?;
-
-        Ok(())
+    pub async fn max_priority(&self, selector: impl Into<TorrentSelector>) -> Result<(), Error> {
+        self.post_selected("torrents/topPrio", selector.into(), |form| form)
+            .await
     }
 
     /// Minimal torrent priority
@@ -969,13 +1404,13 @@ impl super::Api {
     ///
     /// # Arguments
     ///
-    /// * `hashes` - The hashes of the torrents you want to min the priority of.
-    ///   If `None` all torrents are selected.
+    /// * `selector` - The torrents you want to min the priority of.
     ///
     /// # Example
     ///
     /// ```no_run
     /// use qbit::{Api, Credentials};
+    /// use qbit::parameters::TorrentSelector;
     ///
     /// #[tokio::main]
     /// async fn main() {
@@ -984,22 +1419,14 @@ impl super::Api {
     ///         .await
     ///         .unwrap();
     ///
-    ///     let result = client.min_priority(None).await;
+    ///     let result = client.min_priority(TorrentSelector::All).await;
     ///
     ///     assert!(result.is_ok());
     /// }
     /// ```
-    pub async fn min_priority(&self, hashes: Option<Vec<&str>>) -> Result<(), Error> {
-        let form = multipart::Form::new().text("hashes", hashes.unwrap_or(vec!["all"]).join("|"));
-
-        self._post("torrents/bottomPrio")
-            .await?
-            .multipart(form)
-            .send()
-            .await?
-            .error_for_status()?;
-
-        Ok(())
+    pub async fn min_priority(&self, selector: impl Into<TorrentSelector>) -> Result<(), Error> {
+        self.post_selected("torrents/bottomPrio", selector.into(), |form| form)
+            .await
     }
 
     /// Set file priority
@@ -1016,7 +1443,7 @@ impl super::Api {
     ///
     /// ```no_run
     /// use qbit::{Api, Credentials};
-    /// use qbit::models::FilePriority;
+    /// use qbit::models::{FilePriority, InfoHash};
     ///
     /// #[tokio::main]
     /// async fn main() {
@@ -1025,14 +1452,15 @@ impl super::Api {
     ///         .await
     ///         .unwrap();
     ///
-    ///     let result = client.set_file_priority("hash", vec![0,1,2], FilePriority::High).await;
+    ///     let hash = InfoHash::new("8c212779b4abde7c6bc608063a0d008b7e40ce32").unwrap();
+    ///     let result = client.set_file_priority(&hash, vec![0,1,2], FilePriority::High).await;
     ///
     ///     assert!(result.is_ok());
     /// }
     /// ```
     pub async fn set_file_priority(
         &self,
-        hash: &str,
+        hash: &InfoHash,
         file_ids: Vec<u64>,
         priority: FilePriority,
     ) -> Result<(), Error> {
@@ -1064,13 +1492,13 @@ impl super::Api {
     ///
     /// # Arguments
     ///
-    /// * `hashes` - The hashes of the torrents you want to get the download limit of.
-    ///   If `None` all torrents are selected.
+    /// * `selector` - The torrents you want to get the download limit of.
     ///
     /// # Example
     ///
     /// ```no_run
     /// use qbit::{Api, Credentials};
+    /// use qbit::parameters::TorrentSelector;
     ///
     /// #[tokio::main]
     /// async fn main() {
@@ -1079,7 +1507,7 @@ impl super::Api {
     ///         .await
     ///         .unwrap();
     ///
-    ///     let limits = client.download_limit(None).await.unwrap();
+    ///     let limits = client.download_limit(TorrentSelector::All).await.unwrap();
     ///
     ///     for limit in limits {
     ///         println!("{:?}", limit);
@@ -1088,21 +1516,27 @@ impl super::Api {
     /// ```
     pub async fn download_limit(
         &self,
-        hashes: Option<Vec<&str>>,
-    ) -> Result<HashMap<String, u64>, Error> {
-        let query = vec![("hashes", hashes.unwrap_or(vec!["all"]).join("|"))];
-
+        selector: impl Into<TorrentSelector>,
+    ) -> Result<HashMap<InfoHash, SpeedLimit>, Error> {
         let limites = self
-            ._get("torrents/downloadLimit")
-            .await?
-            .query(&query)
-            .send()
-            .await?
-            .error_for_status()?
-            .json::<HashMap<String, u64>>()
+            .for_each_chunk(selector.into(), |hashes| async move {
+                let query = vec![("hashes", hashes)];
+
+                let limites = self
+                    ._get("torrents/downloadLimit")
+                    .await?
+                    .query(&query)
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json::<HashMap<InfoHash, SpeedLimit>>()
+                    .await?;
+
+                Ok(limites)
+            })
             .await?;
 
-        Ok(limites)
+        Ok(limites.into_iter().flatten().collect())
     }
 
     /// Set torrent download limit
@@ -1111,14 +1545,16 @@ impl super::Api {
     ///
     /// # Arguments
     ///
-    /// * `hashes` - The hashes of the torrents you want to set the download limit of.
-    ///   If `None` all torrents are selected.
-    /// * `limit` - Download limit
+    /// * `selector` - The torrents you want to set the download limit of.
+    /// * `limit` - Download limit. A torrent without its own limit is still bound
+    ///   by the global limit.
     ///
     /// # Example
     ///
     /// ```no_run
     /// use qbit::{Api, Credentials};
+    /// use qbit::models::{BytesPerSecond, SpeedLimit};
+    /// use qbit::parameters::TorrentSelector;
     ///
     /// #[tokio::main]
     /// async fn main() {
@@ -1127,28 +1563,21 @@ impl super::Api {
     ///         .await
     ///         .unwrap();
     ///
-    ///     let result = client.set_download_limit(None, 10).await;
+    ///     let limit = SpeedLimit::Value(BytesPerSecond::from_kib(512));
+    ///     let result = client.set_download_limit(TorrentSelector::All, limit).await;
     ///
     ///     assert!(result.is_ok());
     /// }
     /// ```
     pub async fn set_download_limit(
         &self,
-        hashes: Option<Vec<&str>>,
-        limit: u64,
+        selector: impl Into<TorrentSelector>,
+        limit: SpeedLimit,
     ) -> Result<(), Error> {
-        let form = multipart::Form::new()
-            .text("hashes", hashes.unwrap_or(vec!["all"]).join("|"))
-            .text("limit", limit.to_string());
-
-        self._post("torrents/setDownloadLimit")
-            .await?
-            .multipart(form)
-            .send()
-            .await?
-            .error_for_status()?;
-
-        Ok(())
+        self.post_selected("torrents/setDownloadLimit", selector.into(), |form| {
+            form.text("limit", limit.to_raw().to_string())
+        })
+        .await
     }
 
     /// Set torrent share limit
@@ -1159,20 +1588,20 @@ impl super::Api {
     ///
     /// # Arguments
     ///
-    /// * `hashes` - The hashes of the torrents you want to set the share limit of.
-    ///   If `None` all torrents are selected.
-    /// * `ratio_limit` - The maximum seeding ratio for the torrent. `-2` means
-    ///   the global limit should be used, `-1` means no limit.
-    /// * `seeding_time_limit` - The maximum seeding time (minutes) for the torrent.
-    ///   `-2` means the global limit should be used, `-1` means no limit.
-    /// * `inactive_seeding_time_limit` - The maximum amount of time (minutes) the
-    ///   torrent is allowed to seed while being inactive. `-2` means the global limit
-    ///   should be used, `-1` means no limit.
+    /// * `selector` - The torrents you want to set the share limit of.
+    /// * `ratio_limit` - The maximum seeding ratio for the torrent.
+    /// * `seeding_time_limit` - The maximum seeding time for the torrent.
+    ///   Only whole minutes are used.
+    /// * `inactive_seeding_time_limit` - The maximum amount of time the
+    ///   torrent is allowed to seed while being inactive. Only whole minutes
+    ///   are used.
     ///
     /// # Example
     ///
     /// ```no_run
     /// use qbit::{Api, Credentials};
+    /// use qbit::models::{SeedingTimeLimit, ShareLimit};
+    /// use qbit::parameters::TorrentSelector;
     ///
     /// #[tokio::main]
     /// async fn main() {
@@ -1181,35 +1610,34 @@ impl super::Api {
     ///         .await
     ///         .unwrap();
     ///
-    ///     let result = client.set_share_limit(None, 0.3, 100, 100).await;
+    ///     let result = client
+    ///         .set_share_limit(
+    ///             TorrentSelector::All,
+    ///             ShareLimit::Value(0.3),
+    ///             SeedingTimeLimit::from_minutes(100),
+    ///             SeedingTimeLimit::Global,
+    ///         )
+    ///         .await;
     ///
     ///     assert!(result.is_ok());
     /// }
     /// ```
     pub async fn set_share_limit(
         &self,
-        hashes: Option<Vec<&str>>,
-        ratio_limit: f64,
-        seeding_time_limit: i64,
-        inactive_seeding_time_limit: i64,
+        selector: impl Into<TorrentSelector>,
+        ratio_limit: ShareLimit,
+        seeding_time_limit: SeedingTimeLimit,
+        inactive_seeding_time_limit: SeedingTimeLimit,
     ) -> Result<(), Error> {
-        let form = multipart::Form::new()
-            .text("hashes", hashes.unwrap_or(vec!["all"]).join("|"))
-            .text("ratioLimit", ratio_limit.to_string())
-            .text("seedingTimeLimit", seeding_time_limit.to_string())
-            .text(
-                "inactiveSeedingTimeLimit",
-                inactive_seeding_time_limit.to_string(),
-            );
-
-        self._post("torrents/setShareLimits")
-            .await?
-            .multipart(form)
-            .send()
-            .await?
-            .error_for_status()?;
-
-        Ok(())
+        self.post_selected("torrents/setShareLimits", selector.into(), |form| {
+            form.text("ratioLimit", ratio_limit.to_raw().to_string())
+                .text("seedingTimeLimit", seeding_time_limit.to_raw().to_string())
+                .text(
+                    "inactiveSeedingTimeLimit",
+                    inactive_seeding_time_limit.to_raw().to_string(),
+                )
+        })
+        .await
     }
 
     /// Get torrent upload limit
@@ -1218,13 +1646,13 @@ impl super::Api {
     ///
     /// # Arguments
     ///
-    /// * `hashes` - The hashes of the torrents you want the upload limit of.
-    ///   If `None` all torrents are selected.
+    /// * `selector` - The torrents you want the upload limit of.
     ///
     /// # Example
     ///
     /// ```no_run
     /// use qbit::{Api, Credentials};
+    /// use qbit::parameters::TorrentSelector;
     ///
     /// #[tokio::main]
     /// async fn main() {
@@ -1233,7 +1661,7 @@ impl super::Api {
     ///         .await
     ///         .unwrap();
     ///
-    ///     let limits = client.upload_limit(None).await.unwrap();
+    ///     let limits = client.upload_limit(TorrentSelector::All).await.unwrap();
     ///
     ///     for limit in limits {
     ///         println!("{:?}", limit);
@@ -1242,21 +1670,27 @@ impl super::Api {
     /// ```
     pub async fn upload_limit(
         &self,
-        hashes: Option<Vec<&str>>,
-    ) -> Result<HashMap<String, i64>, Error> {
-        let query = vec![("hashes", hashes.unwrap_or(vec!["all"]).join("|"))];
-
+        selector: impl Into<TorrentSelector>,
+    ) -> Result<HashMap<InfoHash, SpeedLimit>, Error> {
         let limites = self
-            ._get("torrents/uploadLimit")
-            .await?
-            .query(&query)
-            .send()
-            .await?
-            .error_for_status()?
-            .json::<HashMap<String, i64>>()
+            .for_each_chunk(selector.into(), |hashes| async move {
+                let query = vec![("hashes", hashes)];
+
+                let limites = self
+                    ._get("torrents/uploadLimit")
+                    .await?
+                    .query(&query)
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json::<HashMap<InfoHash, SpeedLimit>>()
+                    .await?;
+
+                Ok(limites)
+            })
             .await?;
 
-        Ok(limites)
+        Ok(limites.into_iter().flatten().collect())
     }
 
     /// Set torrent upload limit
@@ -1265,14 +1699,16 @@ impl super::Api {
     ///
     /// # Arguments
     ///
-    /// * `hashes` - The hashes of the torrents you want to set the upload limit of.
-    ///   If `None` all torrents are selected.
-    /// * `limit` - Upload limit
+    /// * `selector` - The torrents you want to set the upload limit of.
+    /// * `limit` - Upload limit. A torrent without its own limit is still bound
+    ///   by the global limit.
     ///
     /// # Example
     ///
     /// ```no_run
     /// use qbit::{Api, Credentials};
+    /// use qbit::models::{BytesPerSecond, SpeedLimit};
+    /// use qbit::parameters::TorrentSelector;
     ///
     /// #[tokio::main]
     /// async fn main() {
@@ -1281,28 +1717,21 @@ impl super::Api {
     ///         .await
     ///         .unwrap();
     ///
-    ///     let result = client.set_upload_limit(None, 10).await;
+    ///     let limit = SpeedLimit::Value(BytesPerSecond::from_kib(512));
+    ///     let result = client.set_upload_limit(TorrentSelector::All, limit).await;
     ///
     ///     assert!(result.is_ok());
     /// }
     /// ```
     pub async fn set_upload_limit(
         &self,
-        hashes: Option<Vec<&str>>,
-        limit: u64,
+        selector: impl Into<TorrentSelector>,
+        limit: SpeedLimit,
     ) -> Result<(), Error> {
-        let form = multipart::Form::new()
-            .text("hashes", hashes.unwrap_or(vec!["all"]).join("|"))
-            .text("limit", limit.to_string());
-
-        self._post("torrents/setUploadLimit")
-            .await?
-            .multipart(form)
-            .send()
-            .await?
-            .error_for_status()?;
-
-        Ok(())
+        self.post_selected("torrents/setUploadLimit", selector.into(), |form| {
+            form.text("limit", limit.to_raw().to_string())
+        })
+        .await
     }
 
     /// Set torrent location
@@ -1311,14 +1740,14 @@ impl super::Api {
     ///
     /// # Arguments
     ///
-    /// * `hashes` - The hashes of the torrents you want to set the location of.
-    ///   If `None` all torrents are selected.
+    /// * `selector` - The torrents you want to set the location of.
     /// * `location` - Location to download the torrent to.
     ///
     /// # Example
     ///
     /// ```no_run
     /// use qbit::{Api, Credentials};
+    /// use qbit::parameters::TorrentSelector;
     ///
     /// #[tokio::main]
     /// async fn main() {
@@ -1327,43 +1756,37 @@ impl super::Api {
     ///         .await
     ///         .unwrap();
     ///
-    ///     let result = client.set_location(None, "new/location").await;
+    ///     let result = client.set_location(TorrentSelector::All, "new/location").await;
     ///
     ///     assert!(result.is_ok());
     /// }
     /// ```
     pub async fn set_location(
         &self,
-        hashes: Option<Vec<&str>>,
+        selector: impl Into<TorrentSelector>,
         location: &str,
     ) -> Result<(), Error> {
-        let form = multipart::Form::new()
-            .text("hashes", hashes.unwrap_or(vec!["all"]).join("|"))
-            .text("location", location.to_string());
-
-        self._post("torrents/setLocation")
-            .await?
-            .multipart(form)
-            .send()
-            .await?
-            .error_for_status()?;
-
-        Ok(())
+        self.post_selected("torrents/setLocation", selector.into(), |form| {
+            form.text("location", location.to_string())
+        })
+        .await
     }
 
-    /// Set torrent name
+    /// Set torrent save path
     ///
-    /// [official documentation](https://github.com/qbittorrent/qBittorrent/wiki/WebUI-API-(qBittorrent-5.0)#set-torrent-name)
+    /// Changes the folder the torrent is saved to once complete, moving its
+    /// data if needed. Requires qBittorrent 4.4 or newer.
     ///
     /// # Arguments
     ///
-    /// * `hash` - The hash of the torrent you want to set the name of.
-    /// * `name` - Location to download the torrent to.
+    /// * `selector` - The torrents you want to set the save path of.
+    /// * `path` - The new save path.
     ///
     /// # Example
     ///
     /// ```no_run
     /// use qbit::{Api, Credentials};
+    /// use qbit::parameters::TorrentSelector;
     ///
     /// #[tokio::main]
     /// async fn main() {
@@ -1372,40 +1795,37 @@ impl super::Api {
     ///         .await
     ///         .unwrap();
     ///
-    ///     let result = client.set_name("hash", "new_torrent_name").await;
+    ///     let result = client.set_save_path(TorrentSelector::All, "/downloads").await;
     ///
     ///     assert!(result.is_ok());
     /// }
     /// ```
-    pub async fn set_name(&self, hash: &str, name: &str) -> Result<(), Error> {
-        let form = multipart::Form::new()
-            .text("hash", hash.to_string())
-            .text("name", name.to_string());
-
-        self._post("torrents/setLocation")
-            .await?
-            .multipart(form)
-            .send()
-            .await?
-            .error_for_status()?;
-
-        Ok(())
+    pub async fn set_save_path(
+        &self,
+        selector: impl Into<TorrentSelector>,
+        path: &str,
+    ) -> Result<(), Error> {
+        self.post_selected("torrents/setSavePath", selector.into(), |form| {
+            form.text("path", path.to_string())
+        })
+        .await
     }
 
-    /// Set torrent category
+    /// Set torrent download path
     ///
-    /// [official documentation](https://github.com/qbittorrent/qBittorrent/wiki/WebUI-API-(qBittorrent-5.0)#set-torrent-category)
+    /// Changes the folder the torrent is downloaded to while incomplete.
+    /// Requires qBittorrent 4.4 or newer.
     ///
     /// # Arguments
     ///
-    /// * `hashes` - The hashes of the torrents you want to set the category of.
-    ///   If `None` all torrents are selected.
-    /// * `category` - Name of the category you want to set.
+    /// * `selector` - The torrents you want to set the download path of.
+    /// * `path` - The new download path.
     ///
     /// # Example
     ///
     /// ```no_run
     /// use qbit::{Api, Credentials};
+    /// use qbit::parameters::TorrentSelector;
     ///
     /// #[tokio::main]
     /// async fn main() {
@@ -1414,38 +1834,51 @@ impl super::Api {
     ///         .await
     ///         .unwrap();
     ///
-    ///     let result = client.set_category(None, "category").await;
+    ///     let result = client.set_download_path(TorrentSelector::All, "/incomplete").await;
     ///
     ///     assert!(result.is_ok());
     /// }
     /// ```
-    pub async fn set_category(
+    pub async fn set_download_path(
         &self,
-        hashes: Option<Vec<&str>>,
-        category: &str,
+        selector: impl Into<TorrentSelector>,
+        path: &str,
     ) -> Result<(), Error> {
-        let form = multipart::Form::new()
-            .text("hashes", hashes.unwrap_or(vec!["all"]).join("|"))
-            .text("category", category.to_string());
-
-        self._post("torrents/setCategory")
-            .await?
-            .multipart(form)
-            .send()
-            .await?
-            .error_for_status()?;
-
-        Ok(())
+        self.post_selected("torrents/setDownloadPath", selector.into(), |form| {
+            form.text("path", path.to_string())
+        })
+        .await
     }
 
-    /// Get all categories
+    /// Move a torrent and wait for the move to finish
     ///
-    /// [official documentation](https://github.com/qbittorrent/qBittorrent/wiki/WebUI-API-(qBittorrent-5.0)#get-all-categories)
+    /// Sets the location of the torrent with [`Api::set_location`](crate::Api::set_location),
+    /// then checks the torrent every `params.interval` until its state is no
+    /// longer [`TorrentState::Moving`].
+    ///
+    /// Returns the `save_path` of the torrent once the move is done and the
+    /// torrent is in `location`.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash` - The hash of the torrent you want to move.
+    /// * `location` - Location to move the torrent to.
+    /// * `params` - How often to check the torrent and how long to wait.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::Timeout`] if the move did not finish within `params.timeout`,
+    /// [`Error::InvalidResponse`] if the torrent disappeared while moving, is
+    /// in an error state afterwards or is not in `location`.
     ///
     /// # Example
     ///
     /// ```no_run
+    /// use std::time::Duration;
+    ///
     /// use qbit::{Api, Credentials};
+    /// use qbit::models::InfoHash;
+    /// use qbit::parameters::MoveParamsBuilder;
     ///
     /// #[tokio::main]
     /// async fn main() {
@@ -1454,39 +1887,101 @@ impl super::Api {
     ///         .await
     ///         .unwrap();
     ///
-    ///     let categories = client.categories().await.unwrap();
+    ///     let hash = InfoHash::new("8c212779b4abde7c6bc608063a0d008b7e40ce32").unwrap();
+    ///     let params = MoveParamsBuilder::default()
+    ///         .timeout(Duration::from_secs(600))
+    ///         .build()
+    ///         .unwrap();
+    ///     let save_path = client.move_and_wait(&hash, "/archive", params).await.unwrap();
     ///
-    ///     for categori in categories {
-    ///         println!("{}", categori);
-    ///     }
+    ///     println!("Moved to {}", save_path);
     /// }
     /// ```
-    pub async fn categories(&self) -> Result<Vec<String>, Error> {
-        let categories = self
-            ._get("torrents/categories")
-            .await?
-            .send()
-            .await?
-            .error_for_status()?
-            .json::<Vec<String>>()
-            .await?;
+    pub async fn move_and_wait(
+        &self,
+        hash: &InfoHash,
+        location: &str,
+        params: MoveParams,
+    ) -> Result<String, Error> {
+        self.set_location(hash, location).await?;
+
+        let wait = async {
+            loop {
+                tokio::time::sleep(params.interval).await;
+
+                let list = TorrentListParams {
+                    hashes: Some(vec![hash.clone()]),
+                    ..Default::default()
+                };
+                let torrent = self
+                    .torrents(Some(list))
+                    .await?
+                    .into_iter()
+                    .next()
+                    .ok_or_else(|| {
+                        Error::InvalidResponse(format!("Torrent {} not found while moving", hash))
+                    })?;
+
+                match torrent.state {
+                    TorrentState::Moving => continue,
+                    TorrentState::Error | TorrentState::MissingFiles => {
+                        return Err(Error::InvalidResponse(format!(
+                            "Torrent {} is in state {:#?} after moving to {}",
+                            hash, torrent.state, location
+                        )));
+                    }
+                    _ if !same_path(&torrent.save_path, location) => {
+                        return Err(Error::InvalidResponse(format!(
+                            "Torrent {} is in {} instead of {} after moving",
+                            hash, torrent.save_path, location
+                        )));
+                    }
+                    _ => return Ok(torrent.save_path),
+                }
+            }
+        };
 
-        Ok(categories)
+        match params.timeout {
+            Some(timeout) => tokio::time::timeout(timeout, wait).await.map_err(|_| {
+                Error::Timeout(format!(
+                    "Torrent {} is still moving after {:?}",
+                    hash, timeout
+                ))
+            })?,
+            None => wait.await,
+        }
     }
 
-    /// Add new category
+    /// Verify the data of a torrent locally
     ///
-    /// [official documentation](https://github.com/qbittorrent/qBittorrent/wiki/WebUI-API-(qBittorrent-5.0)#add-new-category)
+    /// Reads the files of the torrent from the local file system and compares
+    /// every piece to the hashes from [`Api::pieces_hashes`](crate::Api::pieces_hashes),
+    /// without asking the server to [`recheck`](crate::Api::recheck) the torrent.
+    /// Bad pieces are mapped back to the files they belong to.
+    ///
+    /// The data is read from the download path while the torrent is
+    /// incomplete and one is set, from the save path otherwise. It must be
+    /// reachable from this machine, see [`VerifyParams::path_map`] for paths that differ from the ones of the
+    /// server.
     ///
     /// # Arguments
     ///
-    /// * `category` - Name for the category to create.
-    /// * `save_path` - Path to download torrents for the category.
+    /// * `hash` - The hash of the torrent you want to verify.
+    /// * `params` - Path mapping and number of hashing threads.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::InvalidRequest`] if the torrent does not exist or is a v2 only
+    /// torrent, which has no v1 piece hashes.
     ///
     /// # Example
     ///
     /// ```no_run
+    /// use std::path::PathBuf;
+    ///
     /// use qbit::{Api, Credentials};
+    /// use qbit::models::InfoHash;
+    /// use qbit::parameters::VerifyParamsBuilder;
     ///
     /// #[tokio::main]
     /// async fn main() {
@@ -1495,39 +1990,77 @@ impl super::Api {
     ///         .await
     ///         .unwrap();
     ///
-    ///     let result = client.create_category("category", "save/path").await;
+    ///     let hash = InfoHash::new("8c212779b4abde7c6bc608063a0d008b7e40ce32").unwrap();
+    ///     let params = VerifyParamsBuilder::default()
+    ///         .path_map(vec![(String::from("/downloads"), PathBuf::from("/mnt/nas"))])
+    ///         .build()
+    ///         .unwrap();
+    ///     let report = client.verify_local_data(&hash, params).await.unwrap();
     ///
-    ///     assert!(result.is_ok());
+    ///     for file in report.bad_files() {
+    ///         println!("{}: pieces {:?}", file.name, file.bad_pieces);
+    ///     }
     /// }
     /// ```
-    pub async fn create_category(&self, category: &str, save_path: &str) -> Result<(), Error> {
-        let form = multipart::Form::new()
-            .text("category", category.to_string())
-            .text("savePath", save_path.to_string());
-
-        self._post("torrents/createCategory")
-            .await?
-            .multipart(form)
-            .send()
+    pub async fn verify_local_data(
+        &self,
+        hash: &InfoHash,
+        params: VerifyParams,
+    ) -> Result<VerifyReport, Error> {
+        let list = TorrentListParams {
+            hashes: Some(vec![hash.clone()]),
+            ..Default::default()
+        };
+        let torrent = self
+            .torrents(Some(list))
             .await?
-            .error_for_status()?;
+            .into_iter()
+            .next()
+            .ok_or_else(|| Error::InvalidRequest(format!("Torrent {} not found", hash)))?;
 
-        Ok(())
+        let properties = self.torrent(hash).await?;
+        let content = self.files(hash, None).await?;
+        let hashes = self.pieces_hashes(hash).await?;
+
+        if hashes.is_empty() && !content.is_empty() {
+            return Err(Error::InvalidRequest(format!(
+                "Torrent {} has no v1 piece hashes",
+                hash
+            )));
+        }
+
+        // Incomplete torrents are kept in the download path, when one is set.
+        let path = if torrent.amount_left.0 > 0 && !torrent.download_path.is_empty() {
+            &torrent.download_path
+        } else {
+            &torrent.save_path
+        };
+        let verifier = PieceVerifier::from_content(
+            properties.piece_size.0.max(0) as u64,
+            &hashes,
+            &content,
+            &params.map_path(path),
+        )?;
+
+        tokio::task::spawn_blocking(move || verifier.verify(params.concurrency))
+            .await
+            .map_err(|e| Error::IoError(std::io::Error::other(e)))
     }
 
-    /// Edit category
+    /// Set torrent name
     ///
-    /// [official documentation](https://github.com/qbittorrent/qBittorrent/wiki/WebUI-API-(qBittorrent-5.0)#edit-category)
+    /// [official documentation](https://github.com/qbittorrent/qBittorrent/wiki/WebUI-API-(qBittorrent-5.0)#set-torrent-name)
     ///
     /// # Arguments
     ///
-    /// * `category` - Name for the category to edit.
-    /// * `save_path` - Path to download torrents for the category.
+    /// * `hash` - The hash of the torrent you want to set the name of.
+    /// * `name` - Location to download the torrent to.
     ///
     /// # Example
     ///
     /// ```no_run
     /// use qbit::{Api, Credentials};
+    /// use qbit::models::InfoHash;
     ///
     /// #[tokio::main]
     /// async fn main() {
@@ -1536,17 +2069,18 @@ impl super::Api {
     ///         .await
     ///         .unwrap();
     ///
-    ///     let result = client.edit_category("category", "new/save/path").await;
+    ///     let hash = InfoHash::new("8c212779b4abde7c6bc608063a0d008b7e40ce32").unwrap();
+    ///     let result = client.set_name(&hash, "new_torrent_name").await;
     ///
     ///     assert!(result.is_ok());
     /// }
     /// ```
-    pub async fn edit_category(&self, category: &str, save_path: &str) -> Result<(), Error> {
+    pub async fn set_name(&self, hash: &InfoHash, name: &str) -> Result<(), Error> {
         let form = multipart::Form::new()
-            .text("category", category.to_string())
-            .text("savePath", save_path.to_string());
+            .text("hash", hash.to_string())
+            .text("name", name.to_string());
 
-        self._post("torrents/editCategory")
+        self._post("torrents/setLocation")
             .await?
             .multipart(form)
             .send()
@@ -1556,18 +2090,20 @@ impl super::Api {
         Ok(())
     }
 
-    /// Remove categories
+    /// Set torrent category
     ///
-    /// [official documentation](https://github.com/qbittorrent/qBittorrent/wiki/WebUI-API-(qBittorrent-5.0)#remove-categories)
+    /// [official documentation](https://github.com/qbittorrent/qBittorrent/wiki/WebUI-API-(qBittorrent-5.0)#set-torrent-category)
     ///
     /// # Arguments
     ///
-    /// * `categories` - List of category names to remove.
+    /// * `selector` - The torrents you want to set the category of.
+    /// * `category` - Name of the category you want to set.
     ///
     /// # Example
     ///
     /// ```no_run
     /// use qbit::{Api, Credentials};
+    /// use qbit::parameters::TorrentSelector;
     ///
     /// #[tokio::main]
     /// async fn main() {
@@ -1576,39 +2112,75 @@ impl super::Api {
     ///         .await
     ///         .unwrap();
     ///
-    ///     let categories = vec!["movie", "distro"];
-    ///     let result = client.remove_categories(categories).await;
+    ///     let result = client.set_category(TorrentSelector::All, "category").await;
     ///
     ///     assert!(result.is_ok());
     /// }
     /// ```
-    pub async fn remove_categories(&self, categories: Vec<&str>) -> Result<(), Error> {
-        let form = multipart::Form::new().text("categories", categories.join("\n"));
+    pub async fn set_category(
+        &self,
+        selector: impl Into<TorrentSelector>,
+        category: &str,
+    ) -> Result<(), Error> {
+        self.post_selected("torrents/setCategory", selector.into(), |form| {
+            form.text("category", category.to_string())
+        })
+        .await
+    }
 
-        self._post("torrents/removeCategories")
+    /// Get all categories
+    ///
+    /// Returns the categories by name.
+    ///
+    /// [official documentation](https://github.com/qbittorrent/qBittorrent/wiki/WebUI-API-(qBittorrent-5.0)#get-all-categories)
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use qbit::{Api, Credentials};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let credentials = Credentials::new("username", "password");
+    ///     let client = Api::new_login("url", credentials)
+    ///         .await
+    ///         .unwrap();
+    ///
+    ///     let categories = client.categories().await.unwrap();
+    ///
+    ///     for (name, category) in categories {
+    ///         println!("{}: {}", name, category.save_path);
+    ///     }
+    /// }
+    /// ```
+    pub async fn categories(&self) -> Result<HashMap<String, Category>, Error> {
+        let categories = self
+            ._get("torrents/categories")
             .await?
-            .multipart(form)
             .send()
             .await?
-            .error_for_status()?;
+            .error_for_status()?
+            .json::<HashMap<String, Category>>()
+            .await?;
 
-        Ok(())
+        Ok(categories)
     }
 
-    /// Add torrent tags
+    /// Add new category
     ///
-    /// [official documentation](https://github.com/qbittorrent/qBittorrent/wiki/WebUI-API-(qBittorrent-5.0)#add-torrent-tags)
+    /// [official documentation](https://github.com/qbittorrent/qBittorrent/wiki/WebUI-API-(qBittorrent-5.0)#add-new-category)
     ///
     /// # Arguments
     ///
-    /// * `hashes` - The hashes of the torrents you want to set the tags of.
-    ///   If `None` all torrents are selected.
-    /// * `tags` - List of names for the tags you want to set.
+    /// * `category` - Name for the category to create.
+    /// * `options` - Save path and download path of the category.
     ///
     /// # Example
     ///
     /// ```no_run
     /// use qbit::{Api, Credentials};
+    /// use qbit::models::CategoryDownloadPath;
+    /// use qbit::parameters::CategoryOptionsBuilder;
     ///
     /// #[tokio::main]
     /// async fn main() {
@@ -1617,18 +2189,24 @@ impl super::Api {
     ///         .await
     ///         .unwrap();
     ///
-    ///     let tags = vec!["listed"];
-    ///     let result = client.add_tags(None, tags).await;
+    ///     let options = CategoryOptionsBuilder::default()
+    ///         .save_path("save/path")
+    ///         .download_path(CategoryDownloadPath::Path("incomplete/path".to_string()))
+    ///         .build()
+    ///         .unwrap();
+    ///     let result = client.create_category("category", options).await;
     ///
     ///     assert!(result.is_ok());
     /// }
     /// ```
-    pub async fn add_tags(&self, hashes: Option<Vec<&str>>, tags: Vec<&str>) -> Result<(), Error> {
-        let form = multipart::Form::new()
-            .text("hashes", hashes.unwrap_or(vec!["all"]).join("|"))
-            .text("tags", tags.join(","));
+    pub async fn create_category(
+        &self,
+        category: &str,
+        options: CategoryOptions,
+    ) -> Result<(), Error> {
+        let form = category_form(category, options);
 
-        self._post("torrents/addTags")
+        self._post("torrents/createCategory")
             .await?
             .multipart(form)
             .send()
@@ -1638,20 +2216,20 @@ impl super::Api {
         Ok(())
     }
 
-    /// Remove torrent tags
+    /// Edit category
     ///
-    /// [official documentation](https://github.com/qbittorrent/qBittorrent/wiki/WebUI-API-(qBittorrent-5.0)#remove-torrent-tags)
+    /// [official documentation](https://github.com/qbittorrent/qBittorrent/wiki/WebUI-API-(qBittorrent-5.0)#edit-category)
     ///
     /// # Arguments
     ///
-    /// * `hashes` - The hashes of the torrents you want to remove the tags of.
-    ///   If `None` all torrents are selected.
-    /// * `tags` - List of names for the tags you want to remove.
+    /// * `category` - Name for the category to edit.
+    /// * `options` - New save path and download path of the category.
     ///
     /// # Example
     ///
     /// ```no_run
     /// use qbit::{Api, Credentials};
+    /// use qbit::parameters::CategoryOptionsBuilder;
     ///
     /// #[tokio::main]
     /// async fn main() {
@@ -1660,22 +2238,23 @@ impl super::Api {
     ///         .await
     ///         .unwrap();
     ///
-    ///     let tags = vec!["listed"];
-    ///     let result = client.remove_tags(None, tags).await;
+    ///     let options = CategoryOptionsBuilder::default()
+    ///         .save_path("new/save/path")
+    ///         .build()
+    ///         .unwrap();
+    ///     let result = client.edit_category("category", options).await;
     ///
     ///     assert!(result.is_ok());
     /// }
     /// ```
-    pub async fn remove_tags(
+    pub async fn edit_category(
         &self,
-        hashes: Option<Vec<&str>>,
-        tags: Vec<&str>,
+        category: &str,
+        options: CategoryOptions,
     ) -> Result<(), Error> {
-        let form = multipart::Form::new()
-            .text("hashes", hashes.unwrap_or(vec!["all"]).join("|"))
-            .text("tags", tags.join(","));
+        let form = category_form(category, options);
 
-        self._post("torrents/removeTags")
+        self._post("torrents/editCategory")
             .await?
             .multipart(form)
             .send()
@@ -1685,9 +2264,27 @@ impl super::Api {
         Ok(())
     }
 
-    /// Get all tags
+    /// Rename category
     ///
-    /// [official documentation](https://github.com/qbittorrent/qBittorrent/wiki/WebUI-API-(qBittorrent-5.0)#get-all-tags)
+    /// The API has no rename operation, so this creates `new_name` with the
+    /// options of `category`, moves every torrent of `category` to it and
+    /// removes `category`. Subcategories are renamed along with it, like
+    /// [`Api::move_category`](crate::Api::move_category) does.
+    ///
+    /// Torrents using Automatic Torrent Management in a category without a
+    /// save path of its own are saved in a folder named after the category,
+    /// so renaming it moves their data to the new folder.
+    ///
+    /// # Arguments
+    ///
+    /// * `category` - Name of the category to rename.
+    /// * `new_name` - New name of the category.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::InvalidRequest`] if `category` does not exist or `new_name`
+    /// is taken. [`Error::CategoryMoveFailed`] if a request fails, with the
+    /// requests applied before it.
     ///
     /// # Example
     ///
@@ -1701,33 +2298,35 @@ impl super::Api {
     ///         .await
     ///         .unwrap();
     ///
-    ///     let tags = client.tags().await.unwrap();
+    ///     let result = client.rename_category("movie", "movies").await;
     ///
-    ///     for tag in tags {
-    ///         println!("{}", tag);
-    ///     }
+    ///     assert!(result.is_ok());
     /// }
     /// ```
-    pub async fn tags(&self) -> Result<Vec<String>, Error> {
-        let tags = self
-            ._get("torrents/tags")
-            .await?
-            .send()
-            .await?
-            .error_for_status()?
-            .json::<Vec<String>>()
-            .await?;
+    pub async fn rename_category(&self, category: &str, new_name: &str) -> Result<(), Error> {
+        let tree = CategoryTree::from(self.categories().await?);
+        let plan = tree.rename_plan(category, new_name)?;
 
-        Ok(tags)
+        self.apply_category_plan(&tree, &plan).await
     }
 
-    /// Create tags
+    /// Move a category and its subcategories
     ///
-    /// [official documentation](https://github.com/qbittorrent/qBittorrent/wiki/WebUI-API-(qBittorrent-5.0)#create-tags)
+    /// Moves `category` under `new_parent`, or to the top level if `None`.
+    /// Every category of the subtree is recreated under its new name with its
+    /// options, its torrents are moved to it, then the old categories are
+    /// removed.
     ///
     /// # Arguments
     ///
-    /// * `tags` - List of tags to create.
+    /// * `category` - Name of the category to move.
+    /// * `new_parent` - Name of the new parent category.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::InvalidRequest`] if `category` does not exist or cannot be
+    /// moved under `new_parent`. [`Error::CategoryMoveFailed`] if a request
+    /// fails, with the requests applied before it.
     ///
     /// # Example
     ///
@@ -1741,32 +2340,97 @@ impl super::Api {
     ///         .await
     ///         .unwrap();
     ///
-    ///     let tags = vec!["listed"];
-    ///     let result = client.create_tags(tags).await;
+    ///     // Moves `tv/anime` and its subcategories to `anime`
+    ///     let result = client.move_category("tv/anime", None).await;
     ///
     ///     assert!(result.is_ok());
     /// }
     /// ```
-    pub async fn create_tags(&self, tags: Vec<&str>) -> Result<(), Error> {
-        let form = multipart::Form::new().text("tags", tags.join(","));
+    pub async fn move_category(
+        &self,
+        category: &str,
+        new_parent: Option<&str>,
+    ) -> Result<(), Error> {
+        let tree = CategoryTree::from(self.categories().await?);
+        let plan = tree.move_plan(category, new_parent)?;
 
-        self._post("torrents/createTags")
-            .await?
-            .multipart(form)
-            .send()
+        self.apply_category_plan(&tree, &plan).await
+    }
+
+    /// Recreates every category of `plan` under its new name, moves its
+    /// torrents to it and removes the old categories. Implicit parents, which
+    /// do not exist on the server, are left out.
+    async fn apply_category_plan(
+        &self,
+        tree: &CategoryTree,
+        plan: &[(String, String)],
+    ) -> Result<(), Error> {
+        let plan: Vec<(&str, &str, CategoryOptions)> = plan
+            .iter()
+            .filter_map(|(old_name, new_name)| {
+                let category = tree.get(old_name)?.category.as_ref()?;
+                Some((
+                    old_name.as_str(),
+                    new_name.as_str(),
+                    CategoryOptions::from(category),
+                ))
+            })
+            .collect();
+        if plan.is_empty() {
+            return Ok(());
+        }
+
+        let mut applied = Vec::new();
+        for (old_name, new_name, options) in &plan {
+            let result = self.create_category(new_name, options.clone()).await;
+            record_step(
+                &mut applied,
+                CategoryStep::Create(new_name.to_string()),
+                result,
+            )?;
+
+            let result = self.recategorize(old_name, new_name).await;
+            let step = CategoryStep::Recategorize {
+                from: old_name.to_string(),
+                to: new_name.to_string(),
+            };
+            record_step(&mut applied, step, result)?;
+        }
+
+        let old_names: Vec<&str> = plan
+            .iter()
+            .rev()
+            .map(|(old_name, _, _)| *old_name)
+            .collect();
+        let result = self.remove_categories(old_names.clone()).await;
+        let step = CategoryStep::Remove(old_names.into_iter().map(String::from).collect());
+        record_step(&mut applied, step, result)
+    }
+
+    /// Moves the torrents of exactly `from` (not its subcategories) to `to`.
+    async fn recategorize(&self, from: &str, to: &str) -> Result<(), Error> {
+        let params = TorrentListParams {
+            category: Some(from.to_string()),
+            ..Default::default()
+        };
+        let hashes: Vec<InfoHash> = self
+            .torrents(Some(params))
             .await?
-            .error_for_status()?;
+            .into_iter()
+            .filter(|torrent| torrent.category == from)
+            .map(|torrent| torrent.hash)
+            .collect();
 
-        Ok(())
+        self.set_category(hashes, to).await
     }
 
-    /// Delete tags
+    /// Remove categories
     ///
-    /// [official documentation](https://github.com/qbittorrent/qBittorrent/wiki/WebUI-API-(qBittorrent-5.0)#delete-tags)
+    /// [official documentation](https://github.com/qbittorrent/qBittorrent/wiki/WebUI-API-(qBittorrent-5.0)#remove-categories)
     ///
     /// # Arguments
     ///
-    /// * `tags` - List of tags to delete.
+    /// * `categories` - List of category names to remove.
     ///
     /// # Example
     ///
@@ -1780,16 +2444,16 @@ impl super::Api {
     ///         .await
     ///         .unwrap();
     ///
-    ///     let tags = vec!["listed"];
-    ///     let result = client.delete_tags(tags).await;
+    ///     let categories = vec!["movie", "distro"];
+    ///     let result = client.remove_categories(categories).await;
     ///
     ///     assert!(result.is_ok());
     /// }
     /// ```
-    pub async fn delete_tags(&self, tags: Vec<&str>) -> Result<(), Error> {
-        let form = multipart::Form::new().text("tags", tags.join(","));
+    pub async fn remove_categories(&self, categories: Vec<&str>) -> Result<(), Error> {
+        let form = multipart::Form::new().text("categories", categories.join("\n"));
 
-        self._post("torrents/deleteTags")
+        self._post("torrents/removeCategories")
             .await?
             .multipart(form)
             .send()
@@ -1799,20 +2463,20 @@ impl super::Api {
         Ok(())
     }
 
-    /// Set automatic torrent management
+    /// Add torrent tags
     ///
-    /// [official documentation](https://github.com/qbittorrent/qBittorrent/wiki/WebUI-API-(qBittorrent-5.0)#set-automatic-torrent-management)
+    /// [official documentation](https://github.com/qbittorrent/qBittorrent/wiki/WebUI-API-(qBittorrent-5.0)#add-torrent-tags)
     ///
     /// # Arguments
     ///
-    /// * `hashes` - The hashes of the torrents you want to set automatic torrent management of.
-    ///   If `None` all torrents are selected.
-    /// * `enable`
+    /// * `selector` - The torrents you want to set the tags of.
+    /// * `tags` - List of names for the tags you want to set.
     ///
     /// # Example
     ///
     /// ```no_run
     /// use qbit::{Api, Credentials};
+    /// use qbit::parameters::TorrentSelector;
     ///
     /// #[tokio::main]
     /// async fn main() {
@@ -1821,43 +2485,37 @@ impl super::Api {
     ///         .await
     ///         .unwrap();
     ///
-    ///     let result = client.set_automatic_torrent_management(None, true).await;
+    ///     let tags = vec!["listed"];
+    ///     let result = client.add_tags(TorrentSelector::All, tags).await;
     ///
     ///     assert!(result.is_ok());
     /// }
     /// ```
-    pub async fn set_automatic_torrent_management(
+    pub async fn add_tags(
         &self,
-        hashes: Option<Vec<&str>>,
-        enable: bool,
+        selector: impl Into<TorrentSelector>,
+        tags: Vec<&str>,
     ) -> Result<(), Error> {
-        let form = multipart::Form::new()
-            .text("hashes", hashes.unwrap_or(vec!["all"]).join("|"))
-            .text("enable", enable.to_string());
-
-        self._post("torrents/setAutoManagement")
-            .await?
-            .multipart(form)
-            .send()
-            .await?
-            .error_for_status()?;
-
-        Ok(())
+        self.post_selected("torrents/addTags", selector.into(), |form| {
+            form.text("tags", tags.join(","))
+        })
+        .await
     }
 
-    /// Toggle sequential download
+    /// Remove torrent tags
     ///
-    /// [official documentation](https://github.com/qbittorrent/qBittorrent/wiki/WebUI-API-(qBittorrent-5.0)#toggle-sequential-download)
+    /// [official documentation](https://github.com/qbittorrent/qBittorrent/wiki/WebUI-API-(qBittorrent-5.0)#remove-torrent-tags)
     ///
     /// # Arguments
     ///
-    /// * `hashes` - The hashes of the torrents you want to toggle sequential download for.
-    ///   If `None` all torrents are selected.
+    /// * `selector` - The torrents you want to remove the tags of.
+    /// * `tags` - List of names for the tags you want to remove.
     ///
     /// # Example
     ///
     /// ```no_run
     /// use qbit::{Api, Credentials};
+    /// use qbit::parameters::TorrentSelector;
     ///
     /// #[tokio::main]
     /// async fn main() {
@@ -1866,32 +2524,26 @@ impl super::Api {
     ///         .await
     ///         .unwrap();
     ///
-    ///     let result = client.toggle_sequential_download(None).await;
+    ///     let tags = vec!["listed"];
+    ///     let result = client.remove_tags(TorrentSelector::All, tags).await;
     ///
     ///     assert!(result.is_ok());
     /// }
     /// ```
-    pub async fn toggle_sequential_download(&self, hashes: Option<Vec<&str>>) -> Result<(), Error> {
-        let form = multipart::Form::new().text("hashes", hashes.unwrap_or(vec!["all"]).join("|"));
-
-        self._post("torrents/toggleSequentialDownload")
-            .await?
-            .multipart(form)
-            .send()
-            .await?
-            .error_for_status()?;
-
-        Ok(())
+    pub async fn remove_tags(
+        &self,
+        selector: impl Into<TorrentSelector>,
+        tags: Vec<&str>,
+    ) -> Result<(), Error> {
+        self.post_selected("torrents/removeTags", selector.into(), |form| {
+            form.text("tags", tags.join(","))
+        })
+        .await
     }
 
-    /// Toggle first/last piece priority
-    ///
-    /// [official documentation](https://github.com/qbittorrent/qBittorrent/wiki/WebUI-API-(qBittorrent-5.0)#set-firstlast-piece-priority)
-    ///
-    /// # Arguments
+    /// Get all tags
     ///
-    /// * `hashes` - The hashes of the torrents you want to toggle first/last piece priority for.
-    ///   If `None` all torrents are selected.
+    /// [official documentation](https://github.com/qbittorrent/qBittorrent/wiki/WebUI-API-(qBittorrent-5.0)#get-all-tags)
     ///
     /// # Example
     ///
@@ -1905,33 +2557,33 @@ impl super::Api {
     ///         .await
     ///         .unwrap();
     ///
-    ///     let result = client.toggle_first_last_priority(None).await;
+    ///     let tags = client.tags().await.unwrap();
     ///
-    ///     assert!(result.is_ok());
+    ///     for tag in tags {
+    ///         println!("{}", tag);
+    ///     }
     /// }
     /// ```
-    pub async fn toggle_first_last_priority(&self, hashes: Option<Vec<&str>>) -> Result<(), Error> {
-        let form = multipart::Form::new().text("hashes", hashes.unwrap_or(vec!["all"]).join("|"));
-
-        self._post("torrents/toggleFirstLastPiecePrio")
+    pub async fn tags(&self) -> Result<Vec<String>, Error> {
+        let tags = self
+            ._get("torrents/tags")
             .await?
-            .multipart(form)
             .send()
             .await?
-            .error_for_status()?;
+            .error_for_status()?
+            .json::<Vec<String>>()
+            .await?;
 
-        Ok(())
+        Ok(tags)
     }
 
-    /// Set force start
+    /// Create tags
     ///
-    /// [official documentation](https://github.com/qbittorrent/qBittorrent/wiki/WebUI-API-(qBittorrent-5.0)#set-force-start)
+    /// [official documentation](https://github.com/qbittorrent/qBittorrent/wiki/WebUI-API-(qBittorrent-5.0)#create-tags)
     ///
     /// # Arguments
     ///
-    /// * `hashes` - The hashes of the torrents you want to set force start of.
-    ///   If `None` all torrents are selected.
-    /// * `enable`
+    /// * `tags` - List of tags to create.
     ///
     /// # Example
     ///
@@ -1945,21 +2597,16 @@ impl super::Api {
     ///         .await
     ///         .unwrap();
     ///
-    ///     let result = client.set_force_start(None, false).await;
+    ///     let tags = vec!["listed"];
+    ///     let result = client.create_tags(tags).await;
     ///
     ///     assert!(result.is_ok());
     /// }
     /// ```
-    pub async fn set_force_start(
-        &self,
-        hashes: Option<Vec<&str>>,
-        enable: bool,
-    ) -> Result<(), Error> {
-        let form = multipart::Form::new()
-            .text("hashes", hashes.unwrap_or(vec!["all"]).join("|"))
-            .text("value", enable.to_string());
+    pub async fn create_tags(&self, tags: Vec<&str>) -> Result<(), Error> {
+        let form = multipart::Form::new().text("tags", tags.join(","));
 
-        self._post("torrents/setForceStart")
+        self._post("torrents/createTags")
             .await?
             .multipart(form)
             .send()
@@ -1969,15 +2616,13 @@ impl super::Api {
         Ok(())
     }
 
-    /// Set super seeding
+    /// Delete tags
     ///
-    /// [official documentation](https://github.com/qbittorrent/qBittorrent/wiki/WebUI-API-(qBittorrent-5.0)#set-super-seeding)
+    /// [official documentation](https://github.com/qbittorrent/qBittorrent/wiki/WebUI-API-(qBittorrent-5.0)#delete-tags)
     ///
     /// # Arguments
     ///
-    /// * `hashes` - The hashes of the torrents you want to set super seeding of.
-    ///   If `None` all torrents are selected.
-    /// * `enable`
+    /// * `tags` - List of tags to delete.
     ///
     /// # Example
     ///
@@ -1991,21 +2636,16 @@ impl super::Api {
     ///         .await
     ///         .unwrap();
     ///
-    ///     let result = client.set_super_seeding(None, false).await;
+    ///     let tags = vec!["listed"];
+    ///     let result = client.delete_tags(tags).await;
     ///
     ///     assert!(result.is_ok());
     /// }
     /// ```
-    pub async fn set_super_seeding(
-        &self,
-        hashes: Option<Vec<&str>>,
-        enable: bool,
-    ) -> Result<(), Error> {
-        let form = multipart::Form::new()
-            .text("hashes", hashes.unwrap_or(vec!["all"]).join("|"))
-            .text("value", enable.to_string());
+    pub async fn delete_tags(&self, tags: Vec<&str>) -> Result<(), Error> {
+        let form = multipart::Form::new().text("tags", tags.join(","));
 
-        self._post("torrents/setSuperSeeding")
+        self._post("torrents/deleteTags")
             .await?
             .multipart(form)
             .send()
@@ -2015,20 +2655,23 @@ impl super::Api {
         Ok(())
     }
 
-    /// Rename file
+    /// Set torrent tags
     ///
-    /// [official documentation](https://github.com/qbittorrent/qBittorrent/wiki/WebUI-API-(qBittorrent-5.0)#rename-file)
+    /// Replaces the tags of the torrents: tags not in `tags` are removed and
+    /// missing ones are added. Tags that do not exist yet are created.
+    ///
+    /// Requires qBittorrent 5.1 or newer.
     ///
     /// # Arguments
     ///
-    /// * `hash` - The hash of the torrent
-    /// * `oldPath` - The old path of the torrent
-    /// * `newPath` - The new path to use for the file
+    /// * `selector` - The torrents you want to set the tags of.
+    /// * `tags` - List of names for the tags the torrents should have.
     ///
     /// # Example
     ///
     /// ```no_run
     /// use qbit::{Api, Credentials};
+    /// use qbit::models::InfoHash;
     ///
     /// #[tokio::main]
     /// async fn main() {
@@ -2037,41 +2680,33 @@ impl super::Api {
     ///         .await
     ///         .unwrap();
     ///
-    ///     let result = client.rename_file("hash", "old/file", "new/file").await;
+    ///     let hash = InfoHash::new("8c212779b4abde7c6bc608063a0d008b7e40ce32").unwrap();
+    ///     let result = client.set_tags(hash, vec!["linux", "iso"]).await;
     ///
     ///     assert!(result.is_ok());
     /// }
     /// ```
-    pub async fn rename_file(
+    pub async fn set_tags(
         &self,
-        hash: &str,
-        old_path: &str,
-        new_path: &str,
+        selector: impl Into<TorrentSelector>,
+        tags: Vec<&str>,
     ) -> Result<(), Error> {
-        let form = multipart::Form::new()
-            .text("hash", hash.to_string())
-            .text("oldPath", old_path.to_string())
-            .text("newPath", new_path.to_string());
-
-        self._post("torrents/renameFile")
-            .await?
-            .multipart(form)
-            .send()
-            .await?
-            .error_for_status()?;
-
-        Ok(())
+        self.post_selected("torrents/setTags", selector.into(), |form| {
+            form.text("tags", tags.join(","))
+        })
+        .await
     }
 
-    /// Rename folder
+    /// Rename a tag
     ///
-    /// [official documentation](https://github.com/qbittorrent/qBittorrent/wiki/WebUI-API-(qBittorrent-5.0)#rename-folder)
+    /// qBittorrent has no endpoint to rename a tag. `new_tag` is created and
+    /// added to every torrent tagged with `tag`, then `tag` is removed from
+    /// them and deleted.
     ///
     /// # Arguments
     ///
-    /// * `hash` - The hash of the torrent
-    /// * `oldPath` - The old path of the torrent
-    /// * `newPath` - The new path to use for the file
+    /// * `tag` - Current name of the tag.
+    /// * `new_tag` - New name of the tag. Must not exist yet.
     ///
     /// # Example
     ///
@@ -2085,24 +2720,465 @@ impl super::Api {
     ///         .await
     ///         .unwrap();
     ///
-    ///     let result = client.rename_folder("hash", "old/folder", "new/folder").await;
+    ///     let result = client.rename_tag("linux", "distros").await;
     ///
     ///     assert!(result.is_ok());
     /// }
     /// ```
-    pub async fn rename_folder(
-        &self,
-        hash: &str,
-        old_path: &str,
-        new_path: &str,
-    ) -> Result<(), Error> {
-        let form = multipart::Form::new()
-            .text("hash", hash.to_string())
-            .text("oldPath", old_path.to_string())
-            .text("newPath", new_path.to_string());
+    pub async fn rename_tag(&self, tag: &str, new_tag: &str) -> Result<(), Error> {
+        let tags = self.tags().await?;
 
-        self._post("torrents/renameFolder")
-            .await?
+        if !tags.iter().any(|t| t == tag) {
+            return Err(Error::InvalidRequest(format!("Unknown tag {:?}", tag)));
+        }
+        if tags.iter().any(|t| t == new_tag) {
+            return Err(Error::InvalidRequest(format!(
+                "Tag {:?} already exists",
+                new_tag
+            )));
+        }
+
+        self.create_tags(vec![new_tag]).await?;
+
+        let params = TorrentListParams {
+            tag: Some(tag.to_string()),
+            ..Default::default()
+        };
+        let hashes: Vec<InfoHash> = self
+            .torrents(Some(params))
+            .await?
+            .into_iter()
+            .filter(|torrent| torrent.tag_list().iter().any(|t| t == tag))
+            .map(|torrent| torrent.hash)
+            .collect();
+
+        if !hashes.is_empty() {
+            self.add_tags(hashes.clone(), vec![new_tag]).await?;
+            self.remove_tags(hashes, vec![tag]).await?;
+        }
+
+        self.delete_tags(vec![tag]).await
+    }
+
+    /// Get how much every tag is used
+    ///
+    /// Returns the number of torrents and their total size for every tag,
+    /// including the tags no torrent uses.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use qbit::{Api, Credentials};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let credentials = Credentials::new("username", "password");
+    ///     let client = Api::new_login("url", credentials)
+    ///         .await
+    ///         .unwrap();
+    ///
+    ///     let usage = client.tag_usage().await.unwrap();
+    ///
+    ///     for (tag, usage) in usage {
+    ///         println!("{}: {} torrents, {}", tag, usage.torrents, usage.size);
+    ///     }
+    /// }
+    /// ```
+    pub async fn tag_usage(&self) -> Result<HashMap<String, TagUsage>, Error> {
+        let tags = self.tags().await?;
+        let torrents = self.torrents(None).await?;
+
+        Ok(TagUsage::collect(tags, &torrents))
+    }
+
+    /// Set automatic torrent management
+    ///
+    /// [official documentation](https://github.com/qbittorrent/qBittorrent/wiki/WebUI-API-(qBittorrent-5.0)#set-automatic-torrent-management)
+    ///
+    /// # Arguments
+    ///
+    /// * `selector` - The torrents you want to set automatic torrent management of.
+    /// * `enable`
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use qbit::{Api, Credentials};
+    /// use qbit::parameters::TorrentSelector;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let credentials = Credentials::new("username", "password");
+    ///     let client = Api::new_login("url", credentials)
+    ///         .await
+    ///         .unwrap();
+    ///
+    ///     let result = client.set_automatic_torrent_management(TorrentSelector::All, true).await;
+    ///
+    ///     assert!(result.is_ok());
+    /// }
+    /// ```
+    pub async fn set_automatic_torrent_management(
+        &self,
+        selector: impl Into<TorrentSelector>,
+        enable: bool,
+    ) -> Result<(), Error> {
+        self.post_selected("torrents/setAutoManagement", selector.into(), |form| {
+            form.text("enable", enable.to_string())
+        })
+        .await
+    }
+
+    /// Toggle sequential download
+    ///
+    /// [official documentation](https://github.com/qbittorrent/qBittorrent/wiki/WebUI-API-(qBittorrent-5.0)#toggle-sequential-download)
+    ///
+    /// Flips the current state of every selected torrent. See
+    /// [`Api::set_sequential_download`](crate::Api::set_sequential_download)
+    /// to set a state instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `selector` - The torrents you want to toggle sequential download for.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use qbit::{Api, Credentials};
+    /// use qbit::parameters::TorrentSelector;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let credentials = Credentials::new("username", "password");
+    ///     let client = Api::new_login("url", credentials)
+    ///         .await
+    ///         .unwrap();
+    ///
+    ///     let result = client.toggle_sequential_download(TorrentSelector::All).await;
+    ///
+    ///     assert!(result.is_ok());
+    /// }
+    /// ```
+    pub async fn toggle_sequential_download(
+        &self,
+        selector: impl Into<TorrentSelector>,
+    ) -> Result<(), Error> {
+        self.post_selected(
+            "torrents/toggleSequentialDownload",
+            selector.into(),
+            |form| form,
+        )
+        .await
+    }
+
+    /// Set sequential download
+    ///
+    /// Reads the state of the selected torrents and toggles sequential
+    /// download for the ones not already in the wanted state, as the server
+    /// only has a toggle. Sends nothing if every torrent is in that state.
+    ///
+    /// # Arguments
+    ///
+    /// * `selector` - The torrents you want to set sequential download for.
+    /// * `enable` - Whether to download pieces in order.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use qbit::{Api, Credentials};
+    /// use qbit::models::InfoHash;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let credentials = Credentials::new("username", "password");
+    ///     let client = Api::new_login("url", credentials)
+    ///         .await
+    ///         .unwrap();
+    ///
+    ///     let hash = InfoHash::new("8c212779b4abde7c6bc608063a0d008b7e40ce32").unwrap();
+    ///     let result = client.set_sequential_download(&hash, true).await;
+    ///
+    ///     assert!(result.is_ok());
+    /// }
+    /// ```
+    pub async fn set_sequential_download(
+        &self,
+        selector: impl Into<TorrentSelector>,
+        enable: bool,
+    ) -> Result<(), Error> {
+        let hashes: Vec<InfoHash> = self
+            .selected_torrents(selector.into())
+            .await?
+            .into_iter()
+            .filter(|torrent| torrent.seq_dl != enable)
+            .map(|torrent| torrent.hash)
+            .collect();
+        if hashes.is_empty() {
+            return Ok(());
+        }
+
+        self.toggle_sequential_download(hashes).await
+    }
+
+    /// Toggle first/last piece priority
+    ///
+    /// [official documentation](https://github.com/qbittorrent/qBittorrent/wiki/WebUI-API-(qBittorrent-5.0)#set-firstlast-piece-priority)
+    ///
+    /// Flips the current state of every selected torrent. See
+    /// [`Api::set_first_last_priority`](crate::Api::set_first_last_priority)
+    /// to set a state instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `selector` - The torrents you want to toggle first/last piece priority for.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use qbit::{Api, Credentials};
+    /// use qbit::parameters::TorrentSelector;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let credentials = Credentials::new("username", "password");
+    ///     let client = Api::new_login("url", credentials)
+    ///         .await
+    ///         .unwrap();
+    ///
+    ///     let result = client.toggle_first_last_priority(TorrentSelector::All).await;
+    ///
+    ///     assert!(result.is_ok());
+    /// }
+    /// ```
+    pub async fn toggle_first_last_priority(
+        &self,
+        selector: impl Into<TorrentSelector>,
+    ) -> Result<(), Error> {
+        self.post_selected(
+            "torrents/toggleFirstLastPiecePrio",
+            selector.into(),
+            |form| form,
+        )
+        .await
+    }
+
+    /// Set first/last piece priority
+    ///
+    /// Reads the state of the selected torrents and toggles first/last piece
+    /// priority for the ones not already in the wanted state, as the server
+    /// only has a toggle. Sends nothing if every torrent is in that state.
+    ///
+    /// # Arguments
+    ///
+    /// * `selector` - The torrents you want to set first/last piece priority for.
+    /// * `enable` - Whether to download the first and last pieces of every file first.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use qbit::{Api, Credentials};
+    /// use qbit::models::InfoHash;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let credentials = Credentials::new("username", "password");
+    ///     let client = Api::new_login("url", credentials)
+    ///         .await
+    ///         .unwrap();
+    ///
+    ///     let hash = InfoHash::new("8c212779b4abde7c6bc608063a0d008b7e40ce32").unwrap();
+    ///     let result = client.set_first_last_priority(&hash, true).await;
+    ///
+    ///     assert!(result.is_ok());
+    /// }
+    /// ```
+    pub async fn set_first_last_priority(
+        &self,
+        selector: impl Into<TorrentSelector>,
+        enable: bool,
+    ) -> Result<(), Error> {
+        let hashes: Vec<InfoHash> = self
+            .selected_torrents(selector.into())
+            .await?
+            .into_iter()
+            .filter(|torrent| torrent.f_l_piece_prio != enable)
+            .map(|torrent| torrent.hash)
+            .collect();
+        if hashes.is_empty() {
+            return Ok(());
+        }
+
+        self.toggle_first_last_priority(hashes).await
+    }
+
+    /// Set force start
+    ///
+    /// [official documentation](https://github.com/qbittorrent/qBittorrent/wiki/WebUI-API-(qBittorrent-5.0)#set-force-start)
+    ///
+    /// # Arguments
+    ///
+    /// * `selector` - The torrents you want to set force start of.
+    /// * `enable`
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use qbit::{Api, Credentials};
+    /// use qbit::parameters::TorrentSelector;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let credentials = Credentials::new("username", "password");
+    ///     let client = Api::new_login("url", credentials)
+    ///         .await
+    ///         .unwrap();
+    ///
+    ///     let result = client.set_force_start(TorrentSelector::All, false).await;
+    ///
+    ///     assert!(result.is_ok());
+    /// }
+    /// ```
+    pub async fn set_force_start(
+        &self,
+        selector: impl Into<TorrentSelector>,
+        enable: bool,
+    ) -> Result<(), Error> {
+        self.post_selected("torrents/setForceStart", selector.into(), |form| {
+            form.text("value", enable.to_string())
+        })
+        .await
+    }
+
+    /// Set super seeding
+    ///
+    /// [official documentation](https://github.com/qbittorrent/qBittorrent/wiki/WebUI-API-(qBittorrent-5.0)#set-super-seeding)
+    ///
+    /// # Arguments
+    ///
+    /// * `selector` - The torrents you want to set super seeding of.
+    /// * `enable`
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use qbit::{Api, Credentials};
+    /// use qbit::parameters::TorrentSelector;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let credentials = Credentials::new("username", "password");
+    ///     let client = Api::new_login("url", credentials)
+    ///         .await
+    ///         .unwrap();
+    ///
+    ///     let result = client.set_super_seeding(TorrentSelector::All, false).await;
+    ///
+    ///     assert!(result.is_ok());
+    /// }
+    /// ```
+    pub async fn set_super_seeding(
+        &self,
+        selector: impl Into<TorrentSelector>,
+        enable: bool,
+    ) -> Result<(), Error> {
+        self.post_selected("torrents/setSuperSeeding", selector.into(), |form| {
+            form.text("value", enable.to_string())
+        })
+        .await
+    }
+
+    /// Rename file
+    ///
+    /// [official documentation](https://github.com/qbittorrent/qBittorrent/wiki/WebUI-API-(qBittorrent-5.0)#rename-file)
+    ///
+    /// # Arguments
+    ///
+    /// * `hash` - The hash of the torrent
+    /// * `oldPath` - The old path of the torrent
+    /// * `newPath` - The new path to use for the file
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use qbit::{Api, Credentials};
+    /// use qbit::models::InfoHash;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let credentials = Credentials::new("username", "password");
+    ///     let client = Api::new_login("url", credentials)
+    ///         .await
+    ///         .unwrap();
+    ///
+    ///     let hash = InfoHash::new("8c212779b4abde7c6bc608063a0d008b7e40ce32").unwrap();
+    ///     let result = client.rename_file(&hash, "old/file", "new/file").await;
+    ///
+    ///     assert!(result.is_ok());
+    /// }
+    /// ```
+    pub async fn rename_file(
+        &self,
+        hash: &InfoHash,
+        old_path: &str,
+        new_path: &str,
+    ) -> Result<(), Error> {
+        let form = multipart::Form::new()
+            .text("hash", hash.to_string())
+            .text("oldPath", old_path.to_string())
+            .text("newPath", new_path.to_string());
+
+        self._post("torrents/renameFile")
+            .await?
+            .multipart(form)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+
+    /// Rename folder
+    ///
+    /// [official documentation](https://github.com/qbittorrent/qBittorrent/wiki/WebUI-API-(qBittorrent-5.0)#rename-folder)
+    ///
+    /// # Arguments
+    ///
+    /// * `hash` - The hash of the torrent
+    /// * `oldPath` - The old path of the torrent
+    /// * `newPath` - The new path to use for the file
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use qbit::{Api, Credentials};
+    /// use qbit::models::InfoHash;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let credentials = Credentials::new("username", "password");
+    ///     let client = Api::new_login("url", credentials)
+    ///         .await
+    ///         .unwrap();
+    ///
+    ///     let hash = InfoHash::new("8c212779b4abde7c6bc608063a0d008b7e40ce32").unwrap();
+    ///     let result = client.rename_folder(&hash, "old/folder", "new/folder").await;
+    ///
+    ///     assert!(result.is_ok());
+    /// }
+    /// ```
+    pub async fn rename_folder(
+        &self,
+        hash: &InfoHash,
+        old_path: &str,
+        new_path: &str,
+    ) -> Result<(), Error> {
+        let form = multipart::Form::new()
+            .text("hash", hash.to_string())
+            .text("oldPath", old_path.to_string())
+            .text("newPath", new_path.to_string());
+
+        self._post("torrents/renameFolder")
+            .await?
             .multipart(form)
             .send()
             .await?
@@ -2110,4 +3186,549 @@ impl super::Api {
 
         Ok(())
     }
+
+    /// Get the files of a torrent as a [`FileTree`]
+    ///
+    /// Same as [`Api::files`](crate::Api::files), with the files grouped by
+    /// folder.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash` - The hash of the torrent you want to get the files of.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::ParseError`] if two files have clashing paths, see
+    /// [`FileTree::new`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use qbit::{Api, Credentials};
+    /// use qbit::models::{FilePriority, InfoHash};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let credentials = Credentials::new("username", "password");
+    ///     let client = Api::new_login("url", credentials)
+    ///         .await
+    ///         .unwrap();
+    ///
+    ///     let hash = InfoHash::new("8c212779b4abde7c6bc608063a0d008b7e40ce32").unwrap();
+    ///     let tree = client.file_tree(&hash).await.unwrap();
+    ///
+    ///     for (name, node) in &tree.root().children {
+    ///         let summary = node.summary();
+    ///         println!("{}: {} files, {:.1}%", name, summary.files, summary.progress * 100.0);
+    ///     }
+    /// }
+    /// ```
+    pub async fn file_tree(&self, hash: &InfoHash) -> Result<FileTree, Error> {
+        FileTree::new(self.files(hash, None).await?)
+    }
+
+    /// Set the priority of a file, or of every file of a folder
+    ///
+    /// Sends one [`Api::set_file_priority`](crate::Api::set_file_priority)
+    /// request for the files under `node`.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash` - The hash of the torrent.
+    /// * `node` - File or folder of the [`FileTree`] of the torrent.
+    /// * `priority` - The new priority.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use qbit::{Api, Credentials};
+    /// use qbit::models::{FilePriority, InfoHash};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let credentials = Credentials::new("username", "password");
+    ///     let client = Api::new_login("url", credentials)
+    ///         .await
+    ///         .unwrap();
+    ///
+    ///     let hash = InfoHash::new("8c212779b4abde7c6bc608063a0d008b7e40ce32").unwrap();
+    ///     let tree = client.file_tree(&hash).await.unwrap();
+    ///
+    ///     if let Some(extras) = tree.get("Movie/Extras") {
+    ///         client
+    ///             .set_node_priority(&hash, extras, FilePriority::DoNotDownload)
+    ///             .await
+    ///             .unwrap();
+    ///     }
+    /// }
+    /// ```
+    pub async fn set_node_priority(
+        &self,
+        hash: &InfoHash,
+        node: &FileTreeNode,
+        priority: FilePriority,
+    ) -> Result<(), Error> {
+        let indexes = node.indexes();
+        if indexes.is_empty() {
+            return Ok(());
+        }
+
+        self.set_file_priority(hash, indexes, priority).await
+    }
+
+    /// Rename or move a file or folder
+    ///
+    /// Uses [`Api::rename_folder`](crate::Api::rename_folder) for folders and
+    /// [`Api::rename_file`](crate::Api::rename_file) for files.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash` - The hash of the torrent.
+    /// * `node` - File or folder of the [`FileTree`] of the torrent.
+    /// * `new_path` - New `/` separated path of the file or folder.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use qbit::{Api, Credentials};
+    /// use qbit::models::{FilePriority, InfoHash};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let credentials = Credentials::new("username", "password");
+    ///     let client = Api::new_login("url", credentials)
+    ///         .await
+    ///         .unwrap();
+    ///
+    ///     let hash = InfoHash::new("8c212779b4abde7c6bc608063a0d008b7e40ce32").unwrap();
+    ///     let tree = client.file_tree(&hash).await.unwrap();
+    ///
+    ///     if let Some(subs) = tree.get("Movie/Subs") {
+    ///         client.rename_node(&hash, subs, "Movie/Subtitles").await.unwrap();
+    ///     }
+    /// }
+    /// ```
+    pub async fn rename_node(
+        &self,
+        hash: &InfoHash,
+        node: &FileTreeNode,
+        new_path: &str,
+    ) -> Result<(), Error> {
+        if node.is_folder() {
+            self.rename_folder(hash, node.path(), new_path).await
+        } else {
+            self.rename_file(hash, node.path(), new_path).await
+        }
+    }
+
+    /// Select the files of a torrent to download
+    ///
+    /// Evaluates `selection` against the files of the torrent, then sets the
+    /// priority of the selected files and skips the others with
+    /// [`FilePriority::DoNotDownload`]. Only files whose priority changes are
+    /// sent, in at most two [`Api::set_file_priority`](crate::Api::set_file_priority)
+    /// requests.
+    ///
+    /// Torrents added from a magnet link have no files until their metadata
+    /// is downloaded, and a torrent that was just added may not be listed
+    /// yet; the files are checked every `selection.interval` until they show
+    /// up. Without `selection.timeout`, an unknown torrent is given up on
+    /// after 10 seconds.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash` - The hash of the torrent.
+    /// * `selection` - Rules picking the files to download.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::InvalidRequest`] if a pattern is invalid,
+    /// [`Error::Timeout`] if the metadata did not arrive within
+    /// `selection.timeout`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    ///
+    /// use qbit::{Api, Credentials};
+    /// use qbit::models::{Bytes, InfoHash};
+    /// use qbit::parameters::FileSelectionBuilder;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let credentials = Credentials::new("username", "password");
+    ///     let client = Api::new_login("url", credentials)
+    ///         .await
+    ///         .unwrap();
+    ///
+    ///     let hash = InfoHash::new("8c212779b4abde7c6bc608063a0d008b7e40ce32").unwrap();
+    ///     let selection = FileSelectionBuilder::default()
+    ///         .include(vec![String::from("*.mkv")])
+    ///         .exclude(vec![String::from("*sample*")])
+    ///         .ignore_case(true)
+    ///         .min_size(Bytes::from_mib(100))
+    ///         .timeout(Duration::from_secs(300))
+    ///         .build()
+    ///         .unwrap();
+    ///     let plan = client.select_files(&hash, &selection).await.unwrap();
+    ///
+    ///     println!("Downloading {} files, skipping {}", plan.selected.len(), plan.skipped.len());
+    /// }
+    /// ```
+    pub async fn select_files(
+        &self,
+        hash: &InfoHash,
+        selection: &FileSelection,
+    ) -> Result<FileSelectionPlan, Error> {
+        selection.validate()?;
+
+        let start = tokio::time::Instant::now();
+        let wait = async {
+            loop {
+                match self.files(hash, None).await {
+                    Ok(files) if !files.is_empty() => return Ok::<_, Error>(files),
+                    Ok(_) => {}
+                    // A torrent that was just added is not listed right away.
+                    // Without a timeout, give up once it should have shown up.
+                    Err(Error::ReqwestError(e))
+                        if e.status() == Some(StatusCode::NOT_FOUND)
+                            && (selection.timeout.is_some()
+                                || start.elapsed() < CONFIRM_TIMEOUT) => {}
+                    Err(e) => return Err(e),
+                }
+
+                tokio::time::sleep(selection.interval).await;
+            }
+        };
+        let files = match selection.timeout {
+            Some(timeout) => tokio::time::timeout(timeout, wait).await.map_err(|_| {
+                Error::Timeout(format!(
+                    "Torrent {} has no metadata after {:?}",
+                    hash, timeout
+                ))
+            })??,
+            None => wait.await?,
+        };
+
+        let plan = selection.plan(&files)?;
+        for (priority, indexes) in &plan.changes {
+            self.set_file_priority(hash, indexes.clone(), *priority)
+                .await?;
+        }
+
+        Ok(plan)
+    }
+
+    /// Rename several files of a torrent
+    ///
+    /// Plans the renames of `rename` over the files of the torrent, then
+    /// sends them one by one with [`Api::rename_file`](crate::Api::rename_file),
+    /// in an order where no file is renamed to a path still in use. With
+    /// [`BatchRename::dry_run`] set, only the plan is returned; its `Display`
+    /// lists the renames and collisions.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash` - The hash of the torrent.
+    /// * `rename` - Pattern and template of the new names.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::InvalidRequest`] if the pattern or template is invalid, or if
+    /// renames collide with each other or with other files. Nothing is
+    /// renamed in that case.
+    ///
+    /// [`Error::RenameFailed`] if a rename fails, with the renames applied
+    /// before it. A rename to a path freed by a previous one waits until the
+    /// file list shows the previous rename.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use qbit::{Api, Credentials};
+    /// use qbit::models::InfoHash;
+    /// use qbit::parameters::BatchRenameBuilder;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let credentials = Credentials::new("username", "password");
+    ///     let client = Api::new_login("url", credentials)
+    ///         .await
+    ///         .unwrap();
+    ///
+    ///     let hash = InfoHash::new("8c212779b4abde7c6bc608063a0d008b7e40ce32").unwrap();
+    ///     let rename = BatchRenameBuilder::default()
+    ///         .pattern(r"(?i)^(?<show>.+)\.s(?<season>\d+)e(?<episode>\d+).*$")
+    ///         .template("{show} - S{season:02}E{episode:02}.{ext}")
+    ///         .dry_run(true)
+    ///         .build()
+    ///         .unwrap();
+    ///     let plan = client.batch_rename(&hash, &rename).await.unwrap();
+    ///
+    ///     print!("{}", plan);
+    /// }
+    /// ```
+    pub async fn batch_rename(
+        &self,
+        hash: &InfoHash,
+        rename: &BatchRename,
+    ) -> Result<RenamePlan, Error> {
+        rename.validate()?;
+
+        let files = self.files(hash, None).await?;
+        let plan = rename.plan(&files)?;
+
+        if rename.dry_run {
+            return Ok(plan);
+        }
+        if !plan.is_valid() {
+            return Err(Error::InvalidRequest(format!(
+                "Renames collide with other files:\n{}",
+                plan
+            )));
+        }
+
+        // Paths freed by renames the file list may not show yet
+        let mut freed = HashSet::new();
+        for (applied, step) in plan.steps.iter().enumerate() {
+            let result = async {
+                if freed.remove(&step.new_path) {
+                    self.wait_for_rename(hash, &step.new_path).await?;
+                }
+                self.rename_file(hash, &step.old_path, &step.new_path).await
+            }
+            .await;
+
+            if let Err(e) = result {
+                return Err(Error::RenameFailed(RenameError {
+                    applied: plan.steps[..applied].to_vec(),
+                    error: Box::new(e),
+                }));
+            }
+            freed.insert(step.old_path.clone());
+        }
+
+        Ok(plan)
+    }
+
+    /// Waits until no file of torrent `hash` has `path`, after it was
+    /// renamed away.
+    async fn wait_for_rename(&self, hash: &InfoHash, path: &str) -> Result<(), Error> {
+        let wait = async {
+            loop {
+                let files = self.files(hash, None).await?;
+                if files.iter().all(|file| file.name != path) {
+                    return Ok(());
+                }
+
+                tokio::time::sleep(CONFIRM_INTERVAL).await;
+            }
+        };
+
+        tokio::time::timeout(CONFIRM_TIMEOUT, wait)
+            .await
+            .map_err(|_| Error::Timeout(format!("{} was not renamed in time", path)))?
+    }
+
+    /// Prepare a file for streaming
+    ///
+    /// Gives the file [`FilePriority::Maximal`] priority, lowers the other
+    /// files of the torrent and enables sequential download and first/last
+    /// piece priority, so the file can be played while it downloads.
+    ///
+    /// Returns the byte ranges of the file that can already be read. Use
+    /// [`Api::stream_status`](crate::Api::stream_status) to follow the
+    /// progress afterwards.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash` - The hash of the torrent.
+    /// * `index` - Index of the file to stream.
+    /// * `skip_others` - Stop downloading the other files with
+    ///   [`FilePriority::DoNotDownload`] instead of lowering them to
+    ///   [`FilePriority::Normal`]. Files already below that priority are
+    ///   left as they are.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::InvalidRequest`] if the torrent has no file with `index`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use qbit::{Api, Credentials};
+    /// use qbit::models::InfoHash;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let credentials = Credentials::new("username", "password");
+    ///     let client = Api::new_login("url", credentials)
+    ///         .await
+    ///         .unwrap();
+    ///
+    ///     let hash = InfoHash::new("8c212779b4abde7c6bc608063a0d008b7e40ce32").unwrap();
+    ///     let stream = client.stream_file(&hash, 0, false).await.unwrap();
+    ///
+    ///     println!("{} bytes ready to play", stream.readable_prefix());
+    /// }
+    /// ```
+    pub async fn stream_file(
+        &self,
+        hash: &InfoHash,
+        index: i64,
+        skip_others: bool,
+    ) -> Result<FileStream, Error> {
+        let files = self.files(hash, None).await?;
+        let file = find_file(&files, hash, index)?;
+
+        let others = if skip_others {
+            FilePriority::DoNotDownload
+        } else {
+            FilePriority::Normal
+        };
+        let lower: Vec<u64> = files
+            .iter()
+            .filter(|other| other.index != index && other.priority as u8 > others as u8)
+            .map(|other| other.index as u64)
+            .collect();
+        if !lower.is_empty() {
+            self.set_file_priority(hash, lower, others).await?;
+        }
+        if file.priority != FilePriority::Maximal {
+            self.set_file_priority(hash, vec![index as u64], FilePriority::Maximal)
+                .await?;
+        }
+
+        self.set_sequential_download(hash, true).await?;
+        self.set_first_last_priority(hash, true).await?;
+
+        self.file_stream(hash, &files, index).await
+    }
+
+    /// Get the downloaded byte ranges of a file
+    ///
+    /// Reads the piece states of the torrent and maps them to the bytes of
+    /// the file, to tell how much of a streamed file can be played.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash` - The hash of the torrent.
+    /// * `index` - Index of the file.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::InvalidRequest`] if the torrent has no file with `index`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use qbit::{Api, Credentials};
+    /// use qbit::models::InfoHash;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let credentials = Credentials::new("username", "password");
+    ///     let client = Api::new_login("url", credentials)
+    ///         .await
+    ///         .unwrap();
+    ///
+    ///     let hash = InfoHash::new("8c212779b4abde7c6bc608063a0d008b7e40ce32").unwrap();
+    ///     let stream = client.stream_status(&hash, 0).await.unwrap();
+    ///
+    ///     for range in &stream.readable {
+    ///         println!("bytes {} to {}", range.start, range.end);
+    ///     }
+    /// }
+    /// ```
+    pub async fn stream_status(&self, hash: &InfoHash, index: i64) -> Result<FileStream, Error> {
+        let files = self.files(hash, None).await?;
+
+        self.file_stream(hash, &files, index).await
+    }
+
+    /// Downloaded byte ranges of the file with `index` among `files`.
+    async fn file_stream(
+        &self,
+        hash: &InfoHash,
+        files: &[TorrentContent],
+        index: i64,
+    ) -> Result<FileStream, Error> {
+        let file = find_file(files, hash, index)?;
+        let piece_size = self.torrent(hash).await?.piece_size.0.max(0) as u64;
+        let map = self.piece_map(hash).await?;
+
+        let packed: i64 = files
+            .iter()
+            .filter(|other| other.index < index)
+            .map(|other| other.size.0)
+            .sum();
+        let offset = file.offset(packed.max(0) as u64, piece_size);
+
+        Ok(FileStream {
+            index,
+            name: file.name.clone(),
+            size: file.size,
+            readable: map.byte_ranges(piece_size, offset, file.size.0.max(0) as u64),
+        })
+    }
+}
+
+/// Time between two checks of the torrent list when confirming added torrents
+const CONFIRM_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Default time to wait for added torrents to show up in the torrent list
+const CONFIRM_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Adds `step` to the `applied` steps of a category rename or move if it
+/// succeeded, or reports the steps applied so far.
+fn record_step(
+    applied: &mut Vec<CategoryStep>,
+    step: CategoryStep,
+    result: Result<(), Error>,
+) -> Result<(), Error> {
+    match result {
+        Ok(()) => {
+            applied.push(step);
+            Ok(())
+        }
+        Err(e) => Err(Error::CategoryMoveFailed(CategoryMoveError {
+            applied: std::mem::take(applied),
+            error: Box::new(e),
+        })),
+    }
+}
+
+/// True if `a` and `b` are the same path, ignoring the separators used and
+/// a trailing separator.
+fn same_path(a: &str, b: &str) -> bool {
+    let normalize = |path: &str| path.replace('\\', "/").trim_end_matches('/').to_string();
+    normalize(a) == normalize(b)
+}
+
+/// The file with `index` among the files of torrent `hash`.
+fn find_file<'a>(
+    files: &'a [TorrentContent],
+    hash: &InfoHash,
+    index: i64,
+) -> Result<&'a TorrentContent, Error> {
+    files
+        .iter()
+        .find(|file| file.index == index)
+        .ok_or_else(|| Error::InvalidRequest(format!("Torrent {} has no file {}", hash, index)))
+}
+
+/// Builds the form shared by `createCategory` and `editCategory`.
+fn category_form(category: &str, options: CategoryOptions) -> multipart::Form {
+    let form = multipart::Form::new()
+        .text("category", category.to_string())
+        .text("savePath", options.save_path);
+
+    match options.download_path {
+        CategoryDownloadPath::Global => form,
+        CategoryDownloadPath::Disabled => form.text("downloadPathEnabled", "false"),
+        CategoryDownloadPath::Path(path) => form
+            .text("downloadPathEnabled", "true")
+            .text("downloadPath", path),
+    }
 }
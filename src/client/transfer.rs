@@ -1,6 +1,9 @@
 use reqwest::multipart;
 
-use crate::{error::Error, models::TransferInfo};
+use crate::{
+    error::Error,
+    models::{SpeedLimit, TransferInfo},
+};
 
 impl super::Api {
     /// Get global transfer info
@@ -127,14 +130,14 @@ impl super::Api {
     ///     println!("Download limit: {}", limit);
     /// }
     /// ```
-    pub async fn global_download_limit(&self) -> Result<u64, Error> {
+    pub async fn global_download_limit(&self) -> Result<SpeedLimit, Error> {
         let limites = self
             ._get("transfer/downloadLimit")
             .await?
             .send()
             .await?
             .error_for_status()?
-            .json::<u64>()
+            .json::<SpeedLimit>()
             .await?;
 
         Ok(limites)
@@ -146,12 +149,13 @@ impl super::Api {
     ///
     /// # Arguments
     ///
-    /// * `limit` - The global download speed limit to set in bytes/second. `0` if no limit.
+    /// * `limit` - The global download speed limit to set.
     ///
     /// # Example
     ///
     /// ```no_run
     /// use qbit::{Api, Credentials};
+    /// use qbit::models::{BytesPerSecond, SpeedLimit};
     ///
     /// #[tokio::main]
     /// async fn main() {
@@ -160,13 +164,14 @@ impl super::Api {
     ///         .await
     ///         .unwrap();
     ///
-    ///     let result = client.set_global_download_limit(1337).await;
+    ///     let limit = SpeedLimit::Value(BytesPerSecond::from_mib(5));
+    ///     let result = client.set_global_download_limit(limit).await;
     ///
     ///     assert!(result.is_ok());
     /// }
     /// ```
-    pub async fn set_global_download_limit(&self, limit: u64) -> Result<(), Error> {
-        let form = multipart::Form::new().text("limit", limit.to_string());
+    pub async fn set_global_download_limit(&self, limit: SpeedLimit) -> Result<(), Error> {
+        let form = multipart::Form::new().text("limit", limit.to_raw().to_string());
 
         self._post("transfer/setDownloadLimit")
             .await?
@@ -199,14 +204,14 @@ impl super::Api {
     ///     println!("Upload limit: {}", limit);
     /// }
     /// ```
-    pub async fn global_upload_limit(&self) -> Result<u64, Error> {
+    pub async fn global_upload_limit(&self) -> Result<SpeedLimit, Error> {
         let limites = self
             ._get("transfer/uploadLimit")
             .await?
             .send()
             .await?
             .error_for_status()?
-            .json::<u64>()
+            .json::<SpeedLimit>()
             .await?;
 
         Ok(limites)
@@ -218,12 +223,13 @@ impl super::Api {
     ///
     /// # Arguments
     ///
-    /// * `limit` - The global upload speed limit to set in bytes/second. `0` if no limit.
+    /// * `limit` - The global upload speed limit to set.
     ///
     /// # Example
     ///
     /// ```no_run
     /// use qbit::{Api, Credentials};
+    /// use qbit::models::{BytesPerSecond, SpeedLimit};
     ///
     /// #[tokio::main]
     /// async fn main() {
@@ -232,13 +238,14 @@ impl super::Api {
     ///         .await
     ///         .unwrap();
     ///
-    ///     let result = client.set_global_upload_limit(1337).await;
+    ///     let limit = SpeedLimit::Value(BytesPerSecond::from_mib(5));
+    ///     let result = client.set_global_upload_limit(limit).await;
     ///
     ///     assert!(result.is_ok());
     /// }
     /// ```
-    pub async fn set_global_upload_limit(&self, limit: u64) -> Result<(), Error> {
-        let form = multipart::Form::new().text("limit", limit.to_string());
+    pub async fn set_global_upload_limit(&self, limit: SpeedLimit) -> Result<(), Error> {
+        let form = multipart::Form::new().text("limit", limit.to_raw().to_string());
 
         self._post("transfer/setUploadLimit")
             .await?
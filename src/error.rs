@@ -1,3 +1,8 @@
+use crate::{
+    models::{CategoryStep, FileRename},
+    parameters::TorrentSelector,
+};
+
 /// Error that can occur.
 ///
 /// This enum encapsulates various types of errors, including authentication failures,
@@ -18,6 +23,93 @@ pub enum Error {
     SerdeJsonError(serde_json::Error),
     /// HTTP 409 Conflict error.
     Http409(String),
+    /// Error that occurs when reading or writing local files.
+    IoError(std::io::Error),
+    /// Error that occurs when parsing a value from a string fails.
+    ParseError(String),
+    /// Error that occurs when some requests of a bulk operation fail.
+    BulkFailed(BulkError),
+    /// Error that occurs when waiting for the server took too long.
+    Timeout(String),
+    /// Error that occurs when a rename of a batch rename fails.
+    RenameFailed(RenameError),
+    /// Error that occurs when a request of a category rename or move fails.
+    CategoryMoveFailed(CategoryMoveError),
+}
+
+/// Report of a bulk operation split into several requests where at least one
+/// request failed.
+#[derive(Debug)]
+pub struct BulkError {
+    /// Number of requests that were sent
+    pub chunks: usize,
+    /// The requests that failed
+    pub failures: Vec<ChunkFailure>,
+}
+
+/// A failed request of a bulk operation.
+#[derive(Debug)]
+pub struct ChunkFailure {
+    /// The torrents the request was sent for. Can be used to retry them.
+    pub selector: TorrentSelector,
+    /// Why the request failed
+    pub error: Error,
+}
+
+/// Report of a batch rename stopped by a failed rename.
+#[derive(Debug)]
+pub struct RenameError {
+    /// The renames applied before the failure, in order
+    pub applied: Vec<FileRename>,
+    /// Why the next rename failed
+    pub error: Box<Error>,
+}
+
+impl std::fmt::Display for RenameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Rename failed after {} renames: {}",
+            self.applied.len(),
+            self.error
+        )
+    }
+}
+
+/// Report of a category rename or move stopped by a failed request.
+#[derive(Debug)]
+pub struct CategoryMoveError {
+    /// The requests applied before the failure, in order
+    pub applied: Vec<CategoryStep>,
+    /// Why the next request failed
+    pub error: Box<Error>,
+}
+
+impl std::fmt::Display for CategoryMoveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Category move failed after {} steps: {}",
+            self.applied.len(),
+            self.error
+        )
+    }
+}
+
+impl std::fmt::Display for BulkError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} of {} bulk requests failed",
+            self.failures.len(),
+            self.chunks
+        )?;
+        if let Some(failure) = self.failures.first() {
+            write!(f, ", first error: {}", failure.error)?;
+        }
+
+        Ok(())
+    }
 }
 
 impl From<reqwest::Error> for Error {
@@ -32,6 +124,12 @@ impl From<serde_json::Error> for Error {
     }
 }
 
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        Self::IoError(err)
+    }
+}
+
 impl From<url::ParseError> for Error {
     fn from(err: url::ParseError) -> Self {
         Self::UrlParseError(err)
@@ -51,6 +149,12 @@ impl std::fmt::Display for Error {
                 Self::UrlParseError(e) => e.to_string(),
                 Self::SerdeJsonError(e) => e.to_string(),
                 Self::Http409(e) => e.to_string(),
+                Self::IoError(e) => e.to_string(),
+                Self::ParseError(e) => e.to_string(),
+                Self::BulkFailed(e) => e.to_string(),
+                Self::Timeout(e) => e.to_string(),
+                Self::RenameFailed(e) => e.to_string(),
+                Self::CategoryMoveFailed(e) => e.to_string(),
             }
         )
     }
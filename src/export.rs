@@ -0,0 +1,414 @@
+//!
+//! This module provides exporters that turn log messages into lines for a
+//! log pipeline, either as JSON Lines or as RFC 5424 syslog messages.
+//!
+//! # Example
+//!
+//! Forward the main log to a file as JSON Lines
+//! ```no_run
+//! use qbit::{Api, Credentials};
+//! use qbit::export::{ExportFormat, LogExporter};
+//! use qbit::parameters::LogStreamParams;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let credentials = Credentials::new("username", "password");
+//!     let client = Api::new_login("url", credentials)
+//!         .await
+//!         .unwrap();
+//!
+//!     let file = tokio::fs::File::create("qbittorrent.jsonl").await.unwrap();
+//!     let mut exporter = LogExporter::new(file, ExportFormat::JsonLines);
+//!     let stream = client.log_stream(None, LogStreamParams::default());
+//!
+//!     exporter.write_stream(stream).await.unwrap();
+//! }
+//! ```
+//!
+
+use std::fmt::Display;
+
+use derive_builder::Builder;
+use futures_util::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+use crate::{
+    error::Error,
+    models::{LogItem, LogPeers, LogType},
+    utiles::timestamp::to_rfc3339,
+};
+
+/// Syslog severity levels (RFC 5424 section 6.2.1)
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Emergency = 0,
+    Alert = 1,
+    Critical = 2,
+    Error = 3,
+    Warning = 4,
+    Notice = 5,
+    #[serde(rename = "info")]
+    Informational = 6,
+    Debug = 7,
+}
+
+impl From<&LogType> for Severity {
+    fn from(value: &LogType) -> Self {
+        match value {
+            LogType::Normal => Self::Notice,
+            LogType::Info => Self::Informational,
+            LogType::Warning => Self::Warning,
+            LogType::Critical => Self::Critical,
+        }
+    }
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Emergency => "emergency",
+                Self::Alert => "alert",
+                Self::Critical => "critical",
+                Self::Error => "error",
+                Self::Warning => "warning",
+                Self::Notice => "notice",
+                Self::Informational => "info",
+                Self::Debug => "debug",
+            }
+        )
+    }
+}
+
+/// Syslog facilities (RFC 5424 section 6.2.1)
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Facility {
+    Kernel = 0,
+    #[default]
+    User = 1,
+    Mail = 2,
+    Daemon = 3,
+    Auth = 4,
+    Syslog = 5,
+    Lpr = 6,
+    News = 7,
+    Uucp = 8,
+    Cron = 9,
+    AuthPriv = 10,
+    Ftp = 11,
+    Local0 = 16,
+    Local1 = 17,
+    Local2 = 18,
+    Local3 = 19,
+    Local4 = 20,
+    Local5 = 21,
+    Local6 = 22,
+    Local7 = 23,
+}
+
+/// Syslog header fields
+#[derive(Debug, Builder, Clone, Deserialize, Serialize, PartialEq)]
+pub struct SyslogOptions {
+    /// Facility of every message. Defaults to `User`.
+    #[builder(default)]
+    pub facility: Facility,
+    /// Host the messages originate from. `-` if unknown.
+    #[builder(setter(into), default = "String::from(\"-\")")]
+    pub hostname: String,
+    /// Application name. Defaults to `qbittorrent`.
+    #[builder(setter(into), default = "String::from(\"qbittorrent\")")]
+    pub app_name: String,
+}
+
+impl Default for SyslogOptions {
+    fn default() -> Self {
+        Self {
+            facility: Facility::default(),
+            hostname: String::from("-"),
+            app_name: String::from("qbittorrent"),
+        }
+    }
+}
+
+/// Output format of a [`LogExporter`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum ExportFormat {
+    /// One JSON object per line with an RFC 3339 timestamp.
+    #[default]
+    JsonLines,
+    /// One RFC 5424 message per line.
+    Syslog(SyslogOptions),
+}
+
+/// A log message that can be exported.
+///
+/// Implemented for [`LogItem`] and [`LogPeers`].
+pub trait ExportRecord {
+    /// Severity of the message
+    fn severity(&self) -> Severity;
+
+    /// The message as a single JSON line (without the line break)
+    fn to_json_line(&self) -> Result<String, Error>;
+
+    /// The message as a single RFC 5424 line (without the line break)
+    fn to_syslog_line(&self, options: &SyslogOptions) -> String;
+}
+
+#[derive(Serialize)]
+struct JsonLogItem<'a> {
+    id: i64,
+    timestamp: String,
+    severity: Severity,
+    #[serde(rename = "type")]
+    log_type: String,
+    message: &'a str,
+}
+
+#[derive(Serialize)]
+struct JsonLogPeer<'a> {
+    id: i64,
+    timestamp: String,
+    severity: Severity,
+    ip: &'a str,
+    blocked: bool,
+    reason: &'a str,
+}
+
+impl ExportRecord for LogItem {
+    fn severity(&self) -> Severity {
+        Severity::from(&self.log_type)
+    }
+
+    fn to_json_line(&self) -> Result<String, Error> {
+        Ok(serde_json::to_string(&JsonLogItem {
+            id: self.id,
+            timestamp: to_rfc3339(self.timestamp),
+            severity: self.severity(),
+            log_type: self.log_type.to_string(),
+            message: &self.message,
+        })?)
+    }
+
+    fn to_syslog_line(&self, options: &SyslogOptions) -> String {
+        syslog_line(
+            options,
+            self.severity(),
+            self.timestamp,
+            "main",
+            &self.message,
+        )
+    }
+}
+
+impl ExportRecord for LogPeers {
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn to_json_line(&self) -> Result<String, Error> {
+        Ok(serde_json::to_string(&JsonLogPeer {
+            id: self.id,
+            timestamp: to_rfc3339(self.timestamp),
+            severity: self.severity(),
+            ip: &self.ip,
+            blocked: self.blocked,
+            reason: &self.reason,
+        })?)
+    }
+
+    fn to_syslog_line(&self, options: &SyslogOptions) -> String {
+        let action = if self.blocked { "blocked" } else { "banned" };
+        let message = format!("{} was {}: {}", self.ip, action, self.reason);
+
+        syslog_line(options, self.severity(), self.timestamp, "peer", &message)
+    }
+}
+
+/// Builds `<PRI>1 TIMESTAMP HOSTNAME APP-NAME PROCID MSGID SD MSG`.
+fn syslog_line(
+    options: &SyslogOptions,
+    severity: Severity,
+    timestamp: i64,
+    msgid: &str,
+    message: &str,
+) -> String {
+    let priority = options.facility as u8 * 8 + severity as u8;
+    // Keep one message per line.
+    let message = message.replace(['\r', '\n'], " ");
+
+    format!(
+        "<{}>1 {} {} {} - {} - {}",
+        priority,
+        to_rfc3339(timestamp),
+        header_field(&options.hostname, 255),
+        header_field(&options.app_name, 48),
+        msgid,
+        message
+    )
+}
+
+/// Header fields must be printable ASCII without spaces, `-` means empty.
+fn header_field(value: &str, max_len: usize) -> String {
+    let value = value
+        .chars()
+        .filter(|c| c.is_ascii_graphic())
+        .take(max_len)
+        .collect::<String>();
+
+    if value.is_empty() {
+        String::from("-")
+    } else {
+        value
+    }
+}
+
+/// Writes log messages to any [`AsyncWrite`] in the chosen format.
+#[derive(Debug)]
+pub struct LogExporter<W> {
+    writer: W,
+    format: ExportFormat,
+}
+
+impl<W: AsyncWrite + Unpin> LogExporter<W> {
+    /// Creates a new exporter.
+    pub fn new(writer: W, format: ExportFormat) -> Self {
+        Self { writer, format }
+    }
+
+    /// Formats a single message without writing it.
+    pub fn format<T: ExportRecord>(&self, record: &T) -> Result<String, Error> {
+        match &self.format {
+            ExportFormat::JsonLines => record.to_json_line(),
+            ExportFormat::Syslog(options) => Ok(record.to_syslog_line(options)),
+        }
+    }
+
+    /// Writes a single message followed by a line break.
+    pub async fn write<T: ExportRecord>(&mut self, record: &T) -> Result<(), Error> {
+        let mut line = self.format(record)?;
+        line.push('\n');
+        self.writer.write_all(line.as_bytes()).await?;
+
+        Ok(())
+    }
+
+    /// Writes every message of a list and flushes the writer.
+    pub async fn write_all<'a, T, I>(&mut self, records: I) -> Result<(), Error>
+    where
+        T: ExportRecord + 'a,
+        I: IntoIterator<Item = &'a T>,
+    {
+        for record in records {
+            self.write(record).await?;
+        }
+        self.flush().await
+    }
+
+    /// Writes every message of a stream, flushing after each one.
+    ///
+    /// Returns the number of written messages once the stream ends, or the
+    /// first error the stream yields.
+    pub async fn write_stream<T, S>(&mut self, stream: S) -> Result<u64, Error>
+    where
+        T: ExportRecord,
+        S: Stream<Item = Result<T, Error>>,
+    {
+        let mut stream = std::pin::pin!(stream);
+        let mut count = 0;
+
+        while let Some(record) = stream.next().await {
+            self.write(&record?).await?;
+            self.flush().await?;
+            count += 1;
+        }
+
+        Ok(count)
+    }
+
+    /// Flushes the underlying writer.
+    pub async fn flush(&mut self) -> Result<(), Error> {
+        self.writer.flush().await?;
+
+        Ok(())
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item() -> LogItem {
+        LogItem {
+            id: 3,
+            message: "Tracker error\nretrying".to_string(),
+            timestamp: 1_700_000_000,
+            log_type: LogType::Warning,
+        }
+    }
+
+    fn peer() -> LogPeers {
+        LogPeers {
+            id: 7,
+            ip: "10.0.0.1".to_string(),
+            timestamp: 1_700_000_000,
+            blocked: true,
+            reason: "IP filter".to_string(),
+        }
+    }
+
+    #[test]
+    fn item_to_json_line() {
+        assert_eq!(
+            item().to_json_line().unwrap(),
+            r#"{"id":3,"timestamp":"2023-11-14T22:13:20Z","severity":"warning","type":"warning","message":"Tracker error\nretrying"}"#
+        );
+    }
+
+    #[test]
+    fn peer_to_json_line() {
+        assert_eq!(
+            peer().to_json_line().unwrap(),
+            r#"{"id":7,"timestamp":"2023-11-14T22:13:20Z","severity":"warning","ip":"10.0.0.1","blocked":true,"reason":"IP filter"}"#
+        );
+    }
+
+    #[test]
+    fn item_to_syslog_line() {
+        let options = SyslogOptionsBuilder::default()
+            .facility(Facility::Local0)
+            .hostname("nas")
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            item().to_syslog_line(&options),
+            "<132>1 2023-11-14T22:13:20Z nas qbittorrent - main - Tracker error retrying"
+        );
+    }
+
+    #[test]
+    fn peer_to_syslog_line() {
+        assert_eq!(
+            peer().to_syslog_line(&SyslogOptions::default()),
+            "<12>1 2023-11-14T22:13:20Z - qbittorrent - peer - 10.0.0.1 was blocked: IP filter"
+        );
+    }
+
+    #[tokio::test]
+    async fn write_all_lines() {
+        let mut exporter = LogExporter::new(Vec::new(), ExportFormat::JsonLines);
+        exporter.write_all(&[item(), item()]).await.unwrap();
+
+        let output = String::from_utf8(exporter.into_inner()).unwrap();
+        assert_eq!(output.lines().count(), 2);
+    }
+}
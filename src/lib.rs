@@ -29,11 +29,22 @@
 //! }
 //! ```
 //!
+//! # Features
+//!
+//! * `chrono` - Typed time accessors on the models return `chrono::DateTime<Utc>`.
+//! * `time` - Typed time accessors on the models return `time::OffsetDateTime`.
+//!
+//! See [`FromTimestamp`](models::FromTimestamp).
+//!
 
 mod client;
 mod error;
 pub(crate) mod utiles;
 
+/// Log exporters.
+pub mod export;
+/// Torrent metainfo (`.torrent` files).
+pub mod metainfo;
 /// Data object models.
 pub mod models;
 /// Parameter objects.
@@ -42,7 +53,7 @@ pub mod parameters;
 use std::fmt::Display;
 
 pub use client::Api;
-pub use error::Error;
+pub use error::{BulkError, CategoryMoveError, ChunkFailure, Error, RenameError};
 use serde::{Deserialize, Serialize};
 
 /// Login state
@@ -0,0 +1,400 @@
+use std::{collections::BTreeMap, ops::Range};
+
+use crate::error::Error;
+
+/// Bencoded dictionary. Keys are raw byte strings, kept sorted as the
+/// encoding requires.
+pub type Dict = BTreeMap<Vec<u8>, Value>;
+
+/// Maximum nesting of lists and dictionaries accepted by [`decode`]
+const MAX_DEPTH: usize = 256;
+
+/// A bencoded value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    /// Integer (`i42e`)
+    Int(i64),
+    /// Byte string (`4:spam`). Not necessarily UTF-8.
+    Bytes(Vec<u8>),
+    /// List (`l...e`)
+    List(Vec<Value>),
+    /// Dictionary (`d...e`)
+    Dict(Dict),
+}
+
+impl Value {
+    /// The integer, if this is one
+    pub fn as_int(&self) -> Option<i64> {
+        match self {
+            Self::Int(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// The byte string, if this is one
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Self::Bytes(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// The byte string as UTF-8, if this is a valid UTF-8 string
+    pub fn as_str(&self) -> Option<&str> {
+        self.as_bytes()
+            .and_then(|bytes| std::str::from_utf8(bytes).ok())
+    }
+
+    /// The list, if this is one
+    pub fn as_list(&self) -> Option<&[Value]> {
+        match self {
+            Self::List(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// The dictionary, if this is one
+    pub fn as_dict(&self) -> Option<&Dict> {
+        match self {
+            Self::Dict(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Encodes the value.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.encode_into(&mut out);
+        out
+    }
+
+    fn encode_into(&self, out: &mut Vec<u8>) {
+        match self {
+            Self::Int(value) => {
+                out.push(b'i');
+                out.extend_from_slice(value.to_string().as_bytes());
+                out.push(b'e');
+            }
+            Self::Bytes(value) => encode_bytes(value, out),
+            Self::List(values) => {
+                out.push(b'l');
+                for value in values {
+                    value.encode_into(out);
+                }
+                out.push(b'e');
+            }
+            Self::Dict(entries) => {
+                out.push(b'd');
+                for (key, value) in entries {
+                    encode_bytes(key, out);
+                    value.encode_into(out);
+                }
+                out.push(b'e');
+            }
+        }
+    }
+}
+
+impl From<i64> for Value {
+    fn from(value: i64) -> Self {
+        Self::Int(value)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Self::Bytes(value.as_bytes().to_vec())
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Self::Bytes(value.into_bytes())
+    }
+}
+
+impl From<Vec<u8>> for Value {
+    fn from(value: Vec<u8>) -> Self {
+        Self::Bytes(value)
+    }
+}
+
+impl From<Vec<Value>> for Value {
+    fn from(value: Vec<Value>) -> Self {
+        Self::List(value)
+    }
+}
+
+impl From<Dict> for Value {
+    fn from(value: Dict) -> Self {
+        Self::Dict(value)
+    }
+}
+
+fn encode_bytes(bytes: &[u8], out: &mut Vec<u8>) {
+    out.extend_from_slice(bytes.len().to_string().as_bytes());
+    out.push(b':');
+    out.extend_from_slice(bytes);
+}
+
+/// Encodes `value`.
+pub fn encode(value: &Value) -> Vec<u8> {
+    value.to_bytes()
+}
+
+/// Decodes a single value making up all of `data`.
+///
+/// Only the canonical encoding is accepted: integers without leading zeros
+/// or `-0`, and dictionary keys unique and sorted. Encoding a decoded value
+/// therefore gives back exactly `data`, which keeps infohashes intact.
+pub fn decode(data: &[u8]) -> Result<Value, Error> {
+    Decoder::new(data, true).all()
+}
+
+/// Decodes a single value making up all of `data`, also accepting the non
+/// canonical encodings some older programs write: integers and string
+/// lengths with leading zeros, and unsorted or repeated dictionary keys. The
+/// last of repeated keys wins.
+///
+/// Encoding the value does not give back `data` for such input. Use
+/// [`dict_value_span`] to get at the bytes of a value as they are.
+pub fn decode_lenient(data: &[u8]) -> Result<Value, Error> {
+    Decoder::new(data, false).all()
+}
+
+/// Byte range of the value of `key` in the dictionary making up all of
+/// `data`, `None` if there is no such key.
+///
+/// `data` is read as [`decode_lenient`] does. With repeated keys, the range
+/// of the last value is returned.
+pub fn dict_value_span(data: &[u8], key: &[u8]) -> Result<Option<Range<usize>>, Error> {
+    let mut decoder = Decoder::new(data, false);
+    if decoder.data.first() != Some(&b'd') {
+        return Err(decoder.error("expected a dictionary"));
+    }
+    decoder.pos += 1;
+
+    let mut span = None;
+    while decoder.data.get(decoder.pos) != Some(&b'e') {
+        let found = decoder.bytes()? == key;
+        let start = decoder.pos;
+        decoder.value(1)?;
+        if found {
+            span = Some(start..decoder.pos);
+        }
+    }
+    decoder.pos += 1;
+
+    if decoder.pos != data.len() {
+        return Err(decoder.error("trailing data after the value"));
+    }
+
+    Ok(span)
+}
+
+struct Decoder<'a> {
+    data: &'a [u8],
+    pos: usize,
+    /// Only accept the canonical encoding
+    strict: bool,
+}
+
+impl<'a> Decoder<'a> {
+    fn new(data: &'a [u8], strict: bool) -> Self {
+        Self {
+            data,
+            pos: 0,
+            strict,
+        }
+    }
+
+    /// Decodes a value making up all of the data.
+    fn all(&mut self) -> Result<Value, Error> {
+        let value = self.value(0)?;
+
+        if self.pos != self.data.len() {
+            return Err(self.error("trailing data after the value"));
+        }
+
+        Ok(value)
+    }
+
+    fn error(&self, message: &str) -> Error {
+        Error::ParseError(format!("Invalid bencode at byte {}: {}", self.pos, message))
+    }
+
+    fn value(&mut self, depth: usize) -> Result<Value, Error> {
+        if depth > MAX_DEPTH {
+            return Err(self.error("nested too deep"));
+        }
+
+        match self.data.get(self.pos) {
+            Some(b'i') => self.int().map(Value::Int),
+            Some(b'0'..=b'9') => self.bytes().map(|bytes| Value::Bytes(bytes.to_vec())),
+            Some(b'l') => {
+                self.pos += 1;
+                let mut values = Vec::new();
+                while self.data.get(self.pos) != Some(&b'e') {
+                    values.push(self.value(depth + 1)?);
+                }
+                self.pos += 1;
+                Ok(Value::List(values))
+            }
+            Some(b'd') => {
+                self.pos += 1;
+                let mut entries = Dict::new();
+                while self.data.get(self.pos) != Some(&b'e') {
+                    let key = self.bytes()?.to_vec();
+                    if self.strict
+                        && entries
+                            .last_key_value()
+                            .is_some_and(|(last, _)| *last >= key)
+                    {
+                        return Err(self.error("dictionary keys are not sorted or not unique"));
+                    }
+                    let value = self.value(depth + 1)?;
+                    entries.insert(key, value);
+                }
+                self.pos += 1;
+                Ok(Value::Dict(entries))
+            }
+            Some(_) => Err(self.error("unexpected byte")),
+            None => Err(self.error("unexpected end of data")),
+        }
+    }
+
+    /// Digits up to `end`, starting at the current position.
+    fn digits(&mut self, end: u8) -> Result<&str, Error> {
+        let len = self.data[self.pos..]
+            .iter()
+            .position(|b| *b == end)
+            .ok_or_else(|| self.error("unterminated number"))?;
+        let digits = std::str::from_utf8(&self.data[self.pos..self.pos + len])
+            .map_err(|_| self.error("invalid number"))?;
+        self.pos += len + 1;
+
+        Ok(digits)
+    }
+
+    fn int(&mut self) -> Result<i64, Error> {
+        let strict = self.strict;
+        self.pos += 1;
+        let digits = self.digits(b'e')?;
+        let unsigned = digits.strip_prefix('-').unwrap_or(digits);
+
+        if unsigned.is_empty()
+            || !unsigned.bytes().all(|b| b.is_ascii_digit())
+            || (strict && unsigned.starts_with('0') && digits != "0")
+        {
+            return Err(self.error("non canonical integer"));
+        }
+
+        digits
+            .parse()
+            .map_err(|_| self.error("integer out of range"))
+    }
+
+    fn bytes(&mut self) -> Result<&[u8], Error> {
+        if !self.data.get(self.pos).is_some_and(u8::is_ascii_digit) {
+            return Err(self.error("expected a byte string"));
+        }
+
+        let strict = self.strict;
+        let digits = self.digits(b':')?;
+        if !digits.bytes().all(|b| b.is_ascii_digit())
+            || (strict && digits.starts_with('0') && digits != "0")
+        {
+            return Err(self.error("non canonical string length"));
+        }
+        let len: usize = digits
+            .parse()
+            .map_err(|_| self.error("string length out of range"))?;
+
+        let start = self.pos;
+        let end = start
+            .checked_add(len)
+            .filter(|end| *end <= self.data.len())
+            .ok_or_else(|| self.error("string past the end of the data"))?;
+        self.pos = end;
+
+        Ok(&self.data[start..end])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_values() {
+        assert_eq!(decode(b"i-42e").unwrap(), Value::Int(-42));
+        assert_eq!(decode(b"4:spam").unwrap(), Value::from("spam"));
+        assert_eq!(
+            decode(b"l4:spami0ee").unwrap(),
+            Value::List(vec![Value::from("spam"), Value::Int(0)])
+        );
+        assert_eq!(
+            decode(b"d1:ai1e1:bli2eee").unwrap().as_dict().unwrap()[&b"b".to_vec()],
+            Value::List(vec![Value::Int(2)])
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let data = b"d8:announce3:url4:infod6:lengthi12e4:name1:ae1:zl0:ee";
+
+        assert_eq!(encode(&decode(data).unwrap()), data);
+    }
+
+    #[test]
+    fn reject_non_canonical() {
+        for data in [
+            &b"i03e"[..],
+            b"i-0e",
+            b"ie",
+            b"03:abc",
+            b"d1:bi1e1:ai2ee",
+            b"d1:ai1e1:ai2ee",
+            b"i1ei2e",
+            b"l4:spam",
+            b"5:spam",
+            b"di1ei2ee",
+        ] {
+            assert!(decode(data).is_err(), "{:?}", String::from_utf8_lossy(data));
+        }
+    }
+
+    #[test]
+    fn lenient_decoding() {
+        let data = b"d1:bi01e1:a02:xye";
+
+        assert!(decode(data).is_err());
+        let value = decode_lenient(data).unwrap();
+        assert_eq!(value.as_dict().unwrap()[&b"b".to_vec()], Value::Int(1));
+        assert_eq!(value.as_dict().unwrap()[&b"a".to_vec()], Value::from("xy"));
+        assert!(decode_lenient(b"i-e").is_err());
+        assert!(decode_lenient(b"d1:ai1e").is_err());
+    }
+
+    #[test]
+    fn value_span() {
+        let data = b"d1:bi01e4:infod1:zi1e1:ai2ee1:c0:e";
+
+        assert_eq!(
+            dict_value_span(data, b"info")
+                .unwrap()
+                .map(|span| &data[span]),
+            Some(&b"d1:zi1e1:ai2ee"[..])
+        );
+        assert_eq!(dict_value_span(data, b"none").unwrap(), None);
+        assert!(dict_value_span(b"l4:infoe", b"info").is_err());
+        assert!(dict_value_span(b"d4:infodee1:x", b"info").is_err());
+    }
+
+    #[test]
+    fn reject_deep_nesting() {
+        let data = [vec![b'l'; MAX_DEPTH + 2], vec![b'e'; MAX_DEPTH + 2]].concat();
+
+        assert!(decode(&data).is_err());
+    }
+}
@@ -0,0 +1,355 @@
+use std::{fmt::Display, ops::RangeInclusive, str::FromStr};
+
+use url::Url;
+
+use crate::{error::Error, models::InfoHash};
+
+/// Magnet link of a torrent
+///
+/// Parses and builds the parameters used by BitTorrent magnet links:
+///
+/// * `xt` - Infohash, as `urn:btih:` (v1, hex or base32) or `urn:btmh:1220`
+///   (v2). Hybrid torrents have both.
+/// * `dn` - Display name
+/// * `xl` - Size in bytes
+/// * `tr` - Trackers
+/// * `ws` - Web seeds
+/// * `so` - Indices of the files to download (BEP 53)
+/// * `x.pe` - Peers to connect to, as `host:port`
+///
+/// Other parameters are kept as they are. Links are written with the hashes
+/// in lower case hex and every value percent encoded, so equal torrents give
+/// equal links.
+///
+/// # Example
+///
+/// ```
+/// use qbit::metainfo::MagnetLink;
+///
+/// let mut magnet: MagnetLink = "magnet:?xt=urn:btih:C9E15763F722F23E98A29DECDFAE341B98D53056&dn=Cosmos+Laundromat"
+///     .parse()
+///     .unwrap();
+///
+/// magnet.add_tracker("udp://tracker.example.org:6969/announce");
+///
+/// assert_eq!(magnet.display_name.as_deref(), Some("Cosmos Laundromat"));
+/// assert_eq!(
+///     magnet.to_string(),
+///     "magnet:?xt=urn:btih:c9e15763f722f23e98a29decdfae341b98d53056&dn=Cosmos%20Laundromat&tr=udp%3A%2F%2Ftracker.example.org%3A6969%2Fannounce"
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MagnetLink {
+    /// BitTorrent v1 infohash
+    pub info_hash_v1: Option<InfoHash>,
+    /// BitTorrent v2 infohash
+    pub info_hash_v2: Option<InfoHash>,
+    /// Name to show until the metadata is received
+    pub display_name: Option<String>,
+    /// Size of the content in bytes
+    pub length: Option<u64>,
+    /// Tracker URLs
+    pub trackers: Vec<String>,
+    /// Web seed URLs
+    pub web_seeds: Vec<String>,
+    /// Ranges of file indices to download. Empty downloads every file.
+    pub select_only: Vec<RangeInclusive<usize>>,
+    /// Peers to connect to, as `host:port`
+    pub peers: Vec<String>,
+    /// Other parameters, in the order they were found
+    pub extra: Vec<(String, String)>,
+}
+
+impl MagnetLink {
+    /// Magnet link for the given hash, v1 or v2
+    pub fn new(hash: InfoHash) -> Self {
+        let mut magnet = Self::default();
+        match hash.is_v2() {
+            true => magnet.info_hash_v2 = Some(hash),
+            false => magnet.info_hash_v1 = Some(hash),
+        }
+
+        magnet
+    }
+
+    /// Parses and validates a magnet link.
+    pub fn parse(link: &str) -> Result<Self, Error> {
+        let invalid =
+            |message: String| Error::ParseError(format!("Invalid magnet link: {}", message));
+
+        let url = Url::parse(link.trim()).map_err(|error| invalid(error.to_string()))?;
+        if url.scheme() != "magnet" {
+            return Err(invalid(format!("unexpected scheme {:?}", url.scheme())));
+        }
+
+        let mut magnet = Self::default();
+
+        for (key, value) in url.query_pairs() {
+            let value = value.into_owned();
+
+            match key.as_ref() {
+                "xt" if value.to_ascii_lowercase().starts_with("urn:btih:") => {
+                    magnet.info_hash_v1 = Some(InfoHash::from_urn(&value)?);
+                }
+                "xt" if value.to_ascii_lowercase().starts_with("urn:btmh:") => {
+                    magnet.info_hash_v2 = Some(InfoHash::from_urn(&value)?);
+                }
+                "dn" => magnet.display_name = Some(value),
+                "xl" => {
+                    magnet.length = Some(
+                        value
+                            .parse()
+                            .map_err(|_| invalid(format!("invalid length {:?}", value)))?,
+                    );
+                }
+                "tr" | "ws" => {
+                    Url::parse(&value)
+                        .map_err(|_| invalid(format!("invalid {} URL {:?}", key, value)))?;
+                    match key.as_ref() {
+                        "tr" => magnet.trackers.push(value),
+                        _ => magnet.web_seeds.push(value),
+                    }
+                }
+                "so" => magnet.select_only.extend(parse_select_only(&value)?),
+                "x.pe" => {
+                    if !value
+                        .rsplit_once(':')
+                        .is_some_and(|(host, port)| !host.is_empty() && port.parse::<u16>().is_ok())
+                    {
+                        return Err(invalid(format!("invalid peer {:?}", value)));
+                    }
+                    magnet.peers.push(value);
+                }
+                _ => magnet.extra.push((key.into_owned(), value)),
+            }
+        }
+
+        if magnet.info_hash_v1.is_none() && magnet.info_hash_v2.is_none() {
+            return Err(invalid(String::from("missing BitTorrent infohash (xt)")));
+        }
+
+        Ok(magnet)
+    }
+
+    /// Hash qBittorrent identifies the torrent by: the v1 hash if there is
+    /// one, otherwise the v2 hash truncated to 40 characters.
+    pub fn info_hash(&self) -> InfoHash {
+        self.info_hash_v1
+            .clone()
+            .or_else(|| self.info_hash_v2.as_ref().map(InfoHash::truncated))
+            .unwrap_or_default()
+    }
+
+    /// Adds a tracker if the link does not have it yet.
+    pub fn add_tracker(&mut self, url: impl Into<String>) {
+        let url = url.into();
+        if !self.trackers.contains(&url) {
+            self.trackers.push(url);
+        }
+    }
+
+    /// Removes a tracker. Returns true if the link had it.
+    pub fn remove_tracker(&mut self, url: &str) -> bool {
+        let count = self.trackers.len();
+        self.trackers.retain(|tracker| tracker != url);
+
+        count != self.trackers.len()
+    }
+
+    /// Removes every tracker, for example before adding the trackers of an
+    /// indexer.
+    pub fn clear_trackers(&mut self) {
+        self.trackers.clear();
+    }
+}
+
+/// Parses a BEP 53 file selection like `0,2,4-6`.
+fn parse_select_only(value: &str) -> Result<Vec<RangeInclusive<usize>>, Error> {
+    let invalid = || Error::ParseError(format!("Invalid magnet link: invalid so {:?}", value));
+
+    value
+        .split(',')
+        .map(|range| {
+            let (start, end) = range.split_once('-').unwrap_or((range, range));
+            let start: usize = start.trim().parse().map_err(|_| invalid())?;
+            let end: usize = end.trim().parse().map_err(|_| invalid())?;
+
+            match start <= end {
+                true => Ok(start..=end),
+                false => Err(invalid()),
+            }
+        })
+        .collect()
+}
+
+/// Percent encodes everything but the unreserved characters.
+fn encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+impl Display for MagnetLink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut params = Vec::new();
+
+        if let Some(hash) = &self.info_hash_v1 {
+            params.push(format!("xt=urn:btih:{}", hash));
+        }
+        if let Some(hash) = &self.info_hash_v2 {
+            params.push(format!("xt=urn:btmh:1220{}", hash));
+        }
+        if let Some(name) = &self.display_name {
+            params.push(format!("dn={}", encode(name)));
+        }
+        if let Some(length) = self.length {
+            params.push(format!("xl={}", length));
+        }
+        params.extend(
+            self.trackers
+                .iter()
+                .map(|url| format!("tr={}", encode(url))),
+        );
+        params.extend(
+            self.web_seeds
+                .iter()
+                .map(|url| format!("ws={}", encode(url))),
+        );
+        if !self.select_only.is_empty() {
+            let ranges: Vec<String> = self
+                .select_only
+                .iter()
+                .map(|range| match range.start() == range.end() {
+                    true => range.start().to_string(),
+                    false => format!("{}-{}", range.start(), range.end()),
+                })
+                .collect();
+            params.push(format!("so={}", ranges.join(",")));
+        }
+        params.extend(
+            self.peers
+                .iter()
+                .map(|peer| format!("x.pe={}", encode(peer))),
+        );
+        params.extend(
+            self.extra
+                .iter()
+                .map(|(key, value)| format!("{}={}", encode(key), encode(value))),
+        );
+
+        write!(f, "magnet:?{}", params.join("&"))
+    }
+}
+
+impl FromStr for MagnetLink {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl TryFrom<&str> for MagnetLink {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+}
+
+impl From<InfoHash> for MagnetLink {
+    fn from(value: InfoHash) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<MagnetLink> for String {
+    fn from(value: MagnetLink) -> Self {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const V1: &str = "c9e15763f722f23e98a29decdfae341b98d53056";
+    const V2: &str = "caf1e1c30e81cb361b9ee167c4aa64228a7fa4fa9f6105232b28ad099f3a302e";
+
+    #[test]
+    fn parse_every_parameter() {
+        let link = format!(
+            "magnet:?xt=urn:btih:{}&xt=urn:btmh:1220{}&dn=a%20b&xl=42&tr=udp%3A%2F%2Ft%3A1&ws=http://seed/&so=0,2-4&x.pe=10.0.0.1:6881&kt=x",
+            V1, V2
+        );
+        let magnet = MagnetLink::parse(&link).unwrap();
+
+        assert_eq!(magnet.info_hash_v1.as_ref().unwrap(), V1);
+        assert_eq!(magnet.info_hash_v2.as_ref().unwrap(), V2);
+        assert_eq!(magnet.display_name.as_deref(), Some("a b"));
+        assert_eq!(magnet.length, Some(42));
+        assert_eq!(magnet.trackers, vec!["udp://t:1"]);
+        assert_eq!(magnet.web_seeds, vec!["http://seed/"]);
+        assert_eq!(magnet.select_only, vec![0..=0, 2..=4]);
+        assert_eq!(magnet.peers, vec!["10.0.0.1:6881"]);
+        assert_eq!(magnet.extra, vec![("kt".to_string(), "x".to_string())]);
+        assert_eq!(magnet.info_hash(), V1);
+    }
+
+    #[test]
+    fn build_round_trip() {
+        let link = format!(
+            "magnet:?xt=urn:btih:{}&dn=a%20b&xl=42&tr=udp%3A%2F%2Ft%3A1&so=0%2C2-4",
+            V1
+        );
+        let magnet = MagnetLink::parse(&link).unwrap();
+
+        assert_eq!(MagnetLink::parse(&magnet.to_string()).unwrap(), magnet);
+        assert!(magnet.to_string().contains("so=0,2-4"));
+    }
+
+    #[test]
+    fn base32_and_v2_only() {
+        let base32 =
+            MagnetLink::parse("magnet:?xt=urn:btih:ZHQVOY7XELZD5GFCTXWN7LRUDOMNKMCW").unwrap();
+        let v2 = MagnetLink::parse(&format!("magnet:?xt=urn:btmh:1220{}", V2)).unwrap();
+
+        assert_eq!(base32.info_hash(), V1);
+        assert_eq!(v2.info_hash(), &V2[..40]);
+        assert!(base32.to_string().contains(V1));
+    }
+
+    #[test]
+    fn edit_trackers() {
+        let mut magnet = MagnetLink::new(InfoHash::new(V1).unwrap());
+
+        magnet.add_tracker("udp://a:1");
+        magnet.add_tracker("udp://a:1");
+        magnet.add_tracker("udp://b:1");
+        assert_eq!(magnet.trackers.len(), 2);
+        assert!(magnet.remove_tracker("udp://a:1"));
+        assert!(!magnet.remove_tracker("udp://a:1"));
+        assert_eq!(magnet.trackers, vec!["udp://b:1"]);
+    }
+
+    #[test]
+    fn reject_invalid() {
+        for link in [
+            "https://example.org/a.torrent".to_string(),
+            "magnet:?dn=no%20hash".to_string(),
+            "magnet:?xt=urn:btih:1234".to_string(),
+            format!("magnet:?xt=urn:btih:{}&xl=abc", V1),
+            format!("magnet:?xt=urn:btih:{}&tr=not%20a%20url", V1),
+            format!("magnet:?xt=urn:btih:{}&so=3-1", V1),
+            format!("magnet:?xt=urn:btih:{}&x.pe=host", V1),
+        ] {
+            assert!(MagnetLink::parse(&link).is_err(), "{}", link);
+        }
+    }
+}
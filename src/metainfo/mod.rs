@@ -0,0 +1,926 @@
+//!
+//! Reading and writing `.torrent` files.
+//!
+//! [`Metainfo`](crate::metainfo::Metainfo) is the typed content of a `.torrent` file, for BitTorrent v1,
+//! v2 and hybrid torrents. It can be read from an upload before adding it, or
+//! from [`Api::export`](crate::Api::export) and
+//! [`Api::get_task_file`](crate::Api::get_task_file).
+//!
+//! [`MagnetLink`](crate::metainfo::MagnetLink) parses and builds magnet links.
+//!
+//! [`PieceVerifier`](crate::metainfo::PieceVerifier) checks local data against the
+//! piece hashes, without a server side recheck.
+//!
+//! The [`bencode`](crate::metainfo::bencode) module holds the underlying codec.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use qbit::{Api, Credentials};
+//! use qbit::metainfo::Metainfo;
+//! use qbit::models::InfoHash;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let credentials = Credentials::new("username", "password");
+//!     let client = Api::new_login("url", credentials)
+//!         .await
+//!         .unwrap();
+//!
+//!     let hash = InfoHash::new("8c212779b4abde7c6bc608063a0d008b7e40ce32").unwrap();
+//!     let data = client.export(&hash).await.unwrap();
+//!     let metainfo = Metainfo::from_bytes(&data).unwrap();
+//!
+//!     assert_eq!(metainfo.info_hash(), hash);
+//!     for file in metainfo.files() {
+//!         println!("{} ({} bytes)", file.path.join("/"), file.length);
+//!     }
+//! }
+//! ```
+//!
+
+use std::collections::BTreeMap;
+
+use sha1::{Digest, Sha1};
+use sha2::Sha256;
+
+use crate::{
+    error::Error,
+    models::{FromTimestamp, InfoHash},
+    utiles::timestamp,
+};
+
+pub mod bencode;
+mod magnet;
+mod verify;
+
+use bencode::{Dict, Value};
+pub use magnet::*;
+pub use verify::*;
+
+/// Length of a v1 piece hash (SHA-1)
+pub const V1_PIECE_HASH_LEN: usize = 20;
+/// Length of a v2 merkle hash (SHA-256)
+pub const V2_HASH_LEN: usize = 32;
+
+/// Content of a `.torrent` file
+///
+/// Keys the crate does not know about are kept in `extra`, so decoding and
+/// encoding a file gives back the same bytes.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Metainfo {
+    /// Main tracker URL
+    pub announce: Option<String>,
+    /// Tracker tiers, tried in order (BEP 12)
+    pub announce_list: Option<Vec<Vec<String>>>,
+    /// Free form comment
+    pub comment: Option<String>,
+    /// Program that created the torrent
+    pub created_by: Option<String>,
+    /// Time of creation (Unix Epoch)
+    pub creation_date: Option<i64>,
+    /// Encoding of the strings, usually `UTF-8`
+    pub encoding: Option<String>,
+    /// Web seeds (BEP 19)
+    pub url_list: Option<UrlList>,
+    /// Hashes of the piece layer of every v2 file larger than a piece, by
+    /// pieces root
+    pub piece_layers: Option<BTreeMap<Vec<u8>, Vec<u8>>>,
+    /// The info dictionary the infohash is computed from
+    pub info: Info,
+    /// The info dictionary as read from the file.
+    ///
+    /// As long as `info` matches it, the infohashes are computed from these
+    /// bytes and they are written back as they are, so details `info` does
+    /// not keep, such as `private` being `i2e`, do not change the infohash.
+    pub raw_info: Option<Vec<u8>>,
+    /// Other keys of the file
+    pub extra: Dict,
+}
+
+/// Info dictionary of a `.torrent` file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Info {
+    /// Name of the file, or of the root folder for multi file torrents.
+    /// Invalid UTF-8 is replaced, [`Metainfo::raw_info`] keeps the original.
+    pub name: String,
+    /// Number of bytes in a piece
+    pub piece_length: i64,
+    /// Concatenated SHA-1 hashes of the pieces. Only in v1 and hybrid torrents.
+    pub pieces: Option<Vec<u8>>,
+    /// Size of a single file torrent. Only in v1 and hybrid torrents.
+    pub length: Option<i64>,
+    /// Files of a multi file torrent. Only in v1 and hybrid torrents.
+    pub files: Option<Vec<FileEntry>>,
+    /// File tree. Only in v2 and hybrid torrents.
+    pub file_tree: Option<BTreeMap<String, FileNode>>,
+    /// `2` for v2 and hybrid torrents
+    pub meta_version: Option<i64>,
+    /// Only use the trackers of the torrent (BEP 27)
+    pub private: Option<bool>,
+    /// Source tag, used by private trackers to get a unique infohash
+    pub source: Option<String>,
+    /// Other keys of the info dictionary
+    pub extra: Dict,
+}
+
+/// A file of the v1 file list
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileEntry {
+    /// Size in bytes
+    pub length: i64,
+    /// Path components below the root folder. Invalid UTF-8 is replaced, as
+    /// in [`Info::name`].
+    pub path: Vec<String>,
+    /// File attributes (BEP 47), `p` marks padding files
+    pub attr: Option<String>,
+    /// Other keys of the file
+    pub extra: Dict,
+}
+
+/// Node of the v2 file tree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileNode {
+    /// A file
+    File(TreeFile),
+    /// A folder and its content, by name
+    Directory(BTreeMap<String, FileNode>),
+}
+
+/// A file of the v2 file tree
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TreeFile {
+    /// Size in bytes
+    pub length: i64,
+    /// Root of the merkle tree of the file. `None` for empty files.
+    pub pieces_root: Option<Vec<u8>>,
+    /// Other keys of the file
+    pub extra: Dict,
+}
+
+/// Web seeds of a torrent, stored either as a single URL or a list
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UrlList {
+    Single(String),
+    List(Vec<String>),
+}
+
+/// BitTorrent protocol versions a torrent can be used with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetainfoVersion {
+    V1,
+    V2,
+    Hybrid,
+}
+
+/// A file of a torrent, in the order the data is laid out
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MetainfoFile {
+    /// Path components, starting with the torrent name for multi file torrents
+    pub path: Vec<String>,
+    /// Size in bytes
+    pub length: i64,
+    /// Offset of the file in the v1 piece data. For v2 only torrents, where
+    /// every file starts on a piece boundary, the offset it would have in a
+    /// piece aligned layout.
+    pub offset: i64,
+    /// True for padding files (BEP 47), which are not stored on disk
+    pub padding: bool,
+}
+
+impl Metainfo {
+    /// Decodes a `.torrent` file.
+    ///
+    /// Files that are not canonically encoded are accepted, see
+    /// [`bencode::decode_lenient`]. The info dictionary is kept as read, in
+    /// [`Metainfo::raw_info`].
+    pub fn from_bytes(data: &[u8]) -> Result<Self, Error> {
+        let mut metainfo = Self::from_value(&bencode::decode_lenient(data)?)?;
+        metainfo.raw_info =
+            bencode::dict_value_span(data, b"info")?.map(|span| data[span].to_vec());
+
+        Ok(metainfo)
+    }
+
+    /// Reads the metainfo out of a decoded `.torrent` file.
+    pub fn from_value(value: &Value) -> Result<Self, Error> {
+        let mut root = value
+            .as_dict()
+            .cloned()
+            .ok_or_else(|| invalid("root is not a dictionary"))?;
+
+        let info = root
+            .remove(&b"info"[..])
+            .ok_or_else(|| invalid("missing info dictionary"))?;
+
+        let metainfo = Self {
+            announce: take_string(&mut root, "announce")?,
+            announce_list: take(&mut root, "announce-list", |value| {
+                list(value)?.iter().map(string_list).collect()
+            })?,
+            comment: take_string(&mut root, "comment")?,
+            created_by: take_string(&mut root, "created by")?,
+            creation_date: take_int(&mut root, "creation date")?,
+            encoding: take_string(&mut root, "encoding")?,
+            url_list: take(&mut root, "url-list", |value| match value {
+                Value::List(_) => string_list(value).map(UrlList::List),
+                _ => string(value).map(UrlList::Single),
+            })?,
+            piece_layers: take(&mut root, "piece layers", |value| {
+                dict(value)?
+                    .iter()
+                    .map(|(key, value)| Ok((key.clone(), bytes(value)?)))
+                    .collect()
+            })?,
+            info: Info::from_value(&info)?,
+            raw_info: Some(info.to_bytes()),
+            extra: root,
+        };
+
+        Ok(metainfo)
+    }
+
+    /// The `.torrent` file as a bencoded value
+    ///
+    /// A non canonical [`Metainfo::raw_info`] is normalized, use
+    /// [`Metainfo::to_bytes`] to keep it as it is.
+    pub fn to_value(&self) -> Value {
+        let mut root = self.root();
+        let info = self.info_bytes();
+        root.insert(
+            b"info".to_vec(),
+            bencode::decode_lenient(&info).unwrap_or_else(|_| self.info.to_value()),
+        );
+
+        Value::Dict(root)
+    }
+
+    /// Encodes the `.torrent` file.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut info = Some(self.info_bytes());
+        let mut out = vec![b'd'];
+
+        for (key, value) in self.root() {
+            if key.as_slice() > &b"info"[..]
+                && let Some(info) = info.take()
+            {
+                out.extend(Value::from("info").to_bytes());
+                out.extend(info);
+            }
+            out.extend(Value::Bytes(key).to_bytes());
+            out.extend(value.to_bytes());
+        }
+        if let Some(info) = info {
+            out.extend(Value::from("info").to_bytes());
+            out.extend(info);
+        }
+        out.push(b'e');
+
+        out
+    }
+
+    /// The encoded info dictionary the infohashes are computed from:
+    /// [`Metainfo::raw_info`] if `info` still matches it, the encoding of
+    /// `info` otherwise.
+    pub fn info_bytes(&self) -> Vec<u8> {
+        let unchanged = self.raw_info.as_ref().is_some_and(|raw| {
+            bencode::decode_lenient(raw)
+                .and_then(|value| Info::from_value(&value))
+                .is_ok_and(|info| info == self.info)
+        });
+
+        match (&self.raw_info, unchanged) {
+            (Some(raw), true) => raw.clone(),
+            _ => self.info.to_value().to_bytes(),
+        }
+    }
+
+    /// The keys of the file but `info`
+    fn root(&self) -> Dict {
+        let mut root = self.extra.clone();
+
+        put(
+            &mut root,
+            "announce",
+            self.announce.clone().map(Value::from),
+        );
+        put(
+            &mut root,
+            "announce-list",
+            self.announce_list.as_ref().map(|tiers| {
+                Value::List(tiers.iter().map(|tier| string_list_value(tier)).collect())
+            }),
+        );
+        put(&mut root, "comment", self.comment.clone().map(Value::from));
+        put(
+            &mut root,
+            "created by",
+            self.created_by.clone().map(Value::from),
+        );
+        put(
+            &mut root,
+            "creation date",
+            self.creation_date.map(Value::Int),
+        );
+        put(
+            &mut root,
+            "encoding",
+            self.encoding.clone().map(Value::from),
+        );
+        put(
+            &mut root,
+            "url-list",
+            self.url_list.as_ref().map(|urls| match urls {
+                UrlList::Single(url) => Value::from(url.as_str()),
+                UrlList::List(urls) => string_list_value(urls),
+            }),
+        );
+        put(
+            &mut root,
+            "piece layers",
+            self.piece_layers.as_ref().map(|layers| {
+                Value::Dict(
+                    layers
+                        .iter()
+                        .map(|(key, value)| (key.clone(), Value::Bytes(value.clone())))
+                        .collect(),
+                )
+            }),
+        );
+
+        root
+    }
+
+    /// Protocol versions the torrent can be used with
+    pub fn version(&self) -> MetainfoVersion {
+        match (self.info.pieces.is_some(), self.info.is_v2()) {
+            (true, true) => MetainfoVersion::Hybrid,
+            (false, true) => MetainfoVersion::V2,
+            _ => MetainfoVersion::V1,
+        }
+    }
+
+    /// SHA-1 hash of the info dictionary, for v1 and hybrid torrents
+    pub fn info_hash_v1(&self) -> Option<InfoHash> {
+        self.info.pieces.as_ref()?;
+
+        InfoHash::from_bytes(&Sha1::digest(self.info_bytes())).ok()
+    }
+
+    /// SHA-256 hash of the info dictionary, for v2 and hybrid torrents
+    pub fn info_hash_v2(&self) -> Option<InfoHash> {
+        if !self.info.is_v2() {
+            return None;
+        }
+
+        InfoHash::from_bytes(&Sha256::digest(self.info_bytes())).ok()
+    }
+
+    /// Hash qBittorrent identifies the torrent by: the v1 hash for v1 and
+    /// hybrid torrents, the v2 hash truncated to 40 characters for v2 only
+    /// torrents.
+    pub fn info_hash(&self) -> InfoHash {
+        self.info_hash_v1()
+            .or_else(|| self.info_hash_v2().map(|hash| hash.truncated()))
+            .unwrap_or_default()
+    }
+
+    /// Infohash of the `.torrent` file `data`, as [`Metainfo::info_hash`]
+    /// gives it, computed from the info dictionary as it is in `data`.
+    ///
+    /// The rest of the file is not read, so this works for files
+    /// [`Metainfo::from_bytes`] cannot decode.
+    pub fn info_hash_of(data: &[u8]) -> Result<InfoHash, Error> {
+        let span = bencode::dict_value_span(data, b"info")?
+            .ok_or_else(|| invalid("missing info dictionary"))?;
+        let raw = &data[span];
+        let value = bencode::decode_lenient(raw)?;
+        let info = dict(&value)?;
+
+        if info.contains_key(&b"pieces"[..]) {
+            InfoHash::from_bytes(&Sha1::digest(raw))
+        } else if info.get(&b"meta version"[..]).and_then(Value::as_int) == Some(2)
+            && info.contains_key(&b"file tree"[..])
+        {
+            InfoHash::from_bytes(&Sha256::digest(raw)).map(|hash| hash.truncated())
+        } else {
+            Err(invalid("info has neither pieces nor a v2 file tree"))
+        }
+    }
+
+    /// True if the torrent may only use its own trackers
+    pub fn is_private(&self) -> bool {
+        self.info.private.unwrap_or(false)
+    }
+
+    /// Time when the torrent was created
+    pub fn created_at<T: FromTimestamp>(&self) -> Option<T> {
+        self.creation_date
+            .and_then(timestamp::instant)
+            .and_then(T::from_timestamp)
+    }
+
+    /// Every tracker URL, from the tracker tiers or from `announce`
+    pub fn trackers(&self) -> Vec<&str> {
+        match &self.announce_list {
+            Some(tiers) if !tiers.is_empty() => {
+                tiers.iter().flatten().map(String::as_str).collect()
+            }
+            _ => self.announce.as_deref().into_iter().collect(),
+        }
+    }
+
+    /// Web seed URLs
+    pub fn web_seeds(&self) -> Vec<&str> {
+        match &self.url_list {
+            Some(UrlList::Single(url)) => vec![url.as_str()],
+            Some(UrlList::List(urls)) => urls.iter().map(String::as_str).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Number of pieces
+    pub fn piece_count(&self) -> usize {
+        match &self.info.pieces {
+            Some(pieces) => pieces.len() / V1_PIECE_HASH_LEN,
+            None => self
+                .files()
+                .iter()
+                .map(|file| pieces_for(file.length, self.info.piece_length))
+                .sum(),
+        }
+    }
+
+    /// SHA-1 hash of every piece, for v1 and hybrid torrents
+    pub fn piece_hashes(&self) -> impl Iterator<Item = &[u8]> {
+        self.info
+            .pieces
+            .iter()
+            .flat_map(|pieces| pieces.chunks_exact(V1_PIECE_HASH_LEN))
+    }
+
+    /// Size of the content, padding files excluded
+    pub fn total_size(&self) -> i64 {
+        self.files()
+            .iter()
+            .filter(|file| !file.padding)
+            .map(|file| file.length)
+            .sum()
+    }
+
+    /// The files of the torrent, padding files included, in data order
+    pub fn files(&self) -> Vec<MetainfoFile> {
+        let info = &self.info;
+        let mut files = Vec::new();
+
+        if let Some(length) = info.length {
+            files.push(MetainfoFile {
+                path: vec![info.name.clone()],
+                length,
+                offset: 0,
+                padding: false,
+            });
+        } else if let Some(entries) = &info.files {
+            let mut offset = 0;
+            for entry in entries {
+                files.push(MetainfoFile {
+                    path: std::iter::once(info.name.clone())
+                        .chain(entry.path.iter().cloned())
+                        .collect(),
+                    length: entry.length,
+                    offset,
+                    padding: entry.is_padding(),
+                });
+                offset += entry.length;
+            }
+        } else if let Some(tree) = &info.file_tree {
+            let mut offset = 0;
+            let single = tree.len() == 1 && matches!(tree.values().next(), Some(FileNode::File(_)));
+            let root = if single {
+                Vec::new()
+            } else {
+                vec![info.name.clone()]
+            };
+
+            walk_tree(tree, root, &mut |path, file| {
+                files.push(MetainfoFile {
+                    path,
+                    length: file.length,
+                    offset,
+                    padding: false,
+                });
+                offset += pieces_for(file.length, info.piece_length) as i64 * info.piece_length;
+            });
+        }
+
+        files
+    }
+}
+
+impl Info {
+    /// Reads the info dictionary out of its decoded value.
+    pub fn from_value(value: &Value) -> Result<Self, Error> {
+        let mut info = dict(value)?.clone();
+
+        let info = Self {
+            name: take(&mut info, "name", lossy_string)?.ok_or_else(|| invalid("missing name"))?,
+            piece_length: take_int(&mut info, "piece length")?
+                .filter(|length| *length > 0)
+                .ok_or_else(|| invalid("missing or invalid piece length"))?,
+            pieces: take(&mut info, "pieces", bytes)?,
+            length: take_int(&mut info, "length")?,
+            files: take(&mut info, "files", |value| {
+                list(value)?.iter().map(FileEntry::from_value).collect()
+            })?,
+            file_tree: take(&mut info, "file tree", tree_from_value)?,
+            meta_version: take_int(&mut info, "meta version")?,
+            private: take_int(&mut info, "private")?.map(|private| private != 0),
+            source: take_string(&mut info, "source")?,
+            extra: info,
+        };
+
+        if info
+            .pieces
+            .as_ref()
+            .is_some_and(|pieces| pieces.len() % V1_PIECE_HASH_LEN != 0)
+        {
+            return Err(invalid("pieces is not a list of SHA-1 hashes"));
+        }
+        if info.pieces.is_some() && info.length.is_none() && info.files.is_none() {
+            return Err(invalid("v1 info has neither length nor files"));
+        }
+        if info.pieces.is_none() && !info.is_v2() {
+            return Err(invalid("info has neither pieces nor a v2 file tree"));
+        }
+
+        Ok(info)
+    }
+
+    /// The info dictionary as a bencoded value
+    pub fn to_value(&self) -> Value {
+        let mut info = self.extra.clone();
+
+        info.insert(b"name".to_vec(), Value::from(self.name.as_str()));
+        info.insert(b"piece length".to_vec(), Value::Int(self.piece_length));
+        put(&mut info, "pieces", self.pieces.clone().map(Value::Bytes));
+        put(&mut info, "length", self.length.map(Value::Int));
+        put(
+            &mut info,
+            "files",
+            self.files
+                .as_ref()
+                .map(|files| Value::List(files.iter().map(FileEntry::to_value).collect())),
+        );
+        put(
+            &mut info,
+            "file tree",
+            self.file_tree.as_ref().map(tree_to_value),
+        );
+        put(&mut info, "meta version", self.meta_version.map(Value::Int));
+        put(
+            &mut info,
+            "private",
+            self.private.map(|private| Value::Int(private as i64)),
+        );
+        put(&mut info, "source", self.source.clone().map(Value::from));
+
+        Value::Dict(info)
+    }
+
+    /// True for v2 and hybrid torrents
+    pub fn is_v2(&self) -> bool {
+        self.meta_version == Some(2) && self.file_tree.is_some()
+    }
+}
+
+impl FileEntry {
+    fn from_value(value: &Value) -> Result<Self, Error> {
+        let mut file = dict(value)?.clone();
+
+        Ok(Self {
+            length: take_int(&mut file, "length")?.ok_or_else(|| invalid("file without length"))?,
+            path: take(&mut file, "path", |value| {
+                list(value)?.iter().map(lossy_string).collect()
+            })?
+            .ok_or_else(|| invalid("file without path"))?,
+            attr: take_string(&mut file, "attr")?,
+            extra: file,
+        })
+    }
+
+    fn to_value(&self) -> Value {
+        let mut file = self.extra.clone();
+
+        file.insert(b"length".to_vec(), Value::Int(self.length));
+        file.insert(b"path".to_vec(), string_list_value(&self.path));
+        put(&mut file, "attr", self.attr.clone().map(Value::from));
+
+        Value::Dict(file)
+    }
+
+    /// True for padding files (BEP 47)
+    pub fn is_padding(&self) -> bool {
+        self.attr.as_deref().is_some_and(|attr| attr.contains('p'))
+    }
+}
+
+/// Number of pieces needed for `length` bytes
+fn pieces_for(length: i64, piece_length: i64) -> usize {
+    if length <= 0 || piece_length <= 0 {
+        0
+    } else {
+        ((length + piece_length - 1) / piece_length) as usize
+    }
+}
+
+/// Calls `visit` for every file of `tree`, in tree order.
+fn walk_tree(
+    tree: &BTreeMap<String, FileNode>,
+    path: Vec<String>,
+    visit: &mut impl FnMut(Vec<String>, &TreeFile),
+) {
+    for (name, node) in tree {
+        let mut path = path.clone();
+        path.push(name.clone());
+
+        match node {
+            FileNode::File(file) => visit(path, file),
+            FileNode::Directory(children) => walk_tree(children, path, visit),
+        }
+    }
+}
+
+/// A file is a dictionary with a single empty key holding its properties.
+fn tree_from_value(value: &Value) -> Result<BTreeMap<String, FileNode>, Error> {
+    dict(value)?
+        .iter()
+        .map(|(name, node)| {
+            let name = String::from_utf8(name.clone())
+                .map_err(|_| invalid("file tree name is not UTF-8"))?;
+            let entries = dict(node)?;
+
+            let node = match entries.get(&b""[..]) {
+                Some(properties) => {
+                    let mut properties = dict(properties)?.clone();
+                    FileNode::File(TreeFile {
+                        length: take_int(&mut properties, "length")?
+                            .ok_or_else(|| invalid("file without length"))?,
+                        pieces_root: take(&mut properties, "pieces root", bytes)?,
+                        extra: properties,
+                    })
+                }
+                None => FileNode::Directory(tree_from_value(node)?),
+            };
+
+            Ok((name, node))
+        })
+        .collect()
+}
+
+fn tree_to_value(tree: &BTreeMap<String, FileNode>) -> Value {
+    Value::Dict(
+        tree.iter()
+            .map(|(name, node)| {
+                let value = match node {
+                    FileNode::File(file) => {
+                        let mut properties = file.extra.clone();
+                        properties.insert(b"length".to_vec(), Value::Int(file.length));
+                        put(
+                            &mut properties,
+                            "pieces root",
+                            file.pieces_root.clone().map(Value::Bytes),
+                        );
+                        Value::Dict(Dict::from([(Vec::new(), Value::Dict(properties))]))
+                    }
+                    FileNode::Directory(children) => tree_to_value(children),
+                };
+                (name.as_bytes().to_vec(), value)
+            })
+            .collect(),
+    )
+}
+
+fn invalid(message: &str) -> Error {
+    Error::ParseError(format!("Invalid metainfo: {}", message))
+}
+
+/// Removes `key` from `dict` and converts it with `convert`.
+fn take<T>(
+    dict: &mut Dict,
+    key: &str,
+    convert: impl FnOnce(&Value) -> Result<T, Error>,
+) -> Result<Option<T>, Error> {
+    dict.remove(key.as_bytes())
+        .map(|value| convert(&value).map_err(|error| invalid(&format!("{:?}: {}", key, error))))
+        .transpose()
+}
+
+fn take_string(dict: &mut Dict, key: &str) -> Result<Option<String>, Error> {
+    take(dict, key, string)
+}
+
+fn take_int(dict: &mut Dict, key: &str) -> Result<Option<i64>, Error> {
+    take(dict, key, |value| {
+        value.as_int().ok_or_else(|| invalid("expected an integer"))
+    })
+}
+
+/// Inserts `value` under `key` if there is one.
+fn put(dict: &mut Dict, key: &str, value: Option<Value>) {
+    if let Some(value) = value {
+        dict.insert(key.as_bytes().to_vec(), value);
+    }
+}
+
+fn string(value: &Value) -> Result<String, Error> {
+    value
+        .as_str()
+        .map(String::from)
+        .ok_or_else(|| invalid("expected a UTF-8 string"))
+}
+
+/// Byte string as UTF-8, invalid sequences replaced. Names and paths are
+/// not always UTF-8 in older torrents.
+fn lossy_string(value: &Value) -> Result<String, Error> {
+    value
+        .as_bytes()
+        .map(|bytes| String::from_utf8_lossy(bytes).into_owned())
+        .ok_or_else(|| invalid("expected a byte string"))
+}
+
+fn bytes(value: &Value) -> Result<Vec<u8>, Error> {
+    value
+        .as_bytes()
+        .map(<[u8]>::to_vec)
+        .ok_or_else(|| invalid("expected a byte string"))
+}
+
+fn list(value: &Value) -> Result<&[Value], Error> {
+    value.as_list().ok_or_else(|| invalid("expected a list"))
+}
+
+fn dict(value: &Value) -> Result<&Dict, Error> {
+    value
+        .as_dict()
+        .ok_or_else(|| invalid("expected a dictionary"))
+}
+
+fn string_list(value: &Value) -> Result<Vec<String>, Error> {
+    list(value)?.iter().map(string).collect()
+}
+
+fn string_list_value(strings: &[String]) -> Value {
+    Value::List(strings.iter().map(|s| Value::from(s.as_str())).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const V1_SINGLE: &[u8] = b"d8:announce9:udp://t/a13:announce-listll9:udp://t/ael9:udp://t/bee13:creation datei1700000000e4:infod6:lengthi12e4:name5:a.txt12:piece lengthi16384e6:pieces20:aaaaaaaaaaaaaaaaaaaa7:privatei1e6:source3:abce8:url-list12:http://seed/e";
+
+    fn hybrid() -> Vec<u8> {
+        let root = [b'r'; 32];
+        [
+            &b"d4:infod9:file treed5:a.txtd0:d6:lengthi12e11:pieces root32:"[..],
+            &root,
+            b"eee5:filesld6:lengthi12e4:pathl5:a.txteed4:attr1:p6:lengthi16372e4:pathl4:.pad5:16372eee",
+            b"12:meta versioni2e4:name3:dir12:piece lengthi16384e",
+            b"6:pieces20:aaaaaaaaaaaaaaaaaaaaee",
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn read_v1() {
+        let metainfo = Metainfo::from_bytes(V1_SINGLE).unwrap();
+
+        assert_eq!(metainfo.version(), MetainfoVersion::V1);
+        assert_eq!(metainfo.trackers(), vec!["udp://t/a", "udp://t/b"]);
+        assert_eq!(metainfo.web_seeds(), vec!["http://seed/"]);
+        assert!(metainfo.is_private());
+        assert_eq!(metainfo.info.source.as_deref(), Some("abc"));
+        assert_eq!(metainfo.piece_count(), 1);
+        assert_eq!(metainfo.total_size(), 12);
+        assert_eq!(metainfo.files()[0].path, vec!["a.txt"]);
+        assert!(metainfo.info_hash_v2().is_none());
+    }
+
+    #[test]
+    fn round_trip_is_lossless() {
+        let metainfo = Metainfo::from_bytes(V1_SINGLE).unwrap();
+        assert_eq!(metainfo.to_bytes(), V1_SINGLE);
+
+        let hybrid = hybrid();
+        assert_eq!(Metainfo::from_bytes(&hybrid).unwrap().to_bytes(), hybrid);
+    }
+
+    #[test]
+    fn v1_info_hash() {
+        let metainfo = Metainfo::from_bytes(V1_SINGLE).unwrap();
+        let start = V1_SINGLE.windows(6).position(|w| w == b"4:info").unwrap() + 6;
+        let end = V1_SINGLE.len() - b"8:url-list12:http://seed/e".len();
+
+        assert_eq!(
+            metainfo.info_hash(),
+            InfoHash::from_bytes(&Sha1::digest(&V1_SINGLE[start..end])).unwrap()
+        );
+    }
+
+    #[test]
+    fn read_hybrid() {
+        let metainfo = Metainfo::from_bytes(&hybrid()).unwrap();
+        let files = metainfo.files();
+
+        assert_eq!(metainfo.version(), MetainfoVersion::Hybrid);
+        assert_eq!(metainfo.info_hash(), metainfo.info_hash_v1().unwrap());
+        assert!(metainfo.info_hash_v2().unwrap().is_v2());
+        assert_eq!(files.len(), 2);
+        assert!(files[1].padding);
+        assert_eq!(metainfo.total_size(), 12);
+    }
+
+    #[test]
+    fn v2_only_uses_truncated_hash() {
+        let mut metainfo = Metainfo::from_bytes(&hybrid()).unwrap();
+        metainfo.info.pieces = None;
+        metainfo.info.files = None;
+        let metainfo = Metainfo::from_bytes(&metainfo.to_bytes()).unwrap();
+
+        assert_eq!(metainfo.version(), MetainfoVersion::V2);
+        assert_eq!(
+            metainfo.info_hash(),
+            metainfo.info_hash_v2().unwrap().truncated()
+        );
+        assert_eq!(metainfo.files()[0].path, vec!["a.txt"]);
+        assert_eq!(metainfo.piece_count(), 1);
+    }
+
+    /// SHA-1 of the value of the `info` key of `data`
+    fn raw_hash(data: &[u8]) -> InfoHash {
+        let span = bencode::dict_value_span(data, b"info").unwrap().unwrap();
+        InfoHash::from_bytes(&Sha1::digest(&data[span])).unwrap()
+    }
+
+    #[test]
+    fn private_flag_keeps_its_value() {
+        let data = b"d4:infod6:lengthi1e4:name1:a12:piece lengthi16384e6:pieces20:aaaaaaaaaaaaaaaaaaaa7:privatei2eee";
+        let metainfo = Metainfo::from_bytes(data).unwrap();
+
+        assert!(metainfo.is_private());
+        assert_eq!(metainfo.info_hash(), raw_hash(data));
+        assert_eq!(metainfo.to_bytes(), data);
+    }
+
+    #[test]
+    fn non_utf8_path() {
+        let data = [
+            &b"d4:infod5:filesld6:lengthi1e4:pathl2:\xff\xfeeee4:name3:dir"[..],
+            b"12:piece lengthi16384e6:pieces20:aaaaaaaaaaaaaaaaaaaaee",
+        ]
+        .concat();
+        let metainfo = Metainfo::from_bytes(&data).unwrap();
+
+        assert_eq!(metainfo.files()[0].path, vec!["dir", "\u{fffd}\u{fffd}"]);
+        assert_eq!(metainfo.info_hash(), raw_hash(&data));
+        assert_eq!(metainfo.to_bytes(), data);
+    }
+
+    #[test]
+    fn non_canonical_info() {
+        let data = b"d4:infod4:name1:a6:lengthi01e12:piece lengthi16384e6:pieces20:aaaaaaaaaaaaaaaaaaaae1:x0:e";
+        let mut metainfo = Metainfo::from_bytes(data).unwrap();
+
+        assert_eq!(metainfo.info.length, Some(1));
+        assert_eq!(metainfo.info_hash(), raw_hash(data));
+        assert_eq!(metainfo.to_bytes(), data);
+
+        metainfo.info.name = String::from("b");
+        assert_ne!(metainfo.info_hash(), raw_hash(data));
+    }
+
+    #[test]
+    fn info_hash_without_decoding() {
+        let hybrid = hybrid();
+        assert_eq!(
+            Metainfo::info_hash_of(&hybrid).unwrap(),
+            Metainfo::from_bytes(&hybrid).unwrap().info_hash()
+        );
+
+        // The announce URL is not UTF-8, the file can not be decoded.
+        let data = b"d8:announce1:\xff4:infod6:lengthi1e4:name1:a12:piece lengthi16384e6:pieces20:aaaaaaaaaaaaaaaaaaaaee";
+        assert!(Metainfo::from_bytes(data).is_err());
+        assert_eq!(Metainfo::info_hash_of(data).unwrap(), raw_hash(data));
+
+        assert!(Metainfo::info_hash_of(b"d4:infod4:name1:aee").is_err());
+    }
+
+    #[test]
+    fn reject_invalid() {
+        assert!(Metainfo::from_bytes(b"i1e").is_err());
+        assert!(Metainfo::from_bytes(b"d4:infod4:name1:a12:piece lengthi1eee").is_err());
+        assert!(
+            Metainfo::from_bytes(b"d4:infod4:name1:a12:piece lengthi1e6:pieces3:abcee").is_err()
+        );
+    }
+}
@@ -0,0 +1,485 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+};
+
+use sha1::{Digest, Sha1};
+
+use crate::{
+    error::Error,
+    metainfo::{Metainfo, V1_PIECE_HASH_LEN},
+    models::TorrentContent,
+};
+
+/// Checks local files against the v1 piece hashes of a torrent, without
+/// involving the server.
+///
+/// Built from a [`Metainfo`], or from what the server reports about a
+/// torrent with [`PieceVerifier::from_content`]. See
+/// [`Api::verify_local_data`](crate::Api::verify_local_data) to verify a
+/// torrent of the server.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::path::Path;
+///
+/// use qbit::metainfo::{Metainfo, PieceVerifier};
+///
+/// let data = std::fs::read("debian.torrent").unwrap();
+/// let metainfo = Metainfo::from_bytes(&data).unwrap();
+///
+/// let verifier = PieceVerifier::from_metainfo(&metainfo, Path::new("/mnt/nas/downloads")).unwrap();
+/// let report = verifier.verify(4);
+///
+/// for file in report.bad_files() {
+///     println!("{}: {} bad pieces", file.name, file.bad_pieces.len());
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PieceVerifier {
+    piece_length: u64,
+    hashes: Vec<[u8; V1_PIECE_HASH_LEN]>,
+    files: Vec<VerifyFile>,
+}
+
+/// A file of the data checked by a [`PieceVerifier`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifyFile {
+    /// Index of the file, as used by the server. `None` for padding files.
+    pub index: Option<i64>,
+    /// Name of the file in the torrent
+    pub name: String,
+    /// Local path of the file. `None` for padding files, read as zeros.
+    pub path: Option<PathBuf>,
+    /// Size in bytes
+    pub length: u64,
+    /// Offset of the file in the piece data
+    pub offset: u64,
+    /// Pieces the file is part of
+    pub pieces: RangeInclusive<usize>,
+}
+
+/// Result of a [`PieceVerifier`] run
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VerifyReport {
+    /// Number of pieces checked
+    pub piece_count: usize,
+    /// Pieces whose data does not match their hash, or could not be read
+    pub bad_pieces: Vec<usize>,
+    /// Every file of the torrent, padding files excluded
+    pub files: Vec<FileReport>,
+}
+
+/// Verification result of one file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileReport {
+    /// Index of the file, as used by the server
+    pub index: i64,
+    /// Name of the file in the torrent
+    pub name: String,
+    /// Local path that was read
+    pub path: PathBuf,
+    /// True if the file does not exist or is shorter than expected
+    pub missing: bool,
+    /// Bad pieces the file is part of
+    pub bad_pieces: Vec<usize>,
+}
+
+impl VerifyReport {
+    /// True if every piece matches its hash
+    pub fn is_ok(&self) -> bool {
+        self.bad_pieces.is_empty()
+    }
+
+    /// The files with at least one bad piece
+    pub fn bad_files(&self) -> impl Iterator<Item = &FileReport> {
+        self.files.iter().filter(|file| !file.bad_pieces.is_empty())
+    }
+}
+
+impl PieceVerifier {
+    /// Verifier for the given pieces and files. `files` must be sorted by
+    /// offset and cover the data without gaps.
+    pub fn new(
+        piece_length: u64,
+        hashes: Vec<[u8; V1_PIECE_HASH_LEN]>,
+        files: Vec<VerifyFile>,
+    ) -> Result<Self, Error> {
+        if piece_length == 0 {
+            return Err(Error::InvalidRequest(String::from("Piece length is zero")));
+        }
+
+        let verifier = Self {
+            piece_length,
+            hashes,
+            files,
+        };
+
+        let expected = verifier.data_length().div_ceil(piece_length) as usize;
+        if expected != verifier.hashes.len() {
+            return Err(Error::InvalidRequest(format!(
+                "Files need {} pieces but there are {} piece hashes",
+                expected,
+                verifier.hashes.len()
+            )));
+        }
+
+        Ok(verifier)
+    }
+
+    /// Verifier for the content of `metainfo` saved in `save_path`.
+    ///
+    /// Only v1 and hybrid torrents have the piece hashes needed.
+    pub fn from_metainfo(metainfo: &Metainfo, save_path: &Path) -> Result<Self, Error> {
+        let piece_length = metainfo.info.piece_length as u64;
+        let hashes = metainfo
+            .piece_hashes()
+            .map(|hash| hash.try_into().unwrap_or_default())
+            .collect::<Vec<_>>();
+
+        if metainfo.info.pieces.is_none() {
+            return Err(Error::InvalidRequest(String::from(
+                "v2 only torrents have no v1 piece hashes",
+            )));
+        }
+
+        let mut index = 0;
+        let files = metainfo
+            .files()
+            .into_iter()
+            .map(|file| {
+                let name = file.path.join("/");
+                let length = file.length as u64;
+                let offset = file.offset as u64;
+                let verify = VerifyFile {
+                    index: (!file.padding).then_some(index),
+                    path: (!file.padding).then(|| local_path(save_path, &name)),
+                    name,
+                    length,
+                    offset,
+                    pieces: piece_range(offset, length, piece_length),
+                };
+                if !file.padding {
+                    index += 1;
+                }
+                verify
+            })
+            .collect();
+
+        Self::new(piece_length, hashes, files)
+    }
+
+    /// Verifier for the files reported by
+    /// [`Api::files`](crate::Api::files), saved in `save_path`.
+    ///
+    /// # Arguments
+    ///
+    /// * `piece_length` - Piece size of the torrent.
+    /// * `hashes` - Hex encoded piece hashes, as returned by
+    ///   [`Api::pieces_hashes`](crate::Api::pieces_hashes).
+    /// * `content` - The files of the torrent.
+    /// * `save_path` - Local folder the file names are relative to.
+    ///
+    /// The server does not list padding files. They are added back where
+    /// the piece range of a file shows it starts after the end of the
+    /// previous one, see [`TorrentContent::offset`].
+    pub fn from_content(
+        piece_length: u64,
+        hashes: &[String],
+        content: &[TorrentContent],
+        save_path: &Path,
+    ) -> Result<Self, Error> {
+        let hashes = hashes
+            .iter()
+            .map(|hash| parse_hash(hash))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut content: Vec<&TorrentContent> = content.iter().collect();
+        content.sort_by_key(|file| file.index);
+
+        let mut files = Vec::new();
+        let mut offset = 0;
+
+        for file in content {
+            let length = file.size.0.max(0) as u64;
+            let start = file.offset(offset, piece_length);
+            if start > offset {
+                files.push(VerifyFile {
+                    index: None,
+                    name: String::from(".pad"),
+                    path: None,
+                    length: start - offset,
+                    offset,
+                    pieces: piece_range(offset, start - offset, piece_length),
+                });
+                offset = start;
+            }
+
+            let pieces = match file.piece_range.as_slice() {
+                [start, end] => (*start).max(0) as usize..=(*end).max(0) as usize,
+                _ => piece_range(offset, length, piece_length),
+            };
+
+            files.push(VerifyFile {
+                index: Some(file.index),
+                name: file.name.clone(),
+                path: Some(local_path(save_path, &file.name)),
+                length,
+                offset,
+                pieces,
+            });
+            offset += length;
+        }
+
+        Self::new(piece_length, hashes, files)
+    }
+
+    /// The files checked, padding files included
+    pub fn files(&self) -> &[VerifyFile] {
+        &self.files
+    }
+
+    /// Reads and hashes every piece, with up to `concurrency` threads.
+    ///
+    /// This blocks until every piece is hashed. In async code, run it on a
+    /// blocking thread.
+    pub fn verify(&self, concurrency: usize) -> VerifyReport {
+        let count = self.hashes.len();
+        let per_thread = count.div_ceil(concurrency.max(1)).max(1);
+        let ranges: Vec<_> = (0..count)
+            .step_by(per_thread)
+            .map(|start| start..(start + per_thread).min(count))
+            .collect();
+
+        let mut bad_pieces: Vec<usize> = std::thread::scope(|scope| {
+            let workers: Vec<_> = ranges
+                .into_iter()
+                .map(|range| {
+                    scope.spawn(move || {
+                        let mut handles = HashMap::new();
+                        range
+                            .filter(|piece| !self.check_piece(*piece, &mut handles))
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+
+            workers
+                .into_iter()
+                .flat_map(|worker| worker.join().unwrap_or_default())
+                .collect()
+        });
+        bad_pieces.sort_unstable();
+
+        let files = self
+            .files
+            .iter()
+            .filter_map(|file| {
+                let index = file.index?;
+                let path = file.path.clone()?;
+                let missing = std::fs::metadata(&path)
+                    .map(|metadata| metadata.len() < file.length)
+                    .unwrap_or(true);
+
+                Some(FileReport {
+                    index,
+                    name: file.name.clone(),
+                    path,
+                    missing,
+                    bad_pieces: bad_pieces
+                        .iter()
+                        .copied()
+                        .filter(|piece| file.length > 0 && file.pieces.contains(piece))
+                        .collect(),
+                })
+            })
+            .collect();
+
+        VerifyReport {
+            piece_count: count,
+            bad_pieces,
+            files,
+        }
+    }
+
+    /// Size of the data covered by the pieces
+    fn data_length(&self) -> u64 {
+        self.files
+            .last()
+            .map(|file| file.offset + file.length)
+            .unwrap_or_default()
+    }
+
+    /// True if the piece can be read and matches its hash.
+    fn check_piece(&self, piece: usize, handles: &mut HashMap<usize, File>) -> bool {
+        let start = piece as u64 * self.piece_length;
+        let end = (start + self.piece_length).min(self.data_length());
+        let mut hasher = Sha1::new();
+        let mut buffer = Vec::new();
+
+        // Files are sorted by offset and contiguous, so their ends are sorted
+        // too: skip to the first file ending after the start of the piece.
+        let first = self
+            .files
+            .partition_point(|file| file.offset + file.length <= start);
+
+        for (position, file) in self.files.iter().enumerate().skip(first) {
+            if file.offset >= end {
+                break;
+            }
+            let file_end = file.offset + file.length;
+
+            let from = start.max(file.offset);
+            let to = end.min(file_end);
+            buffer.resize((to - from) as usize, 0);
+
+            match &file.path {
+                Some(path) => {
+                    let read = read_at(handles, position, path, from - file.offset, &mut buffer);
+                    if read.is_err() {
+                        return false;
+                    }
+                }
+                None => buffer.fill(0),
+            }
+            hasher.update(&buffer);
+        }
+
+        hasher.finalize().as_slice() == self.hashes[piece]
+    }
+}
+
+/// Reads exactly `buffer.len()` bytes at `offset`, keeping the file open in
+/// `handles`.
+fn read_at(
+    handles: &mut HashMap<usize, File>,
+    position: usize,
+    path: &Path,
+    offset: u64,
+    buffer: &mut [u8],
+) -> std::io::Result<()> {
+    let file = match handles.entry(position) {
+        std::collections::hash_map::Entry::Occupied(entry) => entry.into_mut(),
+        std::collections::hash_map::Entry::Vacant(entry) => entry.insert(File::open(path)?),
+    };
+
+    file.seek(SeekFrom::Start(offset))?;
+    file.read_exact(buffer)
+}
+
+/// Pieces overlapping `length` bytes at `offset`
+fn piece_range(offset: u64, length: u64, piece_length: u64) -> RangeInclusive<usize> {
+    let first = offset / piece_length;
+    let last = (offset + length.max(1) - 1) / piece_length;
+
+    first as usize..=last as usize
+}
+
+/// Joins a `/` separated torrent file name to `save_path`.
+fn local_path(save_path: &Path, name: &str) -> PathBuf {
+    name.split('/')
+        .filter(|part| !part.is_empty())
+        .fold(save_path.to_path_buf(), |path, part| path.join(part))
+}
+
+fn parse_hash(hash: &str) -> Result<[u8; V1_PIECE_HASH_LEN], Error> {
+    let invalid = || Error::InvalidResponse(format!("Invalid piece hash: {:?}", hash));
+
+    if hash.len() != V1_PIECE_HASH_LEN * 2 || !hash.is_ascii() {
+        return Err(invalid());
+    }
+
+    let mut bytes = [0; V1_PIECE_HASH_LEN];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hash[i * 2..i * 2 + 2], 16).map_err(|_| invalid())?;
+    }
+
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Bytes;
+
+    const PIECE: u64 = 4;
+
+    fn sha1_hex(data: &[u8]) -> String {
+        Sha1::digest(data)
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+
+    fn content(index: i64, name: &str, size: i64, pieces: [i64; 2]) -> TorrentContent {
+        TorrentContent {
+            index,
+            name: name.to_string(),
+            size: Bytes(size),
+            piece_range: pieces.to_vec(),
+            ..Default::default()
+        }
+    }
+
+    /// Writes `files` to a fresh folder named after the test.
+    fn save_path(test: &str, files: &[(&str, &[u8])]) -> PathBuf {
+        let root =
+            std::env::temp_dir().join(format!("qbit-verify-{}-{}", test, std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        for (name, data) in files {
+            let path = local_path(&root, name);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, data).unwrap();
+        }
+        root
+    }
+
+    #[test]
+    fn packed_files() {
+        // Pieces: "abcd" "efgh" "ij"
+        let hashes = vec![sha1_hex(b"abcd"), sha1_hex(b"efgh"), sha1_hex(b"ij")];
+        let content = vec![content(0, "t/a", 6, [0, 1]), content(1, "t/b", 4, [1, 2])];
+        let root = save_path("packed", &[("t/a", b"abcdef"), ("t/b", b"gxij")]);
+
+        let verifier = PieceVerifier::from_content(PIECE, &hashes, &content, &root).unwrap();
+        let report = verifier.verify(2);
+
+        assert_eq!(report.bad_pieces, vec![1]);
+        assert_eq!(report.files[0].bad_pieces, vec![1]);
+        assert_eq!(report.files[1].bad_pieces, vec![1]);
+        assert_eq!(report.bad_files().count(), 2);
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn padded_files_and_missing_file() {
+        // Pieces: "abc" + 1 padding byte, "de"
+        let hashes = vec![sha1_hex(b"abc\0"), sha1_hex(b"de")];
+        let content = vec![content(0, "t/a", 3, [0, 0]), content(1, "t/b", 2, [1, 1])];
+        let root = save_path("aligned", &[("t/a", b"abc")]);
+
+        let verifier = PieceVerifier::from_content(PIECE, &hashes, &content, &root).unwrap();
+        let report = verifier.verify(4);
+
+        assert_eq!(verifier.files().len(), 3);
+        assert_eq!(report.bad_pieces, vec![1]);
+        assert!(!report.files[0].missing);
+        assert!(report.files[1].missing);
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn reject_piece_count_mismatch() {
+        let hashes = vec![sha1_hex(b"abcd")];
+        let content = vec![content(0, "a", 6, [0, 1])];
+
+        assert!(PieceVerifier::from_content(PIECE, &hashes, &content, Path::new("/")).is_err());
+        assert!(parse_hash("xyz").is_err());
+    }
+}
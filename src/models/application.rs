@@ -3,6 +3,8 @@ use std::{collections::HashMap, fmt::Display};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_repr::{Deserialize_repr, Serialize_repr};
 
+use crate::{models::FromTimestamp, utiles::timestamp};
+
 /// Build info response data object.
 #[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 pub struct BuildInfo {
@@ -677,19 +679,21 @@ pub struct Cookie {
     pub expiration: i64,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, PartialOrd, Ord)]
+impl Cookie {
+    /// Expiration date of the cookie. `None` for session cookies.
+    pub fn expires_at<T: FromTimestamp>(&self) -> Option<T> {
+        timestamp::instant(self.expiration).and_then(T::from_timestamp)
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq, Eq, PartialOrd, Ord)]
 pub enum DirMode {
     Dirs,
     Files,
+    #[default]
     All,
 }
 
-impl Default for DirMode {
-    fn default() -> Self {
-        Self::All
-    }
-}
-
 impl Display for DirMode {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
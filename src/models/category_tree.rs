@@ -0,0 +1,458 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    error::Error,
+    models::{Category, Torrent},
+};
+
+/// Separator between the levels of a subcategory name (`tv/shows/anime`)
+pub const CATEGORY_SEPARATOR: char = '/';
+
+/// Categories arranged by their subcategory names.
+///
+/// qBittorrent stores subcategories as flat names like `tv/shows/anime`
+/// (see `ServerState::use_subcategories`). The tree links every category to
+/// its parent and children. Parents missing from the server data, which can
+/// happen when subcategories are disabled, are added as implicit nodes
+/// without a [`Category`].
+///
+/// Can be built from [`Api::categories`](crate::Api::categories) or
+/// [`MainData::categories`](crate::models::MainData::categories).
+///
+/// # Example
+///
+/// ```no_run
+/// use qbit::{Api, Credentials};
+/// use qbit::models::CategoryTree;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let credentials = Credentials::new("username", "password");
+///     let client = Api::new_login("url", credentials)
+///         .await
+///         .unwrap();
+///
+///     let tree = CategoryTree::from(client.categories().await.unwrap());
+///     let torrents = client.torrents(None).await.unwrap();
+///
+///     for node in tree.descendants("tv") {
+///         println!("{}: {} torrents", node.name, tree.torrent_count(&node.name, &torrents));
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CategoryTree {
+    nodes: BTreeMap<String, CategoryNode>,
+}
+
+/// A request sent to rename or move categories, see
+/// [`CategoryMoveError`](crate::CategoryMoveError)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CategoryStep {
+    /// The category was created
+    Create(String),
+    /// The torrents of category `from` were moved to category `to`
+    Recategorize { from: String, to: String },
+    /// The categories were removed
+    Remove(Vec<String>),
+}
+
+/// A category in a [`CategoryTree`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CategoryNode {
+    /// Full category name, like `tv/shows`
+    pub name: String,
+    /// The category as sent by the server. `None` for implicit parents.
+    pub category: Option<Category>,
+    /// Full name of the parent category. `None` for top level categories.
+    pub parent: Option<String>,
+    /// Full names of the direct subcategories, sorted
+    pub children: Vec<String>,
+}
+
+impl CategoryNode {
+    /// Last level of the name (`anime` for `tv/shows/anime`)
+    pub fn label(&self) -> &str {
+        self.name
+            .rsplit(CATEGORY_SEPARATOR)
+            .next()
+            .unwrap_or(&self.name)
+    }
+
+    /// Number of ancestors, `0` for top level categories
+    pub fn depth(&self) -> usize {
+        self.name.matches(CATEGORY_SEPARATOR).count()
+    }
+}
+
+impl CategoryTree {
+    /// Builds the tree from a list of categories.
+    pub fn new(categories: impl IntoIterator<Item = Category>) -> Self {
+        let mut tree = Self::default();
+
+        for category in categories {
+            let name = category.name.clone();
+            tree.insert(&name);
+            if let Some(node) = tree.nodes.get_mut(&name) {
+                node.category = Some(category);
+            }
+        }
+
+        tree
+    }
+
+    /// Adds the node `name` and its missing ancestors.
+    fn insert(&mut self, name: &str) {
+        if self.nodes.contains_key(name) {
+            return;
+        }
+
+        let parent = name
+            .rsplit_once(CATEGORY_SEPARATOR)
+            .map(|(parent, _)| parent.to_string());
+
+        if let Some(parent) = &parent {
+            self.insert(parent);
+            if let Some(node) = self.nodes.get_mut(parent) {
+                node.children.push(name.to_string());
+                node.children.sort();
+            }
+        }
+
+        self.nodes.insert(
+            name.to_string(),
+            CategoryNode {
+                name: name.to_string(),
+                category: None,
+                parent,
+                children: Vec::new(),
+            },
+        );
+    }
+
+    /// The category `name`
+    pub fn get(&self, name: &str) -> Option<&CategoryNode> {
+        self.nodes.get(name)
+    }
+
+    /// True if the tree has no category
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Number of categories, including implicit parents
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Every category, sorted by name
+    pub fn iter(&self) -> impl Iterator<Item = &CategoryNode> {
+        self.nodes.values()
+    }
+
+    /// The top level categories
+    pub fn roots(&self) -> impl Iterator<Item = &CategoryNode> {
+        self.nodes.values().filter(|node| node.parent.is_none())
+    }
+
+    /// The parent of `name`
+    pub fn parent(&self, name: &str) -> Option<&CategoryNode> {
+        self.get(name)
+            .and_then(|node| node.parent.as_deref())
+            .and_then(|parent| self.get(parent))
+    }
+
+    /// The direct subcategories of `name`
+    pub fn children(&self, name: &str) -> impl Iterator<Item = &CategoryNode> {
+        self.get(name)
+            .into_iter()
+            .flat_map(|node| node.children.iter())
+            .filter_map(|child| self.get(child))
+    }
+
+    /// The ancestors of `name`, nearest first
+    pub fn ancestors(&self, name: &str) -> Vec<&CategoryNode> {
+        let mut ancestors = Vec::new();
+        let mut current = self.parent(name);
+
+        while let Some(node) = current {
+            ancestors.push(node);
+            current = self.parent(&node.name);
+        }
+
+        ancestors
+    }
+
+    /// `name` and all its subcategories, parents before their children
+    pub fn descendants(&self, name: &str) -> Vec<&CategoryNode> {
+        let mut descendants = Vec::new();
+        let mut stack: Vec<&CategoryNode> = self.get(name).into_iter().collect();
+
+        while let Some(node) = stack.pop() {
+            descendants.push(node);
+            stack.extend(
+                self.children(&node.name)
+                    .collect::<Vec<_>>()
+                    .into_iter()
+                    .rev(),
+            );
+        }
+
+        descendants
+    }
+
+    /// Save path of `name`, resolved as the server does.
+    ///
+    /// A category without its own save path uses `default_save_path`
+    /// extended with its full name, whatever the save path of its parent.
+    /// Relative save paths are relative to `default_save_path`.
+    ///
+    /// `None` if the category does not exist.
+    pub fn save_path(&self, name: &str, default_save_path: &Path) -> Option<PathBuf> {
+        let node = self.get(name)?;
+
+        let own = node
+            .category
+            .as_ref()
+            .map(|category| category.save_path.as_str())
+            .filter(|path| !path.is_empty());
+
+        Some(match own {
+            Some(path) => default_save_path.join(path),
+            None => default_save_path.join(&node.name),
+        })
+    }
+
+    /// Number of torrents in `name` or any of its subcategories
+    pub fn torrent_count(&self, name: &str, torrents: &[Torrent]) -> usize {
+        torrents
+            .iter()
+            .filter(|torrent| is_in_subtree(&torrent.category, name))
+            .count()
+    }
+
+    /// Number of torrents in every subtree, by category name
+    pub fn torrent_counts(&self, torrents: &[Torrent]) -> HashMap<String, usize> {
+        let mut counts: HashMap<String, usize> =
+            self.nodes.keys().map(|name| (name.clone(), 0)).collect();
+
+        for torrent in torrents {
+            let mut current = self.get(&torrent.category);
+            while let Some(node) = current {
+                *counts.entry(node.name.clone()).or_default() += 1;
+                current = self.parent(&node.name);
+            }
+        }
+
+        counts
+    }
+
+    /// Renames needed to move `name` and its subcategories under
+    /// `new_parent`, or to the top level if `None`.
+    ///
+    /// Returns `(old name, new name)` pairs, parents before their children.
+    pub fn move_plan(
+        &self,
+        name: &str,
+        new_parent: Option<&str>,
+    ) -> Result<Vec<(String, String)>, Error> {
+        let node = self
+            .get(name)
+            .ok_or_else(|| Error::InvalidRequest(format!("Unknown category {:?}", name)))?;
+
+        let new_name = match new_parent {
+            Some(parent) if is_in_subtree(parent, name) => {
+                return Err(Error::InvalidRequest(format!(
+                    "Cannot move category {:?} into its own subtree {:?}",
+                    name, parent
+                )));
+            }
+            Some(parent) => format!("{}{}{}", parent, CATEGORY_SEPARATOR, node.label()),
+            None => node.label().to_string(),
+        };
+
+        self.rename_plan(name, &new_name)
+    }
+
+    /// Renames needed to rename `name` to `new_name`, along with its
+    /// subcategories.
+    ///
+    /// Returns `(old name, new name)` pairs, parents before their children.
+    pub fn rename_plan(&self, name: &str, new_name: &str) -> Result<Vec<(String, String)>, Error> {
+        if self.get(name).is_none() {
+            return Err(Error::InvalidRequest(format!(
+                "Unknown category {:?}",
+                name
+            )));
+        }
+        if new_name.is_empty() {
+            return Err(Error::InvalidRequest(String::from(
+                "Category name cannot be empty",
+            )));
+        }
+        if new_name == name {
+            return Ok(Vec::new());
+        }
+        if is_in_subtree(new_name, name) {
+            return Err(Error::InvalidRequest(format!(
+                "Cannot rename category {:?} into its own subtree {:?}",
+                name, new_name
+            )));
+        }
+        if self.nodes.contains_key(new_name) {
+            return Err(Error::InvalidRequest(format!(
+                "Category {:?} already exists",
+                new_name
+            )));
+        }
+
+        Ok(self
+            .descendants(name)
+            .into_iter()
+            .map(|node| {
+                let renamed = format!("{}{}", new_name, &node.name[name.len()..]);
+                (node.name.clone(), renamed)
+            })
+            .collect())
+    }
+}
+
+impl From<HashMap<String, Category>> for CategoryTree {
+    fn from(value: HashMap<String, Category>) -> Self {
+        Self::new(value.into_iter().map(|(name, mut category)| {
+            category.name = name;
+            category
+        }))
+    }
+}
+
+/// True if `category` is `name` or one of its subcategories.
+fn is_in_subtree(category: &str, name: &str) -> bool {
+    category
+        .strip_prefix(name)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with(CATEGORY_SEPARATOR))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tree() -> CategoryTree {
+        let category = |name: &str, save_path: &str| Category {
+            name: name.to_string(),
+            save_path: save_path.to_string(),
+            ..Default::default()
+        };
+
+        CategoryTree::new(vec![
+            category("tv", "/media/tv"),
+            category("tv/shows", ""),
+            category("tv/shows/anime", ""),
+            category("movies/hd", ""),
+            category("linux", "distros"),
+        ])
+    }
+
+    fn torrent(category: &str) -> Torrent {
+        Torrent {
+            category: category.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn links_parents_and_children() {
+        let tree = tree();
+
+        assert_eq!(tree.len(), 6);
+        assert_eq!(tree.get("movies").unwrap().category, None);
+        assert_eq!(tree.parent("tv/shows").unwrap().name, "tv");
+        assert_eq!(
+            tree.roots().map(|n| n.name.as_str()).collect::<Vec<_>>(),
+            vec!["linux", "movies", "tv"]
+        );
+        assert_eq!(
+            tree.ancestors("tv/shows/anime")
+                .iter()
+                .map(|n| n.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["tv/shows", "tv"]
+        );
+        assert_eq!(tree.descendants("tv").len(), 3);
+    }
+
+    #[test]
+    fn resolved_save_path() {
+        let tree = tree();
+        let default = Path::new("/downloads");
+
+        assert_eq!(
+            tree.save_path("tv", default).unwrap(),
+            PathBuf::from("/media/tv")
+        );
+        assert_eq!(
+            tree.save_path("tv/shows/anime", default).unwrap(),
+            PathBuf::from("/downloads/tv/shows/anime")
+        );
+        assert_eq!(
+            tree.save_path("movies/hd", default).unwrap(),
+            PathBuf::from("/downloads/movies/hd")
+        );
+        assert_eq!(
+            tree.save_path("linux", default).unwrap(),
+            PathBuf::from("/downloads/distros")
+        );
+    }
+
+    #[test]
+    fn count_torrents_per_subtree() {
+        let tree = tree();
+        let torrents = vec![torrent("tv"), torrent("tv/shows/anime"), torrent("tvx")];
+
+        assert_eq!(tree.torrent_count("tv", &torrents), 2);
+        assert_eq!(tree.torrent_count("tv/shows", &torrents), 1);
+        assert_eq!(tree.torrent_counts(&torrents)["tv/shows"], 1);
+        assert_eq!(tree.torrent_counts(&torrents)["linux"], 0);
+    }
+
+    #[test]
+    fn plan_subtree_move() {
+        let tree = tree();
+
+        assert_eq!(
+            tree.move_plan("tv/shows", Some("movies")).unwrap(),
+            vec![
+                ("tv/shows".to_string(), "movies/shows".to_string()),
+                (
+                    "tv/shows/anime".to_string(),
+                    "movies/shows/anime".to_string()
+                ),
+            ]
+        );
+        assert_eq!(
+            tree.move_plan("movies/hd", None).unwrap(),
+            vec![("movies/hd".to_string(), "hd".to_string())]
+        );
+        assert!(tree.move_plan("tv", Some("tv/shows")).is_err());
+        assert!(tree.move_plan("unknown", None).is_err());
+    }
+
+    #[test]
+    fn plan_subtree_rename() {
+        let tree = tree();
+
+        assert_eq!(
+            tree.rename_plan("tv/shows", "tv/series").unwrap(),
+            vec![
+                ("tv/shows".to_string(), "tv/series".to_string()),
+                ("tv/shows/anime".to_string(), "tv/series/anime".to_string()),
+            ]
+        );
+        assert!(tree.rename_plan("tv", "tv/shows/tv").is_err());
+        assert!(tree.rename_plan("tv/shows", "movies").is_err());
+        assert!(tree.rename_plan("tv", "").is_err());
+    }
+}
@@ -3,10 +3,12 @@ use std::fmt::{Debug, Display};
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
 
+use crate::{models::FromTimestamp, utiles::timestamp};
+
 /// The format of the torrent.
 ///
 /// See [torrent format hybrid v1 and v2](https://www.reddit.com/r/qBittorrent/comments/uiwchy/torrent_format_hybrid_v1_and_v2/) for more information
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Debug)]
+#[derive(Clone, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Debug)]
 pub enum TorrentFormat {
     /// Old version, uses SHA-1 for hashing.
     #[serde(rename = "v1")]
@@ -16,15 +18,10 @@ pub enum TorrentFormat {
     V2,
     /// Attempts to work with both v1 and v2 torrents.
     #[serde(rename = "hybrid")]
+    #[default]
     Hybrid,
 }
 
-impl Default for TorrentFormat {
-    fn default() -> Self {
-        Self::Hybrid
-    }
-}
-
 impl Display for TorrentFormat {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -257,3 +254,30 @@ pub struct TorrentCreatorTaskStatus {
     /// List of URL seeds
     pub url_seeds: Vec<String>,
 }
+
+impl TorrentCreatorTaskStatus {
+    /// The time this task got added
+    ///
+    /// qBittorrent formats the time in the local time zone of the server,
+    /// without an offset. `utc_offset` is the offset of that time zone from
+    /// UTC in seconds, for example `3600` for UTC+1.
+    pub fn added_at<T: FromTimestamp>(&self, utc_offset: i64) -> Option<T> {
+        timestamp::parse_text_date(&self.time_added, utc_offset).and_then(T::from_timestamp)
+    }
+
+    /// The time this task started being processed. See [`Self::added_at`].
+    pub fn started_at<T: FromTimestamp>(&self, utc_offset: i64) -> Option<T> {
+        self.time_started
+            .as_deref()
+            .and_then(|time| timestamp::parse_text_date(time, utc_offset))
+            .and_then(T::from_timestamp)
+    }
+
+    /// The time this task finished. See [`Self::added_at`].
+    pub fn finished_at<T: FromTimestamp>(&self, utc_offset: i64) -> Option<T> {
+        self.time_finished
+            .as_deref()
+            .and_then(|time| timestamp::parse_text_date(time, utc_offset))
+            .and_then(T::from_timestamp)
+    }
+}
@@ -0,0 +1,85 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Conversion from seconds since the Unix epoch into a date time type.
+///
+/// Used by the typed time accessors on the models, such as
+/// [`Torrent::added_at`](crate::models::Torrent::added_at).
+///
+/// Implemented for [`SystemTime`], for `time::OffsetDateTime` with the
+/// `time` feature and for `chrono::DateTime<Utc>` with the `chrono` feature.
+pub trait FromTimestamp: Sized {
+    /// Returns `None` if the timestamp can not be represented.
+    fn from_timestamp(seconds: i64) -> Option<Self>;
+}
+
+impl FromTimestamp for SystemTime {
+    fn from_timestamp(seconds: i64) -> Option<Self> {
+        if seconds >= 0 {
+            UNIX_EPOCH.checked_add(Duration::from_secs(seconds.unsigned_abs()))
+        } else {
+            UNIX_EPOCH.checked_sub(Duration::from_secs(seconds.unsigned_abs()))
+        }
+    }
+}
+
+#[cfg(feature = "time")]
+impl FromTimestamp for time::OffsetDateTime {
+    fn from_timestamp(seconds: i64) -> Option<Self> {
+        Self::from_unix_timestamp(seconds).ok()
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl FromTimestamp for chrono::DateTime<chrono::Utc> {
+    fn from_timestamp(seconds: i64) -> Option<Self> {
+        Self::from_timestamp(seconds, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Torrent;
+
+    fn torrent() -> Torrent {
+        Torrent {
+            added_on: 1_700_000_000,
+            completion_on: -1,
+            eta: 8_640_000,
+            seeding_time: 60,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn system_time_from_timestamp() {
+        let added: Option<SystemTime> = torrent().added_at();
+
+        assert_eq!(added, Some(UNIX_EPOCH + Duration::from_secs(1_700_000_000)));
+    }
+
+    #[test]
+    fn sentinels_decode_to_none() {
+        let torrent = torrent();
+
+        assert_eq!(torrent.completed_at::<SystemTime>(), None);
+        assert_eq!(torrent.eta_duration(), None);
+        assert_eq!(torrent.seeding_duration(), Some(Duration::from_secs(60)));
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn offset_date_time_from_timestamp() {
+        let added: Option<time::OffsetDateTime> = torrent().added_at();
+
+        assert_eq!(added.unwrap().unix_timestamp(), 1_700_000_000);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono_date_time_from_timestamp() {
+        let added: Option<chrono::DateTime<chrono::Utc>> = torrent().added_at();
+
+        assert_eq!(added.unwrap().timestamp(), 1_700_000_000);
+    }
+}
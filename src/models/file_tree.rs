@@ -0,0 +1,403 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::Error,
+    models::{Bytes, FilePriority, TorrentContent},
+    utiles::glob,
+};
+
+/// Folders and files of a torrent
+///
+/// Built from the flat list returned by [`Api::files`](crate::Api::files),
+/// where folders only show up in the `/` separated file names. Not to be
+/// confused with [`FileNode`](crate::metainfo::FileNode), the file tree of
+/// a v2 `.torrent` file.
+///
+/// # Example
+///
+/// ```
+/// use qbit::models::{Bytes, FileTree, TorrentContent};
+///
+/// let file = |index, name: &str, size| TorrentContent {
+///     index,
+///     name: name.to_string(),
+///     size: Bytes(size),
+///     ..Default::default()
+/// };
+/// let tree = FileTree::new(vec![
+///     file(0, "Show/e01.mkv", 700),
+///     file(1, "Show/Subs/e01.srt", 1),
+/// ])
+/// .unwrap();
+///
+/// let show = tree.get("Show").unwrap();
+/// assert_eq!(show.summary().size, Bytes(701));
+/// assert_eq!(tree.glob("*.srt").unwrap()[0].index, 1);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FileTree {
+    root: FileTreeFolder,
+}
+
+/// A file or folder of a [`FileTree`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum FileTreeNode {
+    /// A file, as returned by [`Api::files`](crate::Api::files)
+    File(TorrentContent),
+    /// A folder and its content
+    Folder(FileTreeFolder),
+}
+
+/// A folder of a [`FileTree`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FileTreeFolder {
+    /// Name of the folder, empty for the root
+    pub name: String,
+    /// `/` separated path of the folder in the torrent, empty for the root
+    pub path: String,
+    /// Files and folders in the folder, by name
+    pub children: BTreeMap<String, FileTreeNode>,
+}
+
+/// Aggregated state of the files in a folder
+///
+/// Progress and availability are averages weighted by file size, over the
+/// files that are not [`FilePriority::DoNotDownload`], like the qBittorrent
+/// user interface does.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FolderSummary {
+    /// Number of files, in all sub folders
+    pub files: usize,
+    /// Total size of the files
+    pub size: Bytes,
+    /// Size of the files selected for download
+    pub selected_size: Bytes,
+    /// Download progress of the selected files, between 0 and 1
+    pub progress: f64,
+    /// Priority shared by every file. `None` if the files have different
+    /// priorities, or if there are no files.
+    pub priority: Option<FilePriority>,
+    /// Availability of the selected files. `None` if no file is selected.
+    pub availability: Option<f64>,
+}
+
+/// Files picked by a [`FileSelection`](crate::parameters::FileSelection)
+///
+/// Returned by [`Api::select_files`](crate::Api::select_files).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileSelectionPlan {
+    /// Indexes of the files to download
+    pub selected: Vec<u64>,
+    /// Indexes of the files to skip
+    pub skipped: Vec<u64>,
+    /// Priority changes needed, one request each. Files that already have
+    /// the right priority are left out.
+    pub changes: Vec<(FilePriority, Vec<u64>)>,
+}
+
+impl FileTree {
+    /// Builds the tree from the files of a torrent
+    ///
+    /// # Errors
+    ///
+    /// [`Error::ParseError`] if two files have the same path, or if a file
+    /// has the path of a folder holding other files.
+    pub fn new(files: Vec<TorrentContent>) -> Result<Self, Error> {
+        let mut root = FileTreeFolder::default();
+
+        for file in files {
+            let parts: Vec<&str> = file.name.split('/').filter(|p| !p.is_empty()).collect();
+            let Some((name, folders)) = parts.split_last() else {
+                continue;
+            };
+
+            let mut folder = &mut root;
+            for part in folders {
+                let path = join(&folder.path, part);
+                let node = folder.children.entry(part.to_string()).or_insert_with(|| {
+                    FileTreeNode::Folder(FileTreeFolder {
+                        name: part.to_string(),
+                        path: path.clone(),
+                        children: BTreeMap::new(),
+                    })
+                });
+                folder = match node {
+                    FileTreeNode::Folder(folder) => folder,
+                    FileTreeNode::File(_) => return Err(clash(&path)),
+                };
+            }
+
+            if folder.children.contains_key(*name) {
+                return Err(clash(&join(&folder.path, name)));
+            }
+            folder
+                .children
+                .insert(name.to_string(), FileTreeNode::File(file));
+        }
+
+        Ok(Self { root })
+    }
+
+    /// The root folder, holding the top level files and folders
+    pub fn root(&self) -> &FileTreeFolder {
+        &self.root
+    }
+
+    /// File or folder at a `/` separated path
+    pub fn get(&self, path: &str) -> Option<&FileTreeNode> {
+        let mut parts = path.split('/').filter(|p| !p.is_empty());
+        let mut node = self.root.children.get(parts.next()?)?;
+
+        for part in parts {
+            node = match node {
+                FileTreeNode::Folder(folder) => folder.children.get(part)?,
+                FileTreeNode::File(_) => return None,
+            };
+        }
+
+        Some(node)
+    }
+
+    /// Every file, in path order
+    pub fn files(&self) -> impl Iterator<Item = &TorrentContent> {
+        self.root.files()
+    }
+
+    /// Files whose path matches a glob pattern.
+    ///
+    /// `*` and `?` do not match `/`, `**` does. Patterns without `/`
+    /// match the file name only, so `*.nfo` matches in every folder.
+    /// `[a-z]`, `[!a-z]` and `{mkv,mp4}` are supported as well.
+    pub fn glob(&self, pattern: &str) -> Result<Vec<&TorrentContent>, Error> {
+        let pattern = glob::compile(pattern, false)?;
+
+        Ok(self
+            .files()
+            .filter(|file| pattern.is_match(&file.name))
+            .collect())
+    }
+
+    /// Files with one of the extensions, compared without case and
+    /// without the leading dot.
+    pub fn with_extension(&self, extensions: &[&str]) -> Vec<&TorrentContent> {
+        self.files()
+            .filter(|file| {
+                extension(&file.name).is_some_and(|ext| {
+                    extensions
+                        .iter()
+                        .any(|e| e.trim_start_matches('.').eq_ignore_ascii_case(ext))
+                })
+            })
+            .collect()
+    }
+}
+
+impl TryFrom<Vec<TorrentContent>> for FileTree {
+    type Error = Error;
+
+    fn try_from(value: Vec<TorrentContent>) -> Result<Self, Self::Error> {
+        FileTree::new(value)
+    }
+}
+
+fn clash(path: &str) -> Error {
+    Error::ParseError(format!("More than one file or folder at {path}"))
+}
+
+impl FileTreeNode {
+    /// Name of the file or folder
+    pub fn name(&self) -> &str {
+        match self {
+            Self::File(file) => file.name.rsplit('/').next().unwrap_or_default(),
+            Self::Folder(folder) => &folder.name,
+        }
+    }
+
+    /// `/` separated path of the file or folder in the torrent
+    pub fn path(&self) -> &str {
+        match self {
+            Self::File(file) => &file.name,
+            Self::Folder(folder) => &folder.path,
+        }
+    }
+
+    /// True for folders
+    pub fn is_folder(&self) -> bool {
+        matches!(self, Self::Folder(_))
+    }
+
+    /// The file, or every file of the folder
+    pub fn files(&self) -> Box<dyn Iterator<Item = &TorrentContent> + '_> {
+        match self {
+            Self::File(file) => Box::new(std::iter::once(file)),
+            Self::Folder(folder) => Box::new(folder.files()),
+        }
+    }
+
+    /// File indexes of the file, or of every file of the folder, as used by
+    /// [`Api::set_file_priority`](crate::Api::set_file_priority)
+    pub fn indexes(&self) -> Vec<u64> {
+        self.files().map(|file| file.index as u64).collect()
+    }
+
+    /// Aggregated state of the file, or of the files of the folder
+    pub fn summary(&self) -> FolderSummary {
+        FolderSummary::collect(self.files())
+    }
+}
+
+impl FileTreeFolder {
+    /// Every file of the folder and its sub folders, in path order
+    pub fn files(&self) -> Box<dyn Iterator<Item = &TorrentContent> + '_> {
+        Box::new(self.children.values().flat_map(|node| node.files()))
+    }
+
+    /// Aggregated state of the files of the folder
+    pub fn summary(&self) -> FolderSummary {
+        FolderSummary::collect(self.files())
+    }
+}
+
+impl FolderSummary {
+    /// Aggregated state of `files`
+    pub fn collect<'a>(files: impl IntoIterator<Item = &'a TorrentContent>) -> Self {
+        let mut summary = Self::default();
+        let mut priority: Option<Option<FilePriority>> = None;
+        let (mut progress, mut availability) = (0.0, 0.0);
+
+        for file in files {
+            summary.files += 1;
+            summary.size += file.size;
+
+            priority = match priority {
+                None => Some(Some(file.priority)),
+                Some(p) if p == Some(file.priority) => Some(p),
+                Some(_) => Some(None),
+            };
+
+            if file.priority != FilePriority::DoNotDownload {
+                summary.selected_size += file.size;
+                progress += file.progress * file.size.0 as f64;
+                availability += file.availability * file.size.0 as f64;
+            }
+        }
+
+        summary.priority = priority.flatten();
+        if summary.selected_size.0 > 0 {
+            summary.progress = progress / summary.selected_size.0 as f64;
+            summary.availability = Some(availability / summary.selected_size.0 as f64);
+        }
+
+        summary
+    }
+}
+
+fn join(path: &str, name: &str) -> String {
+    if path.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}/{}", path, name)
+    }
+}
+
+fn extension(name: &str) -> Option<&str> {
+    let file_name = name.rsplit('/').next()?;
+    let (stem, ext) = file_name.rsplit_once('.')?;
+
+    (!stem.is_empty()).then_some(ext)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(
+        index: i64,
+        name: &str,
+        size: i64,
+        progress: f64,
+        priority: FilePriority,
+    ) -> TorrentContent {
+        TorrentContent {
+            index,
+            name: name.to_string(),
+            size: Bytes(size),
+            progress,
+            priority,
+            availability: 1.0,
+            ..Default::default()
+        }
+    }
+
+    fn tree() -> FileTree {
+        FileTree::new(vec![
+            file(0, "Show/Season 1/e01.mkv", 300, 1.0, FilePriority::Normal),
+            file(1, "Show/Season 1/e02.MKV", 100, 0.0, FilePriority::Normal),
+            file(
+                2,
+                "Show/Season 1/e01.srt",
+                10,
+                0.0,
+                FilePriority::DoNotDownload,
+            ),
+            file(3, "Show/info.nfo", 1, 1.0, FilePriority::High),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn build_tree() {
+        let tree = tree();
+
+        assert_eq!(tree.root().children.len(), 1);
+        let season = tree.get("Show/Season 1").unwrap();
+        assert!(season.is_folder());
+        assert_eq!(season.name(), "Season 1");
+        assert_eq!(season.indexes(), vec![0, 2, 1]);
+        assert_eq!(tree.get("Show/info.nfo").unwrap().name(), "info.nfo");
+        assert!(tree.get("Show/info.nfo/x").is_none());
+        assert!(tree.get("").is_none());
+    }
+
+    #[test]
+    fn reject_clashes() {
+        let normal = |index, name| file(index, name, 1, 0.0, FilePriority::Normal);
+
+        for files in [
+            vec![normal(0, "Show/e01"), normal(1, "Show")],
+            vec![normal(0, "Show"), normal(1, "Show/e01")],
+            vec![normal(0, "Show/e01"), normal(1, "Show/e01")],
+        ] {
+            assert!(FileTree::new(files).is_err());
+        }
+    }
+
+    #[test]
+    fn folder_summary() {
+        let summary = tree().get("Show/Season 1").unwrap().summary();
+
+        assert_eq!(summary.files, 3);
+        assert_eq!(summary.size, Bytes(410));
+        assert_eq!(summary.selected_size, Bytes(400));
+        assert_eq!(summary.progress, 0.75);
+        assert_eq!(summary.priority, None);
+        assert_eq!(summary.availability, Some(1.0));
+
+        let nfo = tree().get("Show/info.nfo").unwrap().summary();
+        assert_eq!(nfo.priority, Some(FilePriority::High));
+    }
+
+    #[test]
+    fn queries() {
+        let tree = tree();
+        let indexes =
+            |files: Vec<&TorrentContent>| files.iter().map(|f| f.index).collect::<Vec<_>>();
+
+        assert_eq!(indexes(tree.glob("*.mkv").unwrap()), vec![0]);
+        assert_eq!(indexes(tree.glob("Show/*").unwrap()), vec![3]);
+        assert_eq!(indexes(tree.glob("Show/**/e01.*").unwrap()), vec![0, 2]);
+        assert_eq!(indexes(tree.with_extension(&[".mkv"])), vec![0, 1]);
+    }
+}
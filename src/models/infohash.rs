@@ -0,0 +1,287 @@
+use std::{fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::error::Error;
+
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// Infohash identifying a torrent.
+///
+/// Holds either a BitTorrent v1 hash (SHA-1, 40 hex characters) or a
+/// BitTorrent v2 hash (SHA-256, 64 hex characters). The hash is stored in
+/// lower case so two hashes compare equal regardless of the case they were
+/// written in.
+///
+/// qBittorrent identifies v2 only torrents by their v2 hash truncated to 40
+/// characters. Such a truncated hash is a valid `InfoHash` and can be made
+/// from a full v2 hash with [`InfoHash::truncated`].
+///
+/// # Example
+///
+/// ```
+/// use qbit::models::InfoHash;
+///
+/// let hash: InfoHash = "8C212779B4ABDE7C6BC608063A0D008B7E40CE32".parse().unwrap();
+///
+/// assert_eq!(hash.to_string(), "8c212779b4abde7c6bc608063a0d008b7e40ce32");
+/// assert!("not a hash".parse::<InfoHash>().is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct InfoHash(String);
+
+impl InfoHash {
+    /// Length of a v1 hash, or truncated v2 hash, in hex characters
+    pub const V1_LEN: usize = 40;
+    /// Length of a v2 hash in hex characters
+    pub const V2_LEN: usize = 64;
+
+    /// Validates a hex encoded hash.
+    pub fn new(hash: &str) -> Result<Self, Error> {
+        let hash = hash.trim();
+
+        if !(hash.len() == Self::V1_LEN || hash.len() == Self::V2_LEN)
+            || !hash.bytes().all(|b| b.is_ascii_hexdigit())
+        {
+            return Err(Error::ParseError(format!("Invalid infohash: {:?}", hash)));
+        }
+
+        Ok(Self(hash.to_ascii_lowercase()))
+    }
+
+    /// Creates a hash from the raw SHA-1 (20 bytes) or SHA-256 (32 bytes)
+    /// digest.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() * 2 != Self::V1_LEN && bytes.len() * 2 != Self::V2_LEN {
+            return Err(Error::ParseError(format!(
+                "Invalid infohash length: {} bytes",
+                bytes.len()
+            )));
+        }
+
+        Ok(Self(bytes.iter().map(|b| format!("{:02x}", b)).collect()))
+    }
+
+    /// Decodes a base32 encoded v1 hash, as found in some magnet links
+    /// (`xt=urn:btih:<32 base32 characters>`).
+    pub fn from_base32(hash: &str) -> Result<Self, Error> {
+        let invalid = || Error::ParseError(format!("Invalid base32 infohash: {:?}", hash));
+
+        if hash.len() != 32 {
+            return Err(invalid());
+        }
+
+        let mut bytes = Vec::with_capacity(20);
+        let mut buffer: u64 = 0;
+        let mut bits = 0;
+
+        for c in hash.bytes() {
+            let value = BASE32_ALPHABET
+                .iter()
+                .position(|a| *a == c.to_ascii_uppercase())
+                .ok_or_else(invalid)?;
+
+            buffer = (buffer << 5) | value as u64;
+            bits += 5;
+
+            if bits >= 8 {
+                bits -= 8;
+                bytes.push((buffer >> bits) as u8);
+            }
+        }
+
+        Self::from_bytes(&bytes)
+    }
+
+    /// Parses a hash from a magnet link `xt` value: `urn:btih:` followed by a
+    /// hex or base32 v1 hash, or `urn:btmh:1220` followed by a hex v2 hash.
+    pub fn from_urn(urn: &str) -> Result<Self, Error> {
+        let lower = urn.to_ascii_lowercase();
+
+        if let Some(hash) = lower.strip_prefix("urn:btih:") {
+            let hash = &urn[urn.len() - hash.len()..];
+            if hash.len() == 32 {
+                Self::from_base32(hash)
+            } else if hash.len() == Self::V1_LEN {
+                Self::new(hash)
+            } else {
+                Err(Error::ParseError(format!("Invalid infohash: {:?}", urn)))
+            }
+        } else if let Some(hash) = lower.strip_prefix("urn:btmh:1220") {
+            match hash.len() {
+                Self::V2_LEN => Self::new(hash),
+                _ => Err(Error::ParseError(format!("Invalid infohash: {:?}", urn))),
+            }
+        } else {
+            Err(Error::ParseError(format!(
+                "Unsupported infohash urn: {:?}",
+                urn
+            )))
+        }
+    }
+
+    /// The lower case hex encoded hash
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The raw digest
+    pub fn to_bytes(&self) -> Vec<u8> {
+        (0..self.0.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&self.0[i..i + 2], 16).unwrap_or_default())
+            .collect()
+    }
+
+    /// True for a full 64 character v2 hash
+    pub fn is_v2(&self) -> bool {
+        self.0.len() == Self::V2_LEN
+    }
+
+    /// The hash as qBittorrent uses it to identify a torrent: a v1 hash is
+    /// kept as is and a v2 hash is truncated to 40 characters.
+    pub fn truncated(&self) -> Self {
+        Self(self.0[..Self::V1_LEN].to_string())
+    }
+
+    /// Joins hashes with `|` as expected by the `hashes` parameter.
+    pub(crate) fn join(hashes: &[InfoHash]) -> String {
+        hashes
+            .iter()
+            .map(InfoHash::as_str)
+            .collect::<Vec<_>>()
+            .join("|")
+    }
+}
+
+/// An all zero v1 hash
+impl Default for InfoHash {
+    fn default() -> Self {
+        Self("0".repeat(Self::V1_LEN))
+    }
+}
+
+impl Display for InfoHash {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromStr for InfoHash {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s)
+    }
+}
+
+impl TryFrom<&str> for InfoHash {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+impl TryFrom<String> for InfoHash {
+    type Error = Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::new(&value)
+    }
+}
+
+impl AsRef<str> for InfoHash {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl PartialEq<str> for InfoHash {
+    fn eq(&self, other: &str) -> bool {
+        self.0.eq_ignore_ascii_case(other)
+    }
+}
+
+impl PartialEq<&str> for InfoHash {
+    fn eq(&self, other: &&str) -> bool {
+        self.0.eq_ignore_ascii_case(other)
+    }
+}
+
+impl<'de> Deserialize<'de> for InfoHash {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let hash = String::deserialize(deserializer)?;
+
+        Self::new(&hash).map_err(serde::de::Error::custom)
+    }
+}
+
+impl Serialize for InfoHash {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const V1: &str = "c9e15763f722f23e98a29decdfae341b98d53056";
+    const V2: &str = "caf1e1c30e81cb361b9ee167c4aa64228a7fa4fa9f6105232b28ad099f3a302e";
+
+    #[test]
+    fn parse_and_normalise() {
+        let hash = V1.to_uppercase().parse::<InfoHash>().unwrap();
+
+        assert_eq!(hash.as_str(), V1);
+        assert!(!hash.is_v2());
+        assert!(V2.parse::<InfoHash>().unwrap().is_v2());
+    }
+
+    #[test]
+    fn reject_invalid() {
+        assert!(InfoHash::new("").is_err());
+        assert!(InfoHash::new(&V1[1..]).is_err());
+        assert!(InfoHash::new(&V1.replace('c', "x")).is_err());
+    }
+
+    #[test]
+    fn truncated_v2() {
+        let hash = InfoHash::new(V2).unwrap().truncated();
+
+        assert_eq!(hash.as_str(), &V2[..40]);
+    }
+
+    #[test]
+    fn base32_round_trip() {
+        // Base32 of the V1 digest
+        let hash = InfoHash::from_base32("ZHQVOY7XELZD5GFCTXWN7LRUDOMNKMCW").unwrap();
+
+        assert_eq!(hash.as_str(), V1);
+        assert_eq!(InfoHash::from_bytes(&hash.to_bytes()).unwrap(), hash);
+    }
+
+    #[test]
+    fn from_urn() {
+        assert_eq!(InfoHash::from_urn(&format!("urn:btih:{}", V1)).unwrap(), V1);
+        assert_eq!(
+            InfoHash::from_urn(&format!("urn:btmh:1220{}", V2)).unwrap(),
+            V2
+        );
+        assert!(InfoHash::from_urn("urn:sha1:abc").is_err());
+    }
+
+    #[test]
+    fn compare_with_str() {
+        let hash = InfoHash::new(V1).unwrap();
+
+        assert_eq!(hash, V1.to_uppercase().as_str());
+    }
+}
@@ -0,0 +1,310 @@
+use std::{fmt::Display, time::Duration};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::models::BytesPerSecond;
+
+/// Share ratio limit of a torrent
+///
+/// Sent and received as `-2` (global), `-1` (unlimited) or the ratio itself.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum ShareLimit {
+    /// Use the global limit
+    #[default]
+    Global,
+    /// No limit
+    Unlimited,
+    /// Stop seeding once this ratio is reached
+    Value(f64),
+}
+
+/// Seeding time limit of a torrent
+///
+/// Sent and received as `-2` (global), `-1` (unlimited) or the limit in
+/// minutes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SeedingTimeLimit {
+    /// Use the global limit
+    #[default]
+    Global,
+    /// No limit
+    Unlimited,
+    /// Stop seeding after this time. Only whole minutes are sent to the server.
+    Value(Duration),
+}
+
+/// Speed limit of a torrent or of the whole client
+///
+/// Sent and received as `0` (or negative) for no limit, or the limit in
+/// bytes/second.
+///
+/// Unlike [`ShareLimit`] and [`SeedingTimeLimit`] there is no `Global`
+/// variant: the server has no value meaning "use the global limit" for
+/// speed limits. The global limit always applies on top of the limit of a
+/// torrent, so a torrent with [`SpeedLimit::Unlimited`] is bound by the
+/// global limit only.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SpeedLimit {
+    /// No limit of its own. For a torrent, only the global limit applies.
+    #[default]
+    Unlimited,
+    /// Limit the speed to this rate
+    Value(BytesPerSecond),
+}
+
+/// What happens to a torrent once it reaches one of its share limits
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ShareLimitAction {
+    /// Use the global action
+    #[default]
+    Default,
+    /// Stop the torrent
+    Stop,
+    /// Remove the torrent, keeping its files
+    Remove,
+    /// Remove the torrent and its files
+    RemoveWithContent,
+    /// Keep seeding with super seeding enabled
+    EnableSuperSeeding,
+}
+
+impl ShareLimit {
+    /// Value as used by the server
+    pub fn to_raw(&self) -> f64 {
+        match self {
+            Self::Global => -2.0,
+            Self::Unlimited => -1.0,
+            Self::Value(ratio) => *ratio,
+        }
+    }
+
+    /// Decodes the value used by the server
+    pub fn from_raw(value: f64) -> Self {
+        if value < -1.5 {
+            Self::Global
+        } else if value < 0.0 {
+            Self::Unlimited
+        } else {
+            Self::Value(value)
+        }
+    }
+}
+
+impl SeedingTimeLimit {
+    /// Value in minutes as used by the server
+    pub fn to_raw(&self) -> i64 {
+        match self {
+            Self::Global => -2,
+            Self::Unlimited => -1,
+            Self::Value(limit) => (limit.as_secs() / 60) as i64,
+        }
+    }
+
+    /// Decodes the value in minutes used by the server
+    pub fn from_raw(value: i64) -> Self {
+        match value {
+            i64::MIN..=-2 => Self::Global,
+            -1 => Self::Unlimited,
+            minutes => Self::Value(Duration::from_secs(minutes as u64 * 60)),
+        }
+    }
+
+    /// Limit of the given number of minutes
+    pub fn from_minutes(minutes: u64) -> Self {
+        Self::Value(Duration::from_secs(minutes * 60))
+    }
+}
+
+impl SpeedLimit {
+    /// Value in bytes/second as used by the server
+    pub fn to_raw(&self) -> i64 {
+        match self {
+            Self::Unlimited => 0,
+            Self::Value(limit) => limit.as_i64(),
+        }
+    }
+
+    /// Decodes the value in bytes/second used by the server
+    pub fn from_raw(value: i64) -> Self {
+        if value <= 0 {
+            Self::Unlimited
+        } else {
+            Self::Value(BytesPerSecond(value))
+        }
+    }
+}
+
+impl From<f64> for ShareLimit {
+    fn from(value: f64) -> Self {
+        Self::Value(value)
+    }
+}
+
+impl From<Duration> for SeedingTimeLimit {
+    fn from(value: Duration) -> Self {
+        Self::Value(value)
+    }
+}
+
+impl From<BytesPerSecond> for SpeedLimit {
+    fn from(value: BytesPerSecond) -> Self {
+        Self::from_raw(value.as_i64())
+    }
+}
+
+impl Display for ShareLimitAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Default => write!(f, "Default"),
+            Self::Stop => write!(f, "Stop"),
+            Self::Remove => write!(f, "Remove"),
+            Self::RemoveWithContent => write!(f, "RemoveWithContent"),
+            Self::EnableSuperSeeding => write!(f, "EnableSuperSeeding"),
+        }
+    }
+}
+
+impl Display for ShareLimit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Global => write!(f, "global"),
+            Self::Unlimited => write!(f, "unlimited"),
+            Self::Value(ratio) => write!(f, "{:.2}", ratio),
+        }
+    }
+}
+
+impl Display for SeedingTimeLimit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Global => write!(f, "global"),
+            Self::Unlimited => write!(f, "unlimited"),
+            Self::Value(_) => write!(f, "{} min", self.to_raw()),
+        }
+    }
+}
+
+impl Display for SpeedLimit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unlimited => write!(f, "unlimited"),
+            Self::Value(limit) => limit.fmt(f),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ShareLimit {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        f64::deserialize(deserializer).map(Self::from_raw)
+    }
+}
+
+impl Serialize for ShareLimit {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_f64(self.to_raw())
+    }
+}
+
+impl<'de> Deserialize<'de> for SeedingTimeLimit {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        i64::deserialize(deserializer).map(Self::from_raw)
+    }
+}
+
+impl Serialize for SeedingTimeLimit {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_i64(self.to_raw())
+    }
+}
+
+impl<'de> Deserialize<'de> for SpeedLimit {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        i64::deserialize(deserializer).map(Self::from_raw)
+    }
+}
+
+impl Serialize for SpeedLimit {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_i64(self.to_raw())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn share_limit_from_raw() {
+        assert_eq!(ShareLimit::from_raw(-2.0), ShareLimit::Global);
+        assert_eq!(ShareLimit::from_raw(-1.0), ShareLimit::Unlimited);
+        assert_eq!(ShareLimit::from_raw(1.5), ShareLimit::Value(1.5));
+    }
+
+    #[test]
+    fn seeding_time_limit_round_trip() {
+        for raw in [-2, -1, 0, 90] {
+            assert_eq!(SeedingTimeLimit::from_raw(raw).to_raw(), raw);
+        }
+        assert_eq!(
+            SeedingTimeLimit::from_raw(90),
+            SeedingTimeLimit::from_minutes(90)
+        );
+    }
+
+    #[test]
+    fn speed_limit_from_raw() {
+        assert_eq!(SpeedLimit::from_raw(-1), SpeedLimit::Unlimited);
+        assert_eq!(SpeedLimit::from_raw(0), SpeedLimit::Unlimited);
+        assert_eq!(
+            SpeedLimit::from_raw(1024),
+            SpeedLimit::Value(BytesPerSecond(1024))
+        );
+    }
+
+    #[test]
+    fn serde_uses_raw_values() {
+        let limit: SeedingTimeLimit = serde_json::from_str("-2").unwrap();
+        assert_eq!(limit, SeedingTimeLimit::Global);
+
+        let limit: ShareLimit = serde_json::from_str("-1").unwrap();
+        assert_eq!(serde_json::to_string(&limit).unwrap(), "-1.0");
+    }
+
+    #[test]
+    fn torrent_effective_limits() {
+        let torrent = crate::models::Torrent {
+            ratio_limit: ShareLimit::Global,
+            max_ratio: 2.0,
+            seeding_time_limit: SeedingTimeLimit::Global,
+            max_seeding_time: -1,
+            inactive_seeding_time_limit: SeedingTimeLimit::from_minutes(30),
+            max_inactive_seeding_time: 60,
+            ..Default::default()
+        };
+
+        assert_eq!(torrent.effective_ratio_limit(), Some(2.0));
+        assert_eq!(torrent.effective_seeding_time_limit(), None);
+        assert_eq!(
+            torrent.effective_inactive_seeding_time_limit(),
+            Some(Duration::from_secs(30 * 60))
+        );
+    }
+}
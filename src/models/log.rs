@@ -1,8 +1,11 @@
 use std::fmt::Display;
 
+use bitflags::bitflags;
 use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
 
+use crate::models::FromTimestamp;
+
 /// Log item data object
 #[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 pub struct LogItem {
@@ -19,6 +22,13 @@ pub struct LogItem {
     pub log_type: LogType,
 }
 
+impl LogItem {
+    /// Time the message was logged
+    pub fn logged_at<T: FromTimestamp>(&self) -> Option<T> {
+        T::from_timestamp(self.timestamp)
+    }
+}
+
 /// Log types
 ///
 /// Log levels used by the logger
@@ -47,6 +57,52 @@ impl Display for LogType {
     }
 }
 
+bitflags! {
+    /// Set of log types
+    ///
+    /// Bitflags representation of [`LogType`], using the same values as the
+    /// server.
+    #[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct LogTypes: u8 {
+        const NORMAL = 1;
+        const INFO = 2;
+        const WARNING = 4;
+        const CRITICAL = 8;
+    }
+}
+
+impl LogTypes {
+    /// Returns true if the given log type is part of the set.
+    pub fn contains_type(&self, log_type: &LogType) -> bool {
+        self.contains(Self::from(log_type.clone()))
+    }
+}
+
+impl Default for LogTypes {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+impl From<LogType> for LogTypes {
+    fn from(value: LogType) -> Self {
+        Self::from_bits_truncate(value as u8)
+    }
+}
+
+impl FromIterator<LogType> for LogTypes {
+    fn from_iter<I: IntoIterator<Item = LogType>>(iter: I) -> Self {
+        iter.into_iter()
+            .fold(Self::empty(), |types, log_type| types | log_type.into())
+    }
+}
+
+impl From<Vec<LogType>> for LogTypes {
+    fn from(value: Vec<LogType>) -> Self {
+        value.into_iter().collect()
+    }
+}
+
 /// Peer log item data object
 #[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 pub struct LogPeers {
@@ -61,3 +117,34 @@ pub struct LogPeers {
     /// Reason of the block
     pub reason: String,
 }
+
+impl LogPeers {
+    /// Time the message was logged
+    pub fn logged_at<T: FromTimestamp>(&self) -> Option<T> {
+        T::from_timestamp(self.timestamp)
+    }
+}
+
+/// Position of a log tail.
+///
+/// Stores the last message that has been handed to the consumer of
+/// [`Api::log_stream`](crate::Api::log_stream) or
+/// [`Api::peer_log_stream`](crate::Api::peer_log_stream). The timestamp is
+/// kept next to the id so a restart of qBittorrent (which resets the ids) can
+/// be detected.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct LogCursor {
+    /// ID of the last seen message. `-1` if nothing has been seen yet.
+    pub last_known_id: i64,
+    /// Seconds since epoch of the last seen message.
+    pub timestamp: i64,
+}
+
+impl Default for LogCursor {
+    fn default() -> Self {
+        Self {
+            last_known_id: -1,
+            timestamp: 0,
+        }
+    }
+}
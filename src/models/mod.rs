@@ -8,22 +8,40 @@
 use serde::{Deserialize, Serialize};
 
 mod application;
+mod category_tree;
 mod creator;
+mod datetime;
+mod file_tree;
+mod infohash;
+mod limits;
 mod log;
+mod pieces;
+mod rename;
 mod rss;
 mod search;
 mod sync;
+mod tag;
 mod torrent;
 mod transfer;
+mod units;
 
 pub use application::*;
+pub use category_tree::*;
 pub use creator::*;
+pub use datetime::*;
+pub use file_tree::*;
+pub use infohash::*;
+pub use limits::*;
 pub use log::*;
+pub use pieces::*;
+pub use rename::*;
 pub use rss::*;
 pub use search::*;
 pub use sync::*;
+pub use tag::*;
 pub use torrent::*;
 pub use transfer::*;
+pub use units::*;
 
 /// Connection status of the Qbit application
 #[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
@@ -0,0 +1,406 @@
+use std::ops::Range;
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::{Bytes, PiecesState, TorrentContent};
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// Compact state of every piece of a torrent
+///
+/// Holds the same information as the list returned by
+/// [`Api::pieces_states`](crate::Api::pieces_states), with two bits per
+/// piece.
+///
+/// # Example
+///
+/// ```
+/// use qbit::models::{PieceMap, PiecesState};
+///
+/// let map = PieceMap::from(vec![
+///     PiecesState::Downloaded,
+///     PiecesState::Downloaded,
+///     PiecesState::Downloading,
+///     PiecesState::NotDownloaded,
+/// ]);
+///
+/// assert_eq!(map.counts().downloaded, 2);
+/// assert_eq!(map.ranges().next(), Some((PiecesState::Downloaded, 0..2)));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PieceMap {
+    len: usize,
+    downloaded: Vec<u64>,
+    downloading: Vec<u64>,
+}
+
+/// Download state of a file being streamed
+///
+/// Returned by [`Api::stream_file`](crate::Api::stream_file) and
+/// [`Api::stream_status`](crate::Api::stream_status).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileStream {
+    /// Index of the file in the torrent
+    pub index: i64,
+    /// Path of the file in the torrent
+    pub name: String,
+    /// Size of the file
+    pub size: Bytes,
+    /// Downloaded byte ranges of the file, relative to its start
+    pub readable: Vec<Range<u64>>,
+}
+
+impl FileStream {
+    /// Number of bytes that can be read from the start of the file
+    pub fn readable_prefix(&self) -> u64 {
+        self.readable
+            .first()
+            .filter(|range| range.start == 0)
+            .map(|range| range.end)
+            .unwrap_or_default()
+    }
+
+    /// True if the whole file is downloaded
+    pub fn is_complete(&self) -> bool {
+        self.readable_prefix() >= self.size.0.max(0) as u64
+    }
+}
+
+/// Number of pieces in each state
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PieceCounts {
+    /// Pieces not downloaded yet
+    pub not_downloaded: usize,
+    /// Pieces being downloaded
+    pub downloading: usize,
+    /// Pieces downloaded
+    pub downloaded: usize,
+}
+
+impl PieceCounts {
+    /// Total number of pieces
+    pub fn total(&self) -> usize {
+        self.not_downloaded + self.downloading + self.downloaded
+    }
+
+    /// Share of downloaded pieces, between 0 and 1. 0 if there are no pieces.
+    pub fn progress(&self) -> f64 {
+        match self.total() {
+            0 => 0.0,
+            total => self.downloaded as f64 / total as f64,
+        }
+    }
+}
+
+impl PieceMap {
+    /// Map of `len` pieces, none of them downloaded
+    pub fn new(len: usize) -> Self {
+        let words = len.div_ceil(WORD_BITS);
+
+        Self {
+            len,
+            downloaded: vec![0; words],
+            downloading: vec![0; words],
+        }
+    }
+
+    /// Number of pieces
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// True if the torrent has no pieces
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// State of a piece, `None` if out of range
+    pub fn get(&self, piece: usize) -> Option<PiecesState> {
+        if piece >= self.len {
+            return None;
+        }
+
+        let (word, bit) = (piece / WORD_BITS, 1 << (piece % WORD_BITS));
+        Some(if self.downloaded[word] & bit != 0 {
+            PiecesState::Downloaded
+        } else if self.downloading[word] & bit != 0 {
+            PiecesState::Downloading
+        } else {
+            PiecesState::NotDownloaded
+        })
+    }
+
+    /// Sets the state of a piece.
+    ///
+    /// # Panics
+    ///
+    /// If `piece` is out of range.
+    pub fn set(&mut self, piece: usize, state: PiecesState) {
+        assert!(piece < self.len, "Piece {} out of range", piece);
+
+        let (word, bit) = (piece / WORD_BITS, 1 << (piece % WORD_BITS));
+        self.downloaded[word] &= !bit;
+        self.downloading[word] &= !bit;
+        match state {
+            PiecesState::Downloaded => self.downloaded[word] |= bit,
+            PiecesState::Downloading => self.downloading[word] |= bit,
+            PiecesState::NotDownloaded => {}
+        }
+    }
+
+    /// Number of pieces in each state
+    pub fn counts(&self) -> PieceCounts {
+        self.counts_in(0..self.len)
+    }
+
+    /// Number of pieces in each state, among the pieces of `range`. The
+    /// range is clamped to the pieces of the map.
+    pub fn counts_in(&self, range: Range<usize>) -> PieceCounts {
+        let range = range.start.min(self.len)..range.end.min(self.len);
+        let downloaded = count_bits(&self.downloaded, range.clone());
+        let downloading = count_bits(&self.downloading, range.clone());
+
+        PieceCounts {
+            not_downloaded: range.len() - downloaded - downloading,
+            downloading,
+            downloaded,
+        }
+    }
+
+    /// Runs of consecutive pieces in the same state, in piece order
+    pub fn ranges(&self) -> impl Iterator<Item = (PiecesState, Range<usize>)> + '_ {
+        let mut start = 0;
+
+        std::iter::from_fn(move || {
+            let state = self.get(start)?;
+            let mut end = start + 1;
+            while self.get(end) == Some(state) {
+                end += 1;
+            }
+
+            let range = start..end;
+            start = end;
+            Some((state, range))
+        })
+    }
+
+    /// Runs of consecutive pieces in `state`, in piece order
+    pub fn ranges_of(&self, state: PiecesState) -> impl Iterator<Item = Range<usize>> + '_ {
+        self.ranges()
+            .filter(move |(s, _)| *s == state)
+            .map(|(_, range)| range)
+    }
+
+    /// Number of pieces in each state, among the pieces of `file`.
+    ///
+    /// Uses [`TorrentContent::piece_range`]. Files without a piece range
+    /// have no pieces.
+    pub fn file_counts(&self, file: &TorrentContent) -> PieceCounts {
+        match file.piece_range.as_slice() {
+            [start, end] if *start >= 0 && end >= start => {
+                self.counts_in(*start as usize..*end as usize + 1)
+            }
+            _ => PieceCounts::default(),
+        }
+    }
+
+    /// Share of downloaded pieces of every file, in the order of `files`
+    pub fn file_progress(&self, files: &[TorrentContent]) -> Vec<f64> {
+        files
+            .iter()
+            .map(|file| self.file_counts(file).progress())
+            .collect()
+    }
+
+    /// Downloaded byte ranges of a file of `length` bytes starting at byte
+    /// `offset` of the torrent data, relative to the start of the file.
+    /// Adjacent downloaded pieces give one range.
+    pub fn byte_ranges(&self, piece_size: u64, offset: u64, length: u64) -> Vec<Range<u64>> {
+        if piece_size == 0 || length == 0 {
+            return Vec::new();
+        }
+
+        let first = (offset / piece_size) as usize;
+        let last = ((offset + length - 1) / piece_size) as usize;
+
+        self.ranges_of(PiecesState::Downloaded)
+            .filter(|pieces| pieces.start <= last && pieces.end > first)
+            .map(|pieces| {
+                let start = (pieces.start as u64 * piece_size).max(offset);
+                let end = (pieces.end as u64 * piece_size).min(offset + length);
+                start - offset..end - offset
+            })
+            .collect()
+    }
+
+    /// Splits the pieces into `buckets` consecutive groups of about the
+    /// same size and counts the states in each, to draw a progress bar
+    /// `buckets` cells wide.
+    ///
+    /// With more buckets than pieces, a piece spans several buckets. An
+    /// empty map gives empty buckets.
+    pub fn buckets(&self, buckets: usize) -> Vec<PieceCounts> {
+        (0..buckets)
+            .map(|bucket| {
+                let start = bucket * self.len / buckets;
+                let end = ((bucket + 1) * self.len / buckets).max(start + 1);
+                self.counts_in(start..end)
+            })
+            .collect()
+    }
+}
+
+impl FromIterator<PiecesState> for PieceMap {
+    fn from_iter<T: IntoIterator<Item = PiecesState>>(iter: T) -> Self {
+        let states: Vec<PiecesState> = iter.into_iter().collect();
+        PieceMap::from(states.as_slice())
+    }
+}
+
+impl From<&[PiecesState]> for PieceMap {
+    fn from(value: &[PiecesState]) -> Self {
+        let mut map = PieceMap::new(value.len());
+        for (piece, state) in value.iter().enumerate() {
+            map.set(piece, *state);
+        }
+        map
+    }
+}
+
+impl From<Vec<PiecesState>> for PieceMap {
+    fn from(value: Vec<PiecesState>) -> Self {
+        PieceMap::from(value.as_slice())
+    }
+}
+
+impl From<&PieceMap> for Vec<PiecesState> {
+    fn from(value: &PieceMap) -> Self {
+        (0..value.len())
+            .filter_map(|piece| value.get(piece))
+            .collect()
+    }
+}
+
+/// Number of set bits of `words` in `range`
+fn count_bits(words: &[u64], range: Range<usize>) -> usize {
+    if range.is_empty() {
+        return 0;
+    }
+
+    let (first, last) = (range.start / WORD_BITS, (range.end - 1) / WORD_BITS);
+    (first..=last)
+        .map(|word| {
+            let mut bits = words[word];
+            if word == first {
+                bits &= u64::MAX << (range.start % WORD_BITS);
+            }
+            if word == last {
+                bits &= u64::MAX >> (WORD_BITS - 1 - (range.end - 1) % WORD_BITS);
+            }
+            bits.count_ones() as usize
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use PiecesState::*;
+
+    fn map(pattern: &str) -> PieceMap {
+        pattern
+            .chars()
+            .map(|c| match c {
+                '#' => Downloaded,
+                '~' => Downloading,
+                _ => NotDownloaded,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn counts_and_round_trip() {
+        let states: Vec<_> = (0..200)
+            .map(|i| match i % 3 {
+                0 => Downloaded,
+                1 => Downloading,
+                _ => NotDownloaded,
+            })
+            .collect();
+        let map = PieceMap::from(states.clone());
+
+        assert_eq!(map.len(), 200);
+        assert_eq!(
+            map.counts(),
+            PieceCounts {
+                not_downloaded: 66,
+                downloading: 67,
+                downloaded: 67,
+            }
+        );
+        assert_eq!(map.counts_in(60..130).total(), 70);
+        assert_eq!(Vec::from(&map), states);
+        assert_eq!(map.get(200), None);
+    }
+
+    #[test]
+    fn ranges() {
+        let map = map("##~..#");
+
+        assert_eq!(
+            map.ranges().collect::<Vec<_>>(),
+            vec![
+                (Downloaded, 0..2),
+                (Downloading, 2..3),
+                (NotDownloaded, 3..5),
+                (Downloaded, 5..6),
+            ]
+        );
+        assert_eq!(
+            map.ranges_of(Downloaded).collect::<Vec<_>>(),
+            vec![0..2, 5..6]
+        );
+        assert_eq!(PieceMap::default().ranges().count(), 0);
+    }
+
+    #[test]
+    fn file_progress() {
+        let map = map("####..");
+        let file = |range: Vec<i64>| TorrentContent {
+            piece_range: range,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            map.file_progress(&[file(vec![0, 1]), file(vec![3, 4]), file(vec![])]),
+            vec![1.0, 0.5, 0.0]
+        );
+    }
+
+    #[test]
+    fn buckets() {
+        let map = map("##..~.");
+
+        let progress: Vec<_> = map.buckets(3).iter().map(|b| b.progress()).collect();
+        assert_eq!(progress, vec![1.0, 0.0, 0.0]);
+        assert_eq!(map.buckets(3)[2].downloading, 1);
+        assert_eq!(map.buckets(12).len(), 12);
+        assert!(map.buckets(12).iter().all(|b| b.total() == 1));
+    }
+
+    #[test]
+    fn byte_ranges() {
+        // Pieces of 10 bytes, file from byte 15 to 45
+        let map = map("##.###");
+        assert_eq!(map.byte_ranges(10, 15, 30), vec![0..5, 15..30]);
+
+        let stream = FileStream {
+            size: Bytes(30),
+            readable: map.byte_ranges(10, 15, 30),
+            ..Default::default()
+        };
+        assert_eq!(stream.readable_prefix(), 5);
+        assert!(!stream.is_complete());
+    }
+}
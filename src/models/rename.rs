@@ -0,0 +1,287 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt::Display,
+};
+
+use serde::{Deserialize, Serialize};
+
+/// One file to rename
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileRename {
+    /// Index of the file in the torrent
+    pub index: i64,
+    /// Current `/` separated path of the file
+    pub old_path: String,
+    /// New `/` separated path of the file
+    pub new_path: String,
+}
+
+/// Several files renamed to the same path, or a file renamed to a path in
+/// the way of another file: its path, a folder holding it, or a path inside
+/// it when it is a file
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RenameCollision {
+    /// The path renamed to
+    pub new_path: String,
+    /// Current paths of the files renamed to `new_path`
+    pub sources: Vec<String>,
+    /// True if a file that is not renamed is in the way
+    pub existing: bool,
+}
+
+/// Renames planned by a [`BatchRename`](crate::parameters::BatchRename)
+///
+/// Displays as one `old -> new` line per rename, followed by the
+/// collisions, as a dry run output.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RenamePlan {
+    /// The renames, in file order. Files keeping their name are left out.
+    pub renames: Vec<FileRename>,
+    /// Renames that would overwrite another file. The plan cannot be applied
+    /// while there are collisions.
+    pub collisions: Vec<RenameCollision>,
+    /// The requests to send, in order, so no file is renamed to a path still
+    /// in use. Files renamed in a cycle go through a temporary name.
+    pub steps: Vec<FileRename>,
+}
+
+impl RenamePlan {
+    /// Plans `renames` among the files of a torrent with `paths`.
+    pub fn new(renames: Vec<FileRename>, paths: &[String]) -> Self {
+        let renames: Vec<FileRename> = renames
+            .into_iter()
+            .filter(|rename| rename.old_path != rename.new_path)
+            .collect();
+
+        let renamed: HashSet<&str> = renames.iter().map(|r| r.old_path.as_str()).collect();
+        let mut targets: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+        for rename in &renames {
+            targets
+                .entry(rename.new_path.as_str())
+                .or_default()
+                .push(rename.old_path.clone());
+        }
+
+        let kept: HashSet<&str> = paths
+            .iter()
+            .map(String::as_str)
+            .filter(|path| !renamed.contains(path))
+            .collect();
+        let kept_folders: HashSet<&str> = kept.iter().flat_map(|path| folders(path)).collect();
+        let target_folders: HashSet<&str> = targets.keys().flat_map(|path| folders(path)).collect();
+
+        let collisions = targets
+            .iter()
+            .filter_map(|(new_path, sources)| {
+                let existing = kept.contains(new_path)
+                    || kept_folders.contains(new_path)
+                    || folders(new_path).any(|folder| kept.contains(folder));
+                let nested = target_folders.contains(new_path)
+                    || folders(new_path).any(|folder| targets.contains_key(folder));
+                (existing || nested || sources.len() > 1).then(|| RenameCollision {
+                    new_path: new_path.to_string(),
+                    sources: sources.clone(),
+                    existing,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let steps = match collisions.is_empty() {
+            true => order(&renames, paths),
+            false => Vec::new(),
+        };
+
+        Self {
+            renames,
+            collisions,
+            steps,
+        }
+    }
+
+    /// True if the plan has no collisions
+    pub fn is_valid(&self) -> bool {
+        self.collisions.is_empty()
+    }
+}
+
+impl Display for RenamePlan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for rename in &self.renames {
+            writeln!(f, "{} -> {}", rename.old_path, rename.new_path)?;
+        }
+        for collision in &self.collisions {
+            write!(
+                f,
+                "collision: {} <- {}",
+                collision.new_path,
+                collision.sources.join(", ")
+            )?;
+            if collision.existing {
+                write!(f, " (existing file)")?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Folders holding the file at `path`, outermost first
+fn folders(path: &str) -> impl Iterator<Item = &str> {
+    path.match_indices('/')
+        .map(move |(index, _)| &path[..index])
+}
+
+/// True if files at `a` and `b` cannot both exist: the paths are the same,
+/// or one is a folder holding the other.
+fn clashes(a: &str, b: &str) -> bool {
+    let inside = |path: &str, folder: &str| {
+        path.strip_prefix(folder)
+            .is_some_and(|rest| rest.starts_with('/'))
+    };
+    a == b || inside(a, b) || inside(b, a)
+}
+
+/// Orders `renames` so every target path is free when renamed to. Cycles
+/// are broken by moving one file to a temporary path first.
+fn order(renames: &[FileRename], paths: &[String]) -> Vec<FileRename> {
+    let mut used: HashSet<String> = paths.iter().cloned().collect();
+    used.extend(renames.iter().map(|r| r.new_path.clone()));
+
+    let mut pending: Vec<FileRename> = renames.to_vec();
+    let mut steps = Vec::new();
+
+    while !pending.is_empty() {
+        let sources: Vec<String> = pending.iter().map(|r| r.old_path.clone()).collect();
+        let (ready, blocked): (Vec<_>, Vec<_>) = pending.into_iter().partition(|rename| {
+            !sources
+                .iter()
+                .any(|source| clashes(source, &rename.new_path))
+        });
+        pending = blocked;
+
+        if ready.is_empty() {
+            // Every pending rename waits on another one: a cycle.
+            let first = &mut pending[0];
+            let temporary = (0..)
+                .map(|n| format!("{}.renaming-{}", first.old_path, n))
+                .find(|path| !used.contains(path))
+                .unwrap_or_default();
+            used.insert(temporary.clone());
+
+            steps.push(FileRename {
+                index: first.index,
+                old_path: std::mem::replace(&mut first.old_path, temporary.clone()),
+                new_path: temporary,
+            });
+        }
+
+        steps.extend(ready);
+    }
+
+    steps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rename(index: i64, old_path: &str, new_path: &str) -> FileRename {
+        FileRename {
+            index,
+            old_path: old_path.to_string(),
+            new_path: new_path.to_string(),
+        }
+    }
+
+    fn paths(paths: &[&str]) -> Vec<String> {
+        paths.iter().map(|p| p.to_string()).collect()
+    }
+
+    #[test]
+    fn order_chain_and_cycle() {
+        let plan = RenamePlan::new(
+            vec![
+                rename(0, "a", "b"),
+                rename(1, "b", "c"),
+                rename(2, "x", "x"),
+            ],
+            &paths(&["a", "b", "x"]),
+        );
+        assert!(plan.is_valid());
+        assert_eq!(plan.renames.len(), 2);
+        assert_eq!(plan.steps, vec![rename(1, "b", "c"), rename(0, "a", "b")]);
+
+        let plan = RenamePlan::new(
+            vec![rename(0, "a", "b"), rename(1, "b", "a")],
+            &paths(&["a", "b"]),
+        );
+        assert_eq!(
+            plan.steps,
+            vec![
+                rename(0, "a", "a.renaming-0"),
+                rename(1, "b", "a"),
+                rename(0, "a.renaming-0", "b"),
+            ]
+        );
+    }
+
+    #[test]
+    fn collisions() {
+        let plan = RenamePlan::new(
+            vec![
+                rename(0, "a", "c"),
+                rename(1, "b", "c"),
+                rename(2, "d", "e"),
+            ],
+            &paths(&["a", "b", "d", "e"]),
+        );
+
+        assert!(!plan.is_valid());
+        assert!(plan.steps.is_empty());
+        assert_eq!(plan.collisions.len(), 2);
+        assert_eq!(plan.collisions[0].sources, vec!["a", "b"]);
+        assert!(plan.collisions[1].existing);
+        assert_eq!(
+            plan.to_string(),
+            "a -> c\nb -> c\nd -> e\ncollision: c <- a, b\ncollision: e <- d (existing file)\n"
+        );
+    }
+
+    #[test]
+    fn folder_collisions() {
+        let plan = RenamePlan::new(
+            vec![rename(0, "b", "a/b"), rename(1, "e1", "Show")],
+            &paths(&["a", "b", "e1", "Show/e2"]),
+        );
+        assert_eq!(plan.collisions.len(), 2);
+        assert!(plan.collisions.iter().all(|c| c.existing));
+
+        let plan = RenamePlan::new(
+            vec![rename(0, "x", "c"), rename(1, "y", "c/d")],
+            &paths(&["x", "y"]),
+        );
+        assert_eq!(plan.collisions.len(), 2);
+        assert!(plan.collisions.iter().all(|c| !c.existing));
+
+        let plan = RenamePlan::new(
+            vec![rename(0, "a", "x"), rename(1, "b", "a/b")],
+            &paths(&["a", "b"]),
+        );
+        assert!(plan.is_valid());
+        assert_eq!(plan.steps, vec![rename(0, "a", "x"), rename(1, "b", "a/b")]);
+
+        let plan = RenamePlan::new(
+            vec![rename(0, "a", "b/x"), rename(1, "b", "a/y")],
+            &paths(&["a", "b"]),
+        );
+        assert_eq!(
+            plan.steps,
+            vec![
+                rename(0, "a", "a.renaming-0"),
+                rename(1, "b", "a/y"),
+                rename(0, "a.renaming-0", "b/x"),
+            ]
+        );
+    }
+}
@@ -1,8 +1,8 @@
 use std::collections::HashMap;
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-use crate::models::{ConnectionStatus, TorrentsMap};
+use crate::models::{Bytes, BytesPerSecond, ConnectionStatus, InfoHash, TorrentsMap};
 
 /// Main response data object
 #[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
@@ -16,7 +16,7 @@ pub struct MainData {
     /// Property: torrent hash, value: TorrentInfo
     pub torrents: Option<TorrentsMap>,
     /// List of hashes of torrents removed since last request
-    pub torrents_removed: Option<Vec<String>>,
+    pub torrents_removed: Option<Vec<InfoHash>>,
     /// Info for categories added since last request
     pub categories: Option<HashMap<String, Category>>,
     /// List of categories removed since last request
@@ -36,31 +36,83 @@ pub struct MainData {
 pub struct Category {
     /// Category name
     pub name: String,
-    /// Category save path
+    /// Category save path. Empty if the default save path is used.
     #[serde(rename = "savePath")]
     pub save_path: String,
+    /// Category download path
+    #[serde(default, alias = "downloadPath")]
+    pub download_path: CategoryDownloadPath,
+}
+
+/// Download path of a category
+///
+/// The server sends nothing when the global setting is used, `false` when
+/// the download path is disabled, or the path itself.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum CategoryDownloadPath {
+    /// Use the global download path setting
+    #[default]
+    Global,
+    /// Never use a download path for this category
+    Disabled,
+    /// Download incomplete torrents of this category to this path
+    Path(String),
+}
+
+impl<'de> Deserialize<'de> for CategoryDownloadPath {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Enabled(bool),
+            Path(String),
+        }
+
+        Ok(match Option::<Raw>::deserialize(deserializer)? {
+            None => Self::Global,
+            Some(Raw::Enabled(false)) => Self::Disabled,
+            Some(Raw::Enabled(true)) => Self::Global,
+            Some(Raw::Path(path)) => Self::Path(path),
+        })
+    }
+}
+
+impl Serialize for CategoryDownloadPath {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Self::Global => serializer.serialize_none(),
+            Self::Disabled => serializer.serialize_bool(false),
+            Self::Path(path) => serializer.serialize_str(path),
+        }
+    }
 }
 
 /// Server state response data object.
 #[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 pub struct ServerState {
     /// Alltime download
-    pub alltime_dl: i64,
+    pub alltime_dl: Bytes,
     /// Alltime upload
-    pub alltime_ul: i64,
+    pub alltime_ul: Bytes,
     pub average_time_queue: i64,
     /// Connection status
     pub connection_status: ConnectionStatus,
     /// DHT nodes
     pub dht_nodes: i64,
     /// Download data
-    pub dl_info_data: i64,
+    pub dl_info_data: Bytes,
     /// Download speed
-    pub dl_info_speed: i64,
+    pub dl_info_speed: BytesPerSecond,
     /// Download rate limit
-    pub dl_rate_limit: i64,
+    pub dl_rate_limit: BytesPerSecond,
     /// Free disk space
-    pub free_space_on_disk: i64,
+    pub free_space_on_disk: Bytes,
     /// Global ratio
     pub global_ratio: String, // Is float in format of string
     /// Last external IPv4 address
@@ -75,18 +127,18 @@ pub struct ServerState {
     /// Refresh Interval
     pub refresh_interval: i64,
     /// Total buffer size
-    pub total_buffers_size: i64,
+    pub total_buffers_size: Bytes,
     /// Total peer connections
     pub total_peer_connections: i64,
     /// Total queued size
-    pub total_queued_size: i64,
-    pub total_wasted_session: i64,
+    pub total_queued_size: Bytes,
+    pub total_wasted_session: Bytes,
     /// Upload data
-    pub up_info_data: i64,
+    pub up_info_data: Bytes,
     /// Upload speed
-    pub up_info_speed: i64,
+    pub up_info_speed: BytesPerSecond,
     /// Upload rate limit
-    pub up_rate_limit: i64,
+    pub up_rate_limit: BytesPerSecond,
     /// Alt speed enabeld
     pub use_alt_speed_limits: bool,
     /// Use subcategories
@@ -120,9 +172,9 @@ pub struct Peer {
     /// country code
     pub country_code: Option<String>,
     /// Download speed
-    pub dl_speed: Option<i64>,
+    pub dl_speed: Option<BytesPerSecond>,
     /// Total downloaded
-    pub downloaded: Option<i64>,
+    pub downloaded: Option<Bytes>,
     /// Files/contents
     pub files: Option<String>,
     /// Flags
@@ -138,7 +190,34 @@ pub struct Peer {
     pub progress: Option<f32>,
     pub relevance: Option<f32>,
     /// Upload speed
-    pub up_speed: Option<i64>,
+    pub up_speed: Option<BytesPerSecond>,
     /// Total uploaded
-    pub uploaded: Option<i64>,
+    pub uploaded: Option<Bytes>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn category_download_path() {
+        let categories: HashMap<String, Category> = serde_json::from_str(
+            r#"{
+                "a": {"name": "a", "savePath": "/a"},
+                "b": {"name": "b", "savePath": "", "download_path": false},
+                "c": {"name": "c", "savePath": "/c", "download_path": "/tmp/c"}
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(categories["a"].download_path, CategoryDownloadPath::Global);
+        assert_eq!(
+            categories["b"].download_path,
+            CategoryDownloadPath::Disabled
+        );
+        assert_eq!(
+            categories["c"].download_path,
+            CategoryDownloadPath::Path("/tmp/c".to_string())
+        );
+    }
 }
@@ -0,0 +1,87 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::{Bytes, Torrent};
+
+/// How much a tag is used
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TagUsage {
+    /// Number of torrents with the tag
+    pub torrents: usize,
+    /// Total size of the files selected for download in those torrents
+    pub size: Bytes,
+    /// Total size of those torrents, including unselected files
+    pub total_size: Bytes,
+}
+
+impl TagUsage {
+    /// Usage of every tag, by tag name.
+    ///
+    /// Every tag of `tags` is included, even if no torrent uses it. Tags
+    /// found on torrents but missing from `tags` are included as well.
+    pub fn collect(
+        tags: impl IntoIterator<Item = String>,
+        torrents: &[Torrent],
+    ) -> HashMap<String, TagUsage> {
+        let mut usage: HashMap<String, TagUsage> = tags
+            .into_iter()
+            .map(|tag| (tag, TagUsage::default()))
+            .collect();
+
+        for torrent in torrents {
+            for tag in torrent.tag_list() {
+                let entry = usage.entry(tag).or_default();
+                entry.torrents += 1;
+                entry.size += torrent.size;
+                entry.total_size += torrent.total_size;
+            }
+        }
+
+        usage
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn torrent(tags: &str, size: i64) -> Torrent {
+        Torrent {
+            tags: tags.to_string(),
+            size: Bytes(size),
+            total_size: Bytes(size * 2),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn parse_tag_list() {
+        assert_eq!(torrent("linux, iso,", 0).tag_list(), vec!["linux", "iso"]);
+        assert!(torrent("", 0).tag_list().is_empty());
+    }
+
+    #[test]
+    fn collect_usage() {
+        let torrents = vec![
+            torrent("linux, iso", 10),
+            torrent("linux", 5),
+            torrent("", 1),
+        ];
+        let tags = vec!["linux".to_string(), "unused".to_string()];
+
+        let usage = TagUsage::collect(tags, &torrents);
+
+        assert_eq!(usage.len(), 3);
+        assert_eq!(
+            usage["linux"],
+            TagUsage {
+                torrents: 2,
+                size: Bytes(15),
+                total_size: Bytes(30),
+            }
+        );
+        assert_eq!(usage["iso"].torrents, 1);
+        assert_eq!(usage["unused"], TagUsage::default());
+    }
+}
@@ -1,4 +1,4 @@
-use std::{collections::HashMap, fmt, ops::Deref};
+use std::{collections::HashMap, fmt, ops::Deref, time::Duration};
 
 use serde::{
     Deserialize, Deserializer, Serialize,
@@ -6,8 +6,14 @@ use serde::{
 };
 use serde_repr::{Deserialize_repr, Serialize_repr};
 
+use crate::error::Error;
+use crate::metainfo::MagnetLink;
+use crate::models::{
+    Bytes, BytesPerSecond, FileSelectionPlan, FromTimestamp, InfoHash, SeedingTimeLimit,
+    ShareLimit, SpeedLimit,
+};
 use crate::parameters::TorrentState;
-use crate::utiles::deserializers;
+use crate::utiles::{deserializers, timestamp};
 
 /// Represents a torrent and its associated metadata.
 ///
@@ -19,7 +25,7 @@ pub struct Torrent {
     /// Time (Unix Epoch) when the torrent was added to the client
     pub added_on: i64,
     /// Amount of data left to download (bytes)
-    pub amount_left: i64,
+    pub amount_left: Bytes,
     /// Whether this torrent is managed by Automatic Torrent Management
     pub auto_tmm: bool,
     /// Percentage of file pieces currently available
@@ -29,7 +35,7 @@ pub struct Torrent {
     /// Torrent comment metadata form the `.torrent` file
     pub comment: String,
     /// Amount of transfer data completed (bytes)
-    pub completed: i64,
+    pub completed: Bytes,
     /// Time (Unix Epoch) when the torrent completed
     pub completion_on: i64,
     /// Root path for multifile torrents, absolute file path for singlefile torrents
@@ -46,14 +52,15 @@ pub struct Torrent {
     ///
     /// Empty when not used.
     pub download_path: String,
-    /// Torrent download speed limit (bytes/s). -1 if unlimited.
-    pub dl_limit: i64,
+    /// Torrent download speed limit. The global limit still applies when
+    /// unlimited.
+    pub dl_limit: SpeedLimit,
     /// Torrent download speed (bytes/s)
-    pub dlspeed: i64,
+    pub dlspeed: BytesPerSecond,
     /// Amount of data downloaded
-    pub downloaded: i64,
+    pub downloaded: Bytes,
     /// Amount of data downloaded this session
-    pub downloaded_session: i64,
+    pub downloaded_session: Bytes,
     /// Torrent ETA (seconds)
     pub eta: i64,
     /// True if first last piece are prioritized
@@ -66,36 +73,35 @@ pub struct Torrent {
     /// have a undefinde value and might be using a default value or `None`
     pub has_metadata: bool,
     /// Torrent hash
-    pub hash: String,
+    pub hash: InfoHash,
     /// Torrent elapsed time while complete (seconds)
     pub seeding_time: i64,
     /// The maximum amount of time (minutes) the torrent is allowed to seed before stopped.
     ///
     /// This field is used to override the global setting for this specific torrent.
     ///
-    /// - `-2` means the global limit should be used. `max_seeding_time`
-    ///   will have the global setting set.
-    /// - `-1` means no limit.
-    pub seeding_time_limit: i64,
+    /// When set to [`SeedingTimeLimit::Global`], `max_seeding_time` will
+    /// have the global setting set. See [`Torrent::effective_seeding_time_limit`].
+    pub seeding_time_limit: SeedingTimeLimit,
     /// The maximum amount of time (minutes) the torrent is allowed to seed before stopped.
     ///
     /// - `-1` means no limit.
     ///
-    /// Uses global limit if `seeding_time_limit` is set to `-2`.
+    /// Uses global limit if `seeding_time_limit` is set to global.
     pub max_seeding_time: i64,
     /// The maximum amount of time (minutes) the torrent is allowed to seed while being inactive before stopped.
     ///
     /// This field is used to override the global setting for this specific torrent.
     ///
-    /// - `-2` means the global limit should be used. `max_inactive_seeding_time`
-    ///   will have the global setting set.
-    /// - `-1` means no limit.
-    pub inactive_seeding_time_limit: i64,
+    /// When set to [`SeedingTimeLimit::Global`], `max_inactive_seeding_time`
+    /// will have the global setting set. See
+    /// [`Torrent::effective_inactive_seeding_time_limit`].
+    pub inactive_seeding_time_limit: SeedingTimeLimit,
     /// The maximum amount of time (minutes) the torrent is allowed to seed while being inactive before stopped.
     ///
     /// - `-1` means no limit.
     ///
-    /// Uses global limit if `inactive_seeding_time_limit` is set to `-2`.
+    /// Uses global limit if `inactive_seeding_time_limit` is set to global.
     pub max_inactive_seeding_time: i64,
     /// Torrent share ratio. Max ratio value: 9999.
     pub ratio: f32,
@@ -103,20 +109,25 @@ pub struct Torrent {
     ///
     /// This field is used to override the global setting for this specific torrent.
     ///
-    /// - `-2` means the global limit should be used. `max_ratio` will have the
-    ///   global setting set.
-    /// - `-1` means no limit.
-    pub ratio_limit: f32,
+    /// When set to [`ShareLimit::Global`], `max_ratio` will have the global
+    /// setting set. See [`Torrent::effective_ratio_limit`].
+    pub ratio_limit: ShareLimit,
     /// Maximum share ratio until torrent is stopped from seeding/uploading
     ///
     /// - `-1` means no limit.
     ///
-    /// Uses global limit if `ratio_limit` is set to `-2`.
+    /// Uses global limit if `ratio_limit` is set to global.
     pub max_ratio: f32,
     /// The SHA-1 hash of the torrent's info dictionary (used in BitTorrent v1).
-    pub infohash_v1: String,
+    ///
+    /// `None` for v2 only torrents.
+    #[serde(deserialize_with = "deserializers::from_empty_string_to_none")]
+    pub infohash_v1: Option<InfoHash>,
     ///  SHA-256 hash of the torrent's info dictionary (used in BitTorrent v2).
-    pub infohash_v2: String,
+    ///
+    /// `None` for v1 only torrents.
+    #[serde(deserialize_with = "deserializers::from_empty_string_to_none")]
+    pub infohash_v2: Option<InfoHash>,
     /// Last time (Unix Epoch) when a chunk was downloaded/uploaded
     pub last_activity: i64,
     /// Magnet URI corresponding to this torrent
@@ -150,7 +161,7 @@ pub struct Torrent {
     /// True if sequential download is enabled
     pub seq_dl: bool,
     /// Total size (bytes) of files selected for download
-    pub size: i64,
+    pub size: Bytes,
     /// State that the torrent is currently in.
     pub state: TorrentState,
     /// True if super seeding is enabled
@@ -160,19 +171,114 @@ pub struct Torrent {
     /// Total active time (seconds)
     pub time_active: i64,
     /// Total size (bytes) of all file in this torrent (including unselected ones)
-    pub total_size: i64,
+    pub total_size: Bytes,
     /// The first tracker with working status. Returns empty string if no tracker is working.
     pub tracker: String,
     /// Totall count of trackers
     pub trackers_count: i64,
-    /// Torrent upload speed limit (bytes/s). -1 if unlimited.
-    pub up_limit: i64,
+    /// Torrent upload speed limit. The global limit still applies when
+    /// unlimited.
+    pub up_limit: SpeedLimit,
     /// Amount of data uploaded
-    pub uploaded: i64,
+    pub uploaded: Bytes,
     /// Amount of data uploaded this session
-    pub uploaded_session: i64,
+    pub uploaded_session: Bytes,
     /// Torrent upload speed (bytes/s)
-    pub upspeed: i64,
+    pub upspeed: BytesPerSecond,
+}
+
+impl Torrent {
+    /// Time when the torrent was added to the client
+    pub fn added_at<T: FromTimestamp>(&self) -> Option<T> {
+        timestamp::instant(self.added_on).and_then(T::from_timestamp)
+    }
+
+    /// Time when the torrent completed. `None` if it has not completed yet.
+    pub fn completed_at<T: FromTimestamp>(&self) -> Option<T> {
+        timestamp::instant(self.completion_on).and_then(T::from_timestamp)
+    }
+
+    /// Last time when a chunk was downloaded/uploaded. `None` if never.
+    pub fn last_activity_at<T: FromTimestamp>(&self) -> Option<T> {
+        timestamp::instant(self.last_activity).and_then(T::from_timestamp)
+    }
+
+    /// Time when this torrent was last seen complete. `None` if never.
+    pub fn seen_complete_at<T: FromTimestamp>(&self) -> Option<T> {
+        timestamp::instant(self.seen_complete).and_then(T::from_timestamp)
+    }
+
+    /// Torrent ETA. `None` if the torrent is not expected to finish.
+    pub fn eta_duration(&self) -> Option<Duration> {
+        timestamp::eta(self.eta)
+    }
+
+    /// Torrent elapsed time while complete
+    pub fn seeding_duration(&self) -> Option<Duration> {
+        timestamp::duration(self.seeding_time)
+    }
+
+    /// Total active time
+    pub fn active_duration(&self) -> Option<Duration> {
+        timestamp::duration(self.time_active)
+    }
+
+    /// Time until the next tracker reannounce
+    pub fn reannounce_duration(&self) -> Option<Duration> {
+        timestamp::duration(self.reannounce)
+    }
+
+    /// The magnet URI, parsed
+    pub fn magnet_link(&self) -> Result<MagnetLink, Error> {
+        MagnetLink::parse(&self.magnet_uri)
+    }
+
+    /// The tags of the torrent, without the empty ones
+    pub fn tag_list(&self) -> Vec<String> {
+        self.tags
+            .split(',')
+            .map(str::trim)
+            .filter(|tag| !tag.is_empty())
+            .map(String::from)
+            .collect()
+    }
+
+    /// Share ratio at which the torrent stops seeding, with the global limit
+    /// resolved. `None` if there is no limit.
+    pub fn effective_ratio_limit(&self) -> Option<f64> {
+        match self.ratio_limit {
+            ShareLimit::Global if self.max_ratio >= 0.0 => Some(self.max_ratio as f64),
+            ShareLimit::Value(ratio) => Some(ratio),
+            _ => None,
+        }
+    }
+
+    /// Seeding time after which the torrent stops seeding, with the global
+    /// limit resolved. `None` if there is no limit.
+    pub fn effective_seeding_time_limit(&self) -> Option<Duration> {
+        resolve_seeding_time(self.seeding_time_limit, self.max_seeding_time)
+    }
+
+    /// Inactive seeding time after which the torrent stops seeding, with the
+    /// global limit resolved. `None` if there is no limit.
+    pub fn effective_inactive_seeding_time_limit(&self) -> Option<Duration> {
+        resolve_seeding_time(
+            self.inactive_seeding_time_limit,
+            self.max_inactive_seeding_time,
+        )
+    }
+}
+
+/// Folds the global `max` (minutes) into a per torrent limit.
+fn resolve_seeding_time(limit: SeedingTimeLimit, max: i64) -> Option<Duration> {
+    match limit {
+        SeedingTimeLimit::Global => match SeedingTimeLimit::from_raw(max) {
+            SeedingTimeLimit::Value(limit) => Some(limit),
+            _ => None,
+        },
+        SeedingTimeLimit::Value(limit) => Some(limit),
+        SeedingTimeLimit::Unlimited => None,
+    }
 }
 
 /// Represents a map of torrents, where the key of the `HashMap` is the
@@ -187,10 +293,10 @@ pub struct Torrent {
 /// The `TorrentsMap` struct also implements the `Deref` trait, allowing you
 /// to use it as if it were a `HashMap` directly.
 #[derive(Debug, Serialize, Clone, Default, PartialEq)]
-pub struct TorrentsMap(pub HashMap<String, Torrent>);
+pub struct TorrentsMap(pub HashMap<InfoHash, Torrent>);
 
 impl Deref for TorrentsMap {
-    type Target = HashMap<String, Torrent>;
+    type Target = HashMap<InfoHash, Torrent>;
 
     fn deref(&self) -> &Self::Target {
         &self.0
@@ -225,26 +331,28 @@ impl<'de> Visitor<'de> for TorrentMapVisitor {
         #[derive(Deserialize)]
         struct TmpTorrent {
             added_on: i64,
-            amount_left: i64,
+            amount_left: Bytes,
             auto_tmm: bool,
             availability: f64,
             category: String,
             comment: String,
-            completed: i64,
+            completed: Bytes,
             completion_on: i64,
             content_path: String,
-            dl_limit: i64,
-            dlspeed: i64,
+            dl_limit: SpeedLimit,
+            dlspeed: BytesPerSecond,
             download_path: String,
-            downloaded: i64,
-            downloaded_session: i64,
+            downloaded: Bytes,
+            downloaded_session: Bytes,
             eta: i64,
             f_l_piece_prio: bool,
             force_start: bool,
             has_metadata: bool,
-            inactive_seeding_time_limit: i64,
-            infohash_v1: String,
-            infohash_v2: String,
+            inactive_seeding_time_limit: SeedingTimeLimit,
+            #[serde(deserialize_with = "deserializers::from_empty_string_to_none")]
+            infohash_v1: Option<InfoHash>,
+            #[serde(deserialize_with = "deserializers::from_empty_string_to_none")]
+            infohash_v2: Option<InfoHash>,
             last_activity: i64,
             max_inactive_seeding_time: i64,
             magnet_uri: String,
@@ -261,29 +369,29 @@ impl<'de> Visitor<'de> for TorrentMapVisitor {
             private: Option<bool>,
             progress: f32,
             ratio: f32,
-            ratio_limit: f32,
+            ratio_limit: ShareLimit,
             reannounce: i64,
             root_path: String,
             save_path: String,
             seeding_time: i64,
-            seeding_time_limit: i64,
+            seeding_time_limit: SeedingTimeLimit,
             seen_complete: i64,
             seq_dl: bool,
-            size: i64,
+            size: Bytes,
             state: TorrentState,
             super_seeding: bool,
             tags: String,
             time_active: i64,
-            total_size: i64,
+            total_size: Bytes,
             tracker: String,
             trackers_count: i64,
-            up_limit: i64,
-            uploaded: i64,
-            uploaded_session: i64,
-            upspeed: i64,
+            up_limit: SpeedLimit,
+            uploaded: Bytes,
+            uploaded_session: Bytes,
+            upspeed: BytesPerSecond,
         }
 
-        while let Some(key) = access.next_key::<String>()? {
+        while let Some(key) = access.next_key::<InfoHash>()? {
             let temp_torrent: TmpTorrent = access.next_value()?;
 
             let torrent = Torrent {
@@ -362,23 +470,23 @@ pub struct TorrentProperties {
     /// Torrent creation date (Unix timestamp)
     pub creation_date: i64,
     /// Torrent piece size (bytes)
-    pub piece_size: i64,
+    pub piece_size: Bytes,
     /// Torrent comment
     pub comment: String,
     /// Total data wasted for torrent (bytes)
-    pub total_wasted: i64,
+    pub total_wasted: Bytes,
     /// Total data uploaded for torrent (bytes)
-    pub total_uploaded: i64,
+    pub total_uploaded: Bytes,
     /// Total data uploaded this session (bytes)
-    pub total_uploaded_session: i64,
+    pub total_uploaded_session: Bytes,
     /// Total data downloaded for torrent (bytes)
-    pub total_downloaded: i64,
+    pub total_downloaded: Bytes,
     /// Total data downloaded this session (bytes)
-    pub total_downloaded_session: i64,
-    /// Torrent upload limit (bytes/s)
-    pub up_limit: i64,
-    /// Torrent download limit (bytes/s)
-    pub dl_limit: i64,
+    pub total_downloaded_session: Bytes,
+    /// Torrent upload limit
+    pub up_limit: SpeedLimit,
+    /// Torrent download limit
+    pub dl_limit: SpeedLimit,
     /// Torrent elapsed time (seconds)
     pub time_elapsed: i64,
     /// Torrent elapsed time while complete (seconds)
@@ -396,9 +504,9 @@ pub struct TorrentProperties {
     /// Torrent creator
     pub created_by: String,
     /// Torrent average download speed (bytes/second)
-    pub dl_speed_avg: i64,
+    pub dl_speed_avg: BytesPerSecond,
     /// Torrent download speed (bytes/second)
-    pub dl_speed: i64,
+    pub dl_speed: BytesPerSecond,
     /// Torrent ETA (seconds)
     pub eta: i64,
     /// Last seen complete date (unix timestamp)
@@ -418,11 +526,11 @@ pub struct TorrentProperties {
     /// Number of seeds in the swarm
     pub seeds_total: i64,
     /// Torrent total size (bytes)
-    pub total_size: i64,
+    pub total_size: Bytes,
     /// Torrent average upload speed (bytes/second)
-    pub up_speed_avg: i64,
+    pub up_speed_avg: BytesPerSecond,
     /// Torrent upload speed (bytes/second)
-    pub up_speed: i64,
+    pub up_speed: BytesPerSecond,
     /// True if torrent is from a private tracker (added in 5.0.0)
     ///
     /// The value will be `null` if the torrent metadata is not available yet.
@@ -430,6 +538,48 @@ pub struct TorrentProperties {
     pub private: Option<bool>,
 }
 
+impl TorrentProperties {
+    /// Torrent creation date. `None` if the `.torrent` has no creation date.
+    pub fn created_at<T: FromTimestamp>(&self) -> Option<T> {
+        timestamp::instant(self.creation_date).and_then(T::from_timestamp)
+    }
+
+    /// When this torrent was added
+    pub fn added_at<T: FromTimestamp>(&self) -> Option<T> {
+        timestamp::instant(self.addition_date).and_then(T::from_timestamp)
+    }
+
+    /// Torrent completion date. `None` if it has not completed yet.
+    pub fn completed_at<T: FromTimestamp>(&self) -> Option<T> {
+        timestamp::instant(self.completion_date).and_then(T::from_timestamp)
+    }
+
+    /// Last seen complete date. `None` if never.
+    pub fn last_seen_at<T: FromTimestamp>(&self) -> Option<T> {
+        timestamp::instant(self.last_seen).and_then(T::from_timestamp)
+    }
+
+    /// Torrent ETA. `None` if the torrent is not expected to finish.
+    pub fn eta_duration(&self) -> Option<Duration> {
+        timestamp::eta(self.eta)
+    }
+
+    /// Torrent elapsed time
+    pub fn elapsed_duration(&self) -> Option<Duration> {
+        timestamp::duration(self.time_elapsed)
+    }
+
+    /// Torrent elapsed time while complete
+    pub fn seeding_duration(&self) -> Option<Duration> {
+        timestamp::duration(self.seeding_time)
+    }
+
+    /// Time until the next announce
+    pub fn reannounce_duration(&self) -> Option<Duration> {
+        timestamp::duration(self.reannounce)
+    }
+}
+
 /// Torrent tracker object
 ///
 /// This struct contains detailed information about a tracker.
@@ -476,7 +626,7 @@ pub struct TorrentContent {
     /// File name (including relative path)
     pub name: String,
     /// File size (bytes)
-    pub size: i64,
+    pub size: Bytes,
     /// File progress (percentage/100)
     pub progress: f64,
     /// File priority.
@@ -489,8 +639,23 @@ pub struct TorrentContent {
     pub availability: f64,
 }
 
+impl TorrentContent {
+    /// Offset of the file in the piece data, given the total size of the
+    /// files before it.
+    ///
+    /// The server does not list padding files, so files following one, in
+    /// hybrid torrents or v1 torrents with BEP 47 pad files, start after
+    /// `packed`, on the first byte of their [`piece_range`](Self::piece_range).
+    pub fn offset(&self, packed: u64, piece_size: u64) -> u64 {
+        match self.piece_range.first() {
+            Some(first) if self.size.0 > 0 => packed.max((*first).max(0) as u64 * piece_size),
+            _ => packed,
+        }
+    }
+}
+
 /// File priority enum
-#[derive(Debug, Deserialize_repr, Serialize_repr, Clone, Default, PartialEq)]
+#[derive(Debug, Deserialize_repr, Serialize_repr, Clone, Copy, Default, PartialEq, Eq)]
 #[repr(u8)]
 pub enum FilePriority {
     /// Do not download
@@ -505,7 +670,7 @@ pub enum FilePriority {
 }
 
 /// Pices state
-#[derive(Debug, Deserialize_repr, Serialize_repr, Clone, Default, PartialEq)]
+#[derive(Debug, Deserialize_repr, Serialize_repr, Clone, Copy, Default, PartialEq, Eq)]
 #[repr(u8)]
 pub enum PiecesState {
     #[default]
@@ -513,3 +678,35 @@ pub enum PiecesState {
     Downloading = 1,
     Downloaded = 2,
 }
+
+/// Result of adding one torrent file or link with
+/// [`Api::add_torrent`](crate::Api::add_torrent)
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+pub struct AddTorrentOutcome {
+    /// File name or link the torrent was added from
+    pub source: String,
+    /// Hash qBittorrent identifies the torrent by. `None` when it cannot be
+    /// known before the server downloads the torrent, like for `http` links,
+    /// or when a `.torrent` file cannot be hashed exactly.
+    pub hash: Option<InfoHash>,
+    /// What happened to the torrent
+    pub status: AddTorrentStatus,
+    /// Result of [`AddTorrent::file_selection`](crate::parameters::AddTorrent::file_selection),
+    /// with the error message if it failed. `None` if no selection was
+    /// applied to the torrent.
+    pub file_selection: Option<Result<FileSelectionPlan, String>>,
+}
+
+/// What happened to a torrent sent to the server
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AddTorrentStatus {
+    /// The server accepted the request but the torrent was not confirmed
+    #[default]
+    Submitted,
+    /// The torrent was confirmed to be added
+    Added,
+    /// The torrent was already in the client before the request
+    Duplicate,
+    /// The torrent is not in the client after the request
+    Failed,
+}
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use crate::models::ConnectionStatus;
+use crate::models::{Bytes, BytesPerSecond, ConnectionStatus};
 
 /// Transfer info data object
 ///
@@ -8,15 +8,15 @@ use crate::models::ConnectionStatus;
 #[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 pub struct TransferInfo {
     /// Global download rate (bytes/s)
-    pub dl_info_speed: i64,
+    pub dl_info_speed: BytesPerSecond,
     /// Data downloaded this session (bytes)
-    pub dl_info_data: i64,
+    pub dl_info_data: Bytes,
     /// Global upload rate (bytes/s)
-    pub up_info_speed: i64,
+    pub up_info_speed: BytesPerSecond,
     /// Data uploaded this session (bytes)
-    pub up_info_data: i64,
+    pub up_info_data: Bytes,
     /// Download rate limit (bytes/s)
-    pub dl_rate_limit: i64,
+    pub dl_rate_limit: BytesPerSecond,
     /// Upload rate limit (bytes/s)
     pub dht_nodes: i64,
     /// DHT nodes connected to
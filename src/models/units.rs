@@ -0,0 +1,317 @@
+use std::{
+    fmt::{self, Display},
+    iter::Sum,
+    ops::{Add, AddAssign, Sub, SubAssign},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+
+const IEC_UNITS: [&str; 6] = ["B", "KiB", "MiB", "GiB", "TiB", "PiB"];
+const SI_UNITS: [&str; 6] = ["B", "kB", "MB", "GB", "TB", "PB"];
+
+/// Amount of data in bytes.
+///
+/// Serialized as a plain integer, like the server does.
+///
+/// Displayed with IEC units (`1.50 MiB`), or with SI units when using the
+/// alternate flag (`{:#}` gives `1.57 MB`). The precision defaults to 2.
+///
+/// Can be parsed from strings like `"1024"`, `"5 MiB"` or `"1.5GB"`.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(transparent)]
+pub struct Bytes(pub i64);
+
+/// Transfer rate in bytes per second.
+///
+/// Serialized as a plain integer, like the server does.
+///
+/// Displayed like [`Bytes`] with a `/s` suffix (`5.00 MiB/s`), and parsed
+/// from strings like `"5 MiB/s"` or `"500 kB"`.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(transparent)]
+pub struct BytesPerSecond(pub i64);
+
+impl Bytes {
+    /// Amount in bytes
+    pub const fn new(bytes: i64) -> Self {
+        Self(bytes)
+    }
+    /// Amount in KibiBytes (1024 bytes)
+    pub const fn from_kib(kib: i64) -> Self {
+        Self(kib * 1024)
+    }
+    /// Amount in MebiBytes (1024 * 1024 bytes)
+    pub const fn from_mib(mib: i64) -> Self {
+        Self(mib * 1024 * 1024)
+    }
+    /// Amount in GibiBytes (1024 * 1024 * 1024 bytes)
+    pub const fn from_gib(gib: i64) -> Self {
+        Self(gib * 1024 * 1024 * 1024)
+    }
+    /// The raw amount of bytes
+    pub const fn as_i64(&self) -> i64 {
+        self.0
+    }
+}
+
+impl BytesPerSecond {
+    /// Rate in bytes per second
+    pub const fn new(bytes: i64) -> Self {
+        Self(bytes)
+    }
+    /// Rate in KibiBytes per second
+    pub const fn from_kib(kib: i64) -> Self {
+        Self(kib * 1024)
+    }
+    /// Rate in MebiBytes per second
+    pub const fn from_mib(mib: i64) -> Self {
+        Self(mib * 1024 * 1024)
+    }
+    /// The raw rate in bytes per second
+    pub const fn as_i64(&self) -> i64 {
+        self.0
+    }
+}
+
+impl From<i64> for Bytes {
+    fn from(value: i64) -> Self {
+        Self(value)
+    }
+}
+
+impl From<Bytes> for i64 {
+    fn from(value: Bytes) -> Self {
+        value.0
+    }
+}
+
+impl From<i64> for BytesPerSecond {
+    fn from(value: i64) -> Self {
+        Self(value)
+    }
+}
+
+impl From<BytesPerSecond> for i64 {
+    fn from(value: BytesPerSecond) -> Self {
+        value.0
+    }
+}
+
+impl Display for Bytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        format_size(self.0, f, "")
+    }
+}
+
+impl Display for BytesPerSecond {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        format_size(self.0, f, "/s")
+    }
+}
+
+impl FromStr for Bytes {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_size(s).map(Self)
+    }
+}
+
+impl FromStr for BytesPerSecond {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let trimmed = s.trim();
+        let size = trimmed
+            .strip_suffix("/s")
+            .or_else(|| trimmed.strip_suffix("/S"))
+            .unwrap_or(trimmed);
+
+        parse_size(size).map(Self)
+    }
+}
+
+macro_rules! impl_arithmetic {
+    ($name:ident) => {
+        impl Add for $name {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self::Output {
+                Self(self.0 + rhs.0)
+            }
+        }
+
+        impl AddAssign for $name {
+            fn add_assign(&mut self, rhs: Self) {
+                self.0 += rhs.0;
+            }
+        }
+
+        impl Sub for $name {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self::Output {
+                Self(self.0 - rhs.0)
+            }
+        }
+
+        impl SubAssign for $name {
+            fn sub_assign(&mut self, rhs: Self) {
+                self.0 -= rhs.0;
+            }
+        }
+
+        impl Sum for $name {
+            fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+                Self(iter.map(|v| v.0).sum())
+            }
+        }
+
+        impl<'a> Sum<&'a $name> for $name {
+            fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+                Self(iter.map(|v| v.0).sum())
+            }
+        }
+    };
+}
+
+impl_arithmetic!(Bytes);
+impl_arithmetic!(BytesPerSecond);
+
+/// Writes `bytes` with the biggest unit that keeps the value above 1.
+fn format_size(bytes: i64, f: &mut fmt::Formatter<'_>, suffix: &str) -> fmt::Result {
+    let (base, units) = if f.alternate() {
+        (1000.0, SI_UNITS)
+    } else {
+        (1024.0, IEC_UNITS)
+    };
+
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value.abs() >= base && unit < units.len() - 1 {
+        value /= base;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        write!(f, "{} B{}", bytes, suffix)
+    } else {
+        let precision = f.precision().unwrap_or(2);
+        write!(f, "{:.*} {}{}", precision, value, units[unit], suffix)
+    }
+}
+
+/// Parses a number with an optional IEC or SI unit into bytes.
+fn parse_size(s: &str) -> Result<i64, Error> {
+    let s = s.trim();
+    let split = s
+        .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == '-' || c == '+'))
+        .unwrap_or(s.len());
+    let (number, unit) = s.split_at(split);
+
+    let number = number
+        .parse::<f64>()
+        .map_err(|_| Error::ParseError(format!("Invalid size: {:?}", s)))?;
+
+    let multiplier: f64 = match unit.trim().to_ascii_lowercase().as_str() {
+        "" | "b" => 1.0,
+        "k" | "kb" => 1e3,
+        "m" | "mb" => 1e6,
+        "g" | "gb" => 1e9,
+        "t" | "tb" => 1e12,
+        "p" | "pb" => 1e15,
+        "kib" => 1024.0,
+        "mib" => 1024.0 * 1024.0,
+        "gib" => 1024.0 * 1024.0 * 1024.0,
+        "tib" => 1024.0 * 1024.0 * 1024.0 * 1024.0,
+        "pib" => 1024.0 * 1024.0 * 1024.0 * 1024.0 * 1024.0,
+        _ => {
+            return Err(Error::ParseError(format!(
+                "Unknown size unit {:?} in {:?}",
+                unit.trim(),
+                s
+            )));
+        }
+    };
+
+    let bytes = (number * multiplier).round();
+    if !bytes.is_finite() || bytes.abs() > i64::MAX as f64 {
+        return Err(Error::ParseError(format!("Size out of range: {:?}", s)));
+    }
+
+    Ok(bytes as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_iec() {
+        assert_eq!(Bytes(512).to_string(), "512 B");
+        assert_eq!(Bytes::from_kib(1536).to_string(), "1.50 MiB");
+        assert_eq!(format!("{:.1}", Bytes::from_gib(2)), "2.0 GiB");
+    }
+
+    #[test]
+    fn display_si() {
+        assert_eq!(format!("{:#}", Bytes::from_kib(1536)), "1.57 MB");
+        assert_eq!(format!("{:#}", BytesPerSecond(1500)), "1.50 kB/s");
+    }
+
+    #[test]
+    fn display_speed() {
+        assert_eq!(BytesPerSecond::from_mib(5).to_string(), "5.00 MiB/s");
+    }
+
+    #[test]
+    fn parse_bytes() {
+        assert_eq!("1024".parse::<Bytes>().unwrap(), Bytes(1024));
+        assert_eq!("5 MiB".parse::<Bytes>().unwrap(), Bytes::from_mib(5));
+        assert_eq!("1.5GB".parse::<Bytes>().unwrap(), Bytes(1_500_000_000));
+        assert_eq!("2 kib".parse::<Bytes>().unwrap(), Bytes(2048));
+    }
+
+    #[test]
+    fn parse_speed() {
+        assert_eq!(
+            "5 MiB/s".parse::<BytesPerSecond>().unwrap(),
+            BytesPerSecond::from_mib(5)
+        );
+        assert_eq!(
+            "500 kB".parse::<BytesPerSecond>().unwrap(),
+            BytesPerSecond(500_000)
+        );
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert!("".parse::<Bytes>().is_err());
+        assert!("five MiB".parse::<Bytes>().is_err());
+        assert!("5 XB".parse::<Bytes>().is_err());
+        assert!("5 µB".parse::<BytesPerSecond>().is_err());
+        assert!("5 µ".parse::<BytesPerSecond>().is_err());
+    }
+
+    #[test]
+    fn serde_is_plain_integer() {
+        assert_eq!(serde_json::to_string(&Bytes(42)).unwrap(), "42");
+        assert_eq!(
+            serde_json::from_str::<BytesPerSecond>("-1").unwrap(),
+            BytesPerSecond(-1)
+        );
+    }
+
+    #[test]
+    fn sum_bytes() {
+        let total: Bytes = [Bytes(1), Bytes(2), Bytes(3)].iter().sum();
+        assert_eq!(total, Bytes(6));
+    }
+}
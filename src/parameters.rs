@@ -4,10 +4,25 @@
 //!
 
 use derive_builder::Builder;
+use futures_util::{Stream, StreamExt};
 use serde::{Deserialize, Serialize};
-use std::fmt::{Debug, Display};
+use std::{
+    fmt::{Debug, Display},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use tokio::io::{AsyncRead, AsyncReadExt};
 
-use crate::models::ContentLayout;
+use crate::{
+    error::Error,
+    metainfo::{MagnetLink, Metainfo, bencode},
+    models::{
+        Bytes, Category, CategoryDownloadPath, ContentLayout, FilePriority, FileRename,
+        FileSelectionPlan, InfoHash, LogCursor, LogTypes, RenamePlan, SeedingTimeLimit, ShareLimit,
+        ShareLimitAction, SpeedLimit, StopCondition, TorrentContent,
+    },
+    utiles::{glob, template::Template},
+};
 
 /// Torrent List/info parameter object
 #[derive(Debug, Default, Builder, Clone, Deserialize, Serialize, PartialEq)]
@@ -33,9 +48,69 @@ pub struct TorrentListParams {
     /// Set offset (if less than 0, offset from end)
     #[builder(setter(into, strip_option), default)]
     pub offset: Option<i64>,
-    /// Filter by hashes. Can contain multiple hashes separated by `|`
+    /// Filter by hashes
     #[builder(setter(into, strip_option), default)]
-    pub hashes: Option<Vec<String>>,
+    pub hashes: Option<Vec<InfoHash>>,
+}
+
+/// Selects the torrents a bulk operation acts on.
+///
+/// Every method acting on several torrents accepts anything that converts
+/// into a selector, so a single [`InfoHash`] or a `Vec<InfoHash>` can be
+/// passed directly.
+///
+/// # Example
+///
+/// Select every torrent in the `linux` category
+/// ```
+/// use qbit::parameters::{TorrentListParamsBuilder, TorrentSelector};
+///
+/// let params = TorrentListParamsBuilder::default()
+///     .category("linux")
+///     .build()
+///     .unwrap();
+/// let selector = TorrentSelector::Filter(params);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum TorrentSelector {
+    /// Every torrent
+    #[default]
+    All,
+    /// The torrents with the given hashes
+    Hashes(Vec<InfoHash>),
+    /// The torrents listed by [`crate::Api::torrents`] with these
+    /// parameters. Resolved client-side before the operation is sent.
+    Filter(TorrentListParams),
+}
+
+impl From<InfoHash> for TorrentSelector {
+    fn from(value: InfoHash) -> Self {
+        Self::Hashes(vec![value])
+    }
+}
+
+impl From<&InfoHash> for TorrentSelector {
+    fn from(value: &InfoHash) -> Self {
+        Self::Hashes(vec![value.clone()])
+    }
+}
+
+impl From<Vec<InfoHash>> for TorrentSelector {
+    fn from(value: Vec<InfoHash>) -> Self {
+        Self::Hashes(value)
+    }
+}
+
+impl From<&[InfoHash]> for TorrentSelector {
+    fn from(value: &[InfoHash]) -> Self {
+        Self::Hashes(value.to_vec())
+    }
+}
+
+impl From<TorrentListParams> for TorrentSelector {
+    fn from(value: TorrentListParams) -> Self {
+        Self::Filter(value)
+    }
 }
 
 /// Possible Torrent states that can be filtered.
@@ -80,7 +155,7 @@ impl Display for FilterTorrentState {
 }
 
 /// Possible states that any given torrent can be in at a time.
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[derive(Clone, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum TorrentState {
     /// Some error occurred, applies to paused torrents
     #[serde(rename = "error")]
@@ -93,6 +168,7 @@ pub enum TorrentState {
     Moving,
     /// Unknown status
     #[serde(rename = "unknown")]
+    #[default]
     Unknown,
     /// Torrent is allocating disk space for download
     #[serde(rename = "allocating")]
@@ -149,12 +225,6 @@ pub enum TorrentState {
     ForcedDownloading,
 }
 
-impl Default for TorrentState {
-    fn default() -> Self {
-        Self::Unknown
-    }
-}
-
 impl From<&str> for TorrentState {
     fn from(value: &str) -> Self {
         match value {
@@ -453,6 +523,9 @@ impl Display for TorrentSort {
 }
 
 /// Add torrent parameter object
+///
+/// Optional fields left to `None` are not sent, so the server uses its own
+/// defaults for them.
 #[derive(Debug, Default, Builder, Clone, Deserialize, Serialize, PartialEq)]
 pub struct AddTorrent {
     /// A list of torrent files or magnet links to be added.
@@ -460,9 +533,18 @@ pub struct AddTorrent {
     /// This field is required and must contain at least one item.
     #[builder(setter(into))]
     pub torrents: AddTorrentType,
+    /// Cookie sent to download the links
+    #[builder(setter(into, strip_option), default)]
+    pub cookie: Option<String>,
     /// Download folder
     #[builder(setter(into, strip_option), default)]
     pub savepath: Option<String>,
+    /// Folder the torrent is downloaded to while incomplete
+    #[builder(setter(into, strip_option), default)]
+    pub download_path: Option<String>,
+    /// Whether `download_path` should be used. Uses the global setting if `None`.
+    #[builder(setter(into, strip_option), default)]
+    pub use_download_path: Option<bool>,
     /// Category for the torrent
     #[builder(setter(into, strip_option), default)]
     pub category: Option<String>,
@@ -472,36 +554,77 @@ pub struct AddTorrent {
     /// Skip hash checking. Possible values are `true`, `false` (default)
     #[builder(default)]
     pub skip_checking: bool,
-    /// Add torrents in the paused state. Possible values are `true`, `false` (default)
-    #[builder(default)]
-    pub paused: bool,
+    /// Add torrents in the stopped state. Uses the global setting if `None`.
+    ///
+    /// Sent as `stopped` to qBittorrent 5 and newer, and as `paused` to older
+    /// versions.
+    #[builder(setter(into, strip_option), default)]
+    pub stopped: Option<bool>,
+    /// When to stop the torrent once added. Uses the global setting if `None`.
+    #[builder(setter(into, strip_option), default)]
+    pub stop_condition: Option<StopCondition>,
+    /// Add the torrents to the top of the queue. Uses the global setting if `None`.
+    #[builder(setter(into, strip_option), default)]
+    pub add_to_top_of_queue: Option<bool>,
+    /// Force start the torrents, ignoring the queue
+    #[builder(setter(into, strip_option), default)]
+    pub forced: Option<bool>,
     /// The torrent subfolder layout.
     #[builder(setter(into), default)]
     pub content_layout: ContentLayout,
     /// Rename torrent
     #[builder(setter(into, strip_option), default)]
     pub rename: Option<String>,
-    /// Set torrent upload speed limit. Unit in bytes/second
+    /// Set torrent upload speed limit
     #[builder(setter(into, strip_option), default)]
-    pub up_limit: Option<i64>,
-    /// Set torrent download speed limit. Unit in bytes/second
+    pub up_limit: Option<SpeedLimit>,
+    /// Set torrent download speed limit
     #[builder(setter(into, strip_option), default)]
-    pub dl_limit: Option<i64>,
+    pub dl_limit: Option<SpeedLimit>,
     /// Set torrent share ratio limit
     #[builder(setter(into, strip_option), default)]
-    pub ratio_limit: Option<f32>,
-    /// Set torrent seeding time limit. Unit in minutes
+    pub ratio_limit: Option<ShareLimit>,
+    /// Set torrent seeding time limit. Only whole minutes are used.
     #[builder(setter(into, strip_option), default)]
-    pub seeding_time_limit: Option<i64>,
-    /// Whether Automatic Torrent Management should be used
-    #[builder(default)]
-    pub auto_tmm: bool,
+    pub seeding_time_limit: Option<SeedingTimeLimit>,
+    /// Set torrent inactive seeding time limit. Only whole minutes are used.
+    #[builder(setter(into, strip_option), default)]
+    pub inactive_seeding_time_limit: Option<SeedingTimeLimit>,
+    /// What to do once a share limit is reached
+    #[builder(setter(into, strip_option), default)]
+    pub share_limit_action: Option<ShareLimitAction>,
+    /// Whether Automatic Torrent Management should be used. Uses the global
+    /// setting if `None`.
+    #[builder(setter(into, strip_option), default)]
+    pub auto_tmm: Option<bool>,
     /// Enable sequential download. Possible values are `true`, `false` (default)
     #[builder(default)]
     pub sequential_download: bool,
     /// Prioritize download first last piece. Possible values are `true`, `false` (default)
     #[builder(default)]
     pub first_last_piece_prio: bool,
+    /// SSL certificate (PEM) for torrents using SSL
+    #[builder(setter(into, strip_option), default)]
+    pub ssl_certificate: Option<String>,
+    /// SSL private key (PEM) for torrents using SSL
+    #[builder(setter(into, strip_option), default)]
+    pub ssl_private_key: Option<String>,
+    /// SSL Diffie-Hellman parameters (PEM) for torrents using SSL
+    #[builder(setter(into, strip_option), default)]
+    pub ssl_dh_params: Option<String>,
+    /// Check the torrent list before and after adding to tell added,
+    /// duplicate and failed torrents apart. Not sent to the server.
+    #[builder(default)]
+    pub confirm: bool,
+    /// How long to wait for added torrents to show up in the torrent list
+    /// when `confirm` is set. The server adds torrents in the background.
+    /// Defaults to 10 seconds.
+    #[builder(setter(strip_option), default)]
+    pub confirm_timeout: Option<Duration>,
+    /// Files to download, applied with [`Api::select_files`](crate::Api::select_files)
+    /// to every torrent once added. Not sent to the server.
+    #[builder(setter(into, strip_option), default)]
+    pub file_selection: Option<FileSelection>,
 }
 
 impl AddTorrent {
@@ -511,19 +634,113 @@ impl AddTorrent {
             ..Default::default()
         }
     }
+
+    /// Checks for parameters the server would reject or silently ignore.
+    pub fn validate(&self) -> Result<(), Error> {
+        let invalid = |message: &str| Err(Error::InvalidRequest(message.to_string()));
+
+        if self.torrents.is_empty() {
+            return invalid("Expected urls or torrents to not be empty!");
+        }
+        if self.auto_tmm == Some(true)
+            && (self.savepath.is_some()
+                || self.download_path.is_some()
+                || self.use_download_path.is_some())
+        {
+            return invalid(
+                "Save and download paths are set by the category when auto_tmm is enabled",
+            );
+        }
+        if self.use_download_path == Some(false) && self.download_path.is_some() {
+            return invalid("download_path is set but use_download_path is disabled");
+        }
+        if self.cookie.is_some() && self.torrents.links().is_empty() {
+            return invalid("cookie is only used to download links");
+        }
+        if self.stopped == Some(true) && self.forced == Some(true) {
+            return invalid("A torrent cannot be added both stopped and forced");
+        }
+        if self.stopped == Some(true)
+            && self
+                .stop_condition
+                .as_ref()
+                .is_some_and(|condition| *condition != StopCondition::None)
+        {
+            return invalid("stop_condition has no effect on a torrent added stopped");
+        }
+        if let Some(selection) = &self.file_selection {
+            selection.validate()?;
+        }
+
+        if let Some(error) = self
+            .torrents
+            .links()
+            .iter()
+            .filter(|link| link.trim_start().starts_with("magnet:"))
+            .find_map(|link| MagnetLink::parse(link).err())
+        {
+            return Err(Error::InvalidRequest(error.to_string()));
+        }
+
+        let invalid_files: Vec<String> = self
+            .torrents
+            .files()
+            .iter()
+            .filter_map(|file| {
+                file.check()
+                    .err()
+                    .map(|error| format!("{:?}: {}", file.filename, error))
+            })
+            .collect();
+        if !invalid_files.is_empty() {
+            return Err(Error::InvalidRequest(format!(
+                "Invalid torrent files: {}",
+                invalid_files.join(", ")
+            )));
+        }
+
+        Ok(())
+    }
 }
 
+/// Torrents to add: links, torrent files, or both in one request
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 pub enum AddTorrentType {
     Links(Vec<String>),
     Files(Vec<TorrentFile>),
+    Mixed {
+        files: Vec<TorrentFile>,
+        links: Vec<String>,
+    },
 }
 
 impl AddTorrentType {
     pub fn is_empty(&self) -> bool {
+        self.links().is_empty() && self.files().is_empty()
+    }
+
+    /// The magnet or http links to add
+    pub fn links(&self) -> &[String] {
+        match self {
+            AddTorrentType::Links(links) | AddTorrentType::Mixed { links, .. } => links,
+            AddTorrentType::Files(_) => &[],
+        }
+    }
+
+    /// The torrent files to add
+    pub fn files(&self) -> &[TorrentFile] {
+        match self {
+            AddTorrentType::Files(files) | AddTorrentType::Mixed { files, .. } => files,
+            AddTorrentType::Links(_) => &[],
+        }
+    }
+
+    /// Splits into the files and the links to add.
+    pub fn into_parts(self) -> (Vec<TorrentFile>, Vec<String>) {
         match self {
-            AddTorrentType::Links(items) => items.is_empty(),
-            AddTorrentType::Files(items) => items.is_empty(),
+            AddTorrentType::Links(links) => (Vec::new(), links),
+            AddTorrentType::Files(files) => (files, Vec::new()),
+            AddTorrentType::Mixed { files, links } => (files, links),
         }
     }
 }
@@ -552,14 +769,786 @@ impl From<TorrentFile> for AddTorrentType {
     }
 }
 
+impl From<(Vec<TorrentFile>, Vec<String>)> for AddTorrentType {
+    fn from((files, links): (Vec<TorrentFile>, Vec<String>)) -> Self {
+        Self::Mixed { files, links }
+    }
+}
+
+impl From<MagnetLink> for AddTorrentType {
+    fn from(value: MagnetLink) -> Self {
+        Self::Links(vec![value.to_string()])
+    }
+}
+
+impl From<Vec<MagnetLink>> for AddTorrentType {
+    fn from(value: Vec<MagnetLink>) -> Self {
+        Self::Links(value.iter().map(MagnetLink::to_string).collect())
+    }
+}
+
 impl Default for AddTorrentType {
     fn default() -> Self {
         AddTorrentType::Links(vec![])
     }
 }
 
+/// A `.torrent` file to upload
 #[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 pub struct TorrentFile {
     pub filename: String,
     pub data: Vec<u8>,
 }
+
+impl TorrentFile {
+    /// Torrent file with the given name and content
+    pub fn new(filename: impl Into<String>, data: impl Into<Vec<u8>>) -> Self {
+        Self {
+            filename: filename.into(),
+            data: data.into(),
+        }
+    }
+
+    /// Reads the torrent file at `path`, named after the last component of
+    /// the path.
+    pub async fn from_path(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let filename = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .ok_or_else(|| Error::InvalidRequest(format!("Path {:?} has no file name", path)))?;
+
+        Ok(Self::new(filename, tokio::fs::read(path).await?))
+    }
+
+    /// Reads a torrent file from `reader` until its end.
+    pub async fn from_reader(
+        filename: impl Into<String>,
+        mut reader: impl AsyncRead + Unpin,
+    ) -> Result<Self, Error> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data).await?;
+
+        Ok(Self::new(filename, data))
+    }
+
+    /// Collects a torrent file from a stream of chunks, like the body of an
+    /// HTTP response.
+    pub async fn from_stream<S, B, E>(filename: impl Into<String>, stream: S) -> Result<Self, Error>
+    where
+        S: Stream<Item = Result<B, E>>,
+        B: AsRef<[u8]>,
+        Error: From<E>,
+    {
+        let mut data = Vec::new();
+        let mut stream = std::pin::pin!(stream);
+        while let Some(chunk) = stream.next().await {
+            data.extend_from_slice(chunk?.as_ref());
+        }
+
+        Ok(Self::new(filename, data))
+    }
+
+    /// Name the file is uploaded with, ending in `.torrent`
+    pub fn upload_name(&self) -> String {
+        if self.filename.ends_with(".torrent") {
+            self.filename.clone()
+        } else {
+            format!("{}.torrent", self.filename)
+        }
+    }
+
+    /// Checks that the file is bencoded and has an `info` dictionary.
+    ///
+    /// Only the structure is checked, and non canonical encodings are
+    /// accepted, as the server does.
+    pub fn check(&self) -> Result<(), Error> {
+        let span = bencode::dict_value_span(&self.data, b"info")?
+            .ok_or_else(|| Error::ParseError(String::from("Missing info dictionary")))?;
+        if self.data[span.start] != b'd' {
+            return Err(Error::ParseError(String::from("info is not a dictionary")));
+        }
+
+        Ok(())
+    }
+
+    /// Infohash qBittorrent will identify the torrent by, computed from the
+    /// `info` dictionary as it is in the file. See [`Metainfo::info_hash_of`].
+    ///
+    /// Fails if the file is not a bencoded torrent with an `info` dictionary.
+    pub fn info_hash(&self) -> Result<InfoHash, Error> {
+        Metainfo::info_hash_of(&self.data)
+    }
+
+    /// Decodes the torrent file.
+    pub fn metainfo(&self) -> Result<Metainfo, Error> {
+        Metainfo::from_bytes(&self.data)
+    }
+}
+
+/// Log filter parameter object
+///
+/// Every log level is sent to the server explicitly, as the server treats a
+/// missing level as requested. The time range and the message pattern are
+/// applied on the client.
+#[derive(Debug, Default, Builder, Clone, Deserialize, Serialize, PartialEq)]
+pub struct LogFilter {
+    /// Exclude messages with "message id" <= `last_known_id` (default: `-1`)
+    #[builder(setter(into, strip_option), default)]
+    pub last_known_id: Option<i64>,
+    /// Log levels to return. Defaults to all.
+    #[builder(setter(into), default)]
+    pub log_types: LogTypes,
+    /// Only keep messages logged at or after this time (seconds since epoch)
+    #[builder(setter(into, strip_option), default)]
+    pub since: Option<i64>,
+    /// Only keep messages logged at or before this time (seconds since epoch)
+    #[builder(setter(into, strip_option), default)]
+    pub until: Option<i64>,
+    /// Only keep messages whose text matches this regular expression
+    #[builder(setter(into, strip_option), default)]
+    pub message: Option<String>,
+}
+
+/// Log stream parameter object
+///
+/// Used by [`Api::log_stream`](crate::Api::log_stream) and
+/// [`Api::peer_log_stream`](crate::Api::peer_log_stream).
+#[derive(Debug, Builder, Clone, Deserialize, Serialize, PartialEq)]
+pub struct LogStreamParams {
+    /// Time to wait between two polls of the server. Defaults to 1 second.
+    #[builder(default = "Duration::from_secs(1)")]
+    pub interval: Duration,
+    /// Position to start tailing from. Defaults to the start of the log.
+    #[builder(setter(strip_option), default)]
+    pub cursor: Option<LogCursor>,
+    /// File the cursor is persisted to.
+    ///
+    /// If the file exists when the stream starts its content takes precedence
+    /// over `cursor`. The file is rewritten every time a batch of messages has
+    /// been consumed.
+    #[builder(setter(into, strip_option), default)]
+    pub cursor_file: Option<PathBuf>,
+}
+
+impl Default for LogStreamParams {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(1),
+            cursor: None,
+            cursor_file: None,
+        }
+    }
+}
+
+/// Bulk request parameter object
+///
+/// Controls how [`TorrentSelector::Hashes`] and [`TorrentSelector::Filter`]
+/// selections are split into several requests, so the `hashes` field stays
+/// small enough for proxies in front of the server. Set with
+/// [`Api::set_bulk_options`](crate::Api::set_bulk_options).
+#[derive(Debug, Builder, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+pub struct BulkOptions {
+    /// Maximum number of hashes sent in one request. Defaults to 250.
+    #[builder(default = "250")]
+    pub chunk_size: usize,
+    /// Maximum number of requests in flight at once. Defaults to 4.
+    #[builder(default = "4")]
+    pub concurrency: usize,
+}
+
+impl Default for BulkOptions {
+    fn default() -> Self {
+        Self {
+            chunk_size: 250,
+            concurrency: 4,
+        }
+    }
+}
+
+/// Move parameter object
+///
+/// Used by [`Api::move_and_wait`](crate::Api::move_and_wait).
+#[derive(Debug, Builder, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+pub struct MoveParams {
+    /// Time to wait between two checks of the torrent state. Defaults to 1 second.
+    #[builder(default = "Duration::from_secs(1)")]
+    pub interval: Duration,
+    /// Give up waiting after this time. Waits forever if `None`.
+    #[builder(setter(strip_option), default)]
+    pub timeout: Option<Duration>,
+}
+
+impl Default for MoveParams {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(1),
+            timeout: None,
+        }
+    }
+}
+
+/// Category parameter object
+///
+/// Used by [`Api::create_category`](crate::Api::create_category) and
+/// [`Api::edit_category`](crate::Api::edit_category).
+#[derive(Debug, Default, Builder, Clone, Deserialize, Serialize, PartialEq)]
+pub struct CategoryOptions {
+    /// Path to save torrents of the category to. Empty uses the default save path.
+    #[builder(setter(into), default)]
+    pub save_path: String,
+    /// Path to download incomplete torrents of the category to.
+    #[builder(setter(into), default)]
+    pub download_path: CategoryDownloadPath,
+}
+
+impl From<&Category> for CategoryOptions {
+    fn from(value: &Category) -> Self {
+        Self {
+            save_path: value.save_path.clone(),
+            download_path: value.download_path.clone(),
+        }
+    }
+}
+
+/// Local verification parameter object
+///
+/// Used by [`Api::verify_local_data`](crate::Api::verify_local_data).
+#[derive(Debug, Builder, Clone, Deserialize, Serialize, PartialEq, Eq)]
+pub struct VerifyParams {
+    /// Replaces the prefix of the save path reported by the server with a
+    /// local path, for data reached through another mount point. The first
+    /// matching prefix is used; prefixes only match whole path components.
+    #[builder(setter(into), default)]
+    pub path_map: Vec<(String, PathBuf)>,
+    /// Number of threads hashing pieces. Defaults to 4.
+    #[builder(default = "4")]
+    pub concurrency: usize,
+}
+
+impl Default for VerifyParams {
+    fn default() -> Self {
+        Self {
+            path_map: Vec::new(),
+            concurrency: 4,
+        }
+    }
+}
+
+impl VerifyParams {
+    /// Local path of a path reported by the server, according to
+    /// [`path_map`](Self::path_map).
+    pub fn map_path(&self, path: &str) -> PathBuf {
+        for (prefix, local) in &self.path_map {
+            let prefix = prefix.trim_end_matches(['/', '\\']);
+            if let Some(rest) = path.strip_prefix(prefix) {
+                if rest.is_empty() {
+                    return local.clone();
+                }
+                if rest.starts_with(['/', '\\']) {
+                    return rest
+                        .split(['/', '\\'])
+                        .filter(|part| !part.is_empty())
+                        .fold(local.clone(), |path, part| path.join(part));
+                }
+            }
+        }
+
+        PathBuf::from(path)
+    }
+}
+
+/// File selection parameter object
+///
+/// Picks the files of a torrent to download by path and size. Used by
+/// [`Api::select_files`](crate::Api::select_files) and
+/// [`AddTorrent::file_selection`].
+///
+/// A file is selected when it matches one of the include patterns, or any
+/// pattern if there are none, matches none of the exclude patterns and its
+/// size is within the bounds. Patterns are matched against the `/`
+/// separated path of the file in the torrent.
+#[derive(Debug, Builder, Clone, Deserialize, Serialize, PartialEq)]
+pub struct FileSelection {
+    /// Glob patterns of the files to download, see
+    /// [`FileTree::glob`](crate::models::FileTree::glob)
+    #[builder(setter(into), default)]
+    pub include: Vec<String>,
+    /// Glob patterns of the files to skip
+    #[builder(setter(into), default)]
+    pub exclude: Vec<String>,
+    /// Regular expressions of the files to download
+    #[builder(setter(into), default)]
+    pub include_regex: Vec<String>,
+    /// Regular expressions of the files to skip
+    #[builder(setter(into), default)]
+    pub exclude_regex: Vec<String>,
+    /// Match glob patterns without case. Regular expressions can use `(?i)`.
+    #[builder(default)]
+    pub ignore_case: bool,
+    /// Skip files smaller than this
+    #[builder(setter(into, strip_option), default)]
+    pub min_size: Option<Bytes>,
+    /// Skip files larger than this
+    #[builder(setter(into, strip_option), default)]
+    pub max_size: Option<Bytes>,
+    /// Priority of the selected files. Defaults to [`FilePriority::High`].
+    #[builder(default = "FilePriority::High")]
+    pub priority: FilePriority,
+    /// Time to wait between two checks for the metadata of a magnet link.
+    /// Defaults to 1 second.
+    #[builder(default = "Duration::from_secs(1)")]
+    pub interval: Duration,
+    /// Give up waiting for metadata after this time. Waits forever if `None`.
+    #[builder(setter(strip_option), default)]
+    pub timeout: Option<Duration>,
+}
+
+impl Default for FileSelection {
+    fn default() -> Self {
+        Self {
+            include: Vec::new(),
+            exclude: Vec::new(),
+            include_regex: Vec::new(),
+            exclude_regex: Vec::new(),
+            ignore_case: false,
+            min_size: None,
+            max_size: None,
+            priority: FilePriority::High,
+            interval: Duration::from_secs(1),
+            timeout: None,
+        }
+    }
+}
+
+impl FileSelection {
+    /// Checks the patterns and the priority.
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.priority == FilePriority::DoNotDownload {
+            return Err(Error::InvalidRequest(String::from(
+                "Selected files cannot have the DoNotDownload priority",
+            )));
+        }
+
+        self.compile().map(|_| ())
+    }
+
+    /// Splits `files` into selected and skipped files, and lists the priority
+    /// changes needed to apply the selection.
+    pub fn plan(&self, files: &[TorrentContent]) -> Result<FileSelectionPlan, Error> {
+        self.validate()?;
+        let (include, exclude) = self.compile()?;
+        let mut plan = FileSelectionPlan::default();
+        let (mut select, mut skip) = (Vec::new(), Vec::new());
+
+        for file in files {
+            let size = file.size.0;
+            let selected = (include.is_empty() || include.iter().any(|r| r.is_match(&file.name)))
+                && !exclude.iter().any(|r| r.is_match(&file.name))
+                && self.min_size.is_none_or(|min| size >= min.0)
+                && self.max_size.is_none_or(|max| size <= max.0);
+
+            let index = file.index as u64;
+            if selected {
+                plan.selected.push(index);
+                if file.priority != self.priority {
+                    select.push(index);
+                }
+            } else {
+                plan.skipped.push(index);
+                if file.priority != FilePriority::DoNotDownload {
+                    skip.push(index);
+                }
+            }
+        }
+
+        if !select.is_empty() {
+            plan.changes.push((self.priority, select));
+        }
+        if !skip.is_empty() {
+            plan.changes.push((FilePriority::DoNotDownload, skip));
+        }
+
+        Ok(plan)
+    }
+
+    /// The include and exclude patterns, as regular expressions
+    fn compile(&self) -> Result<(Vec<regex::Regex>, Vec<regex::Regex>), Error> {
+        let compile = |globs: &[String], regexes: &[String]| {
+            globs
+                .iter()
+                .map(|pattern| glob::compile(pattern, self.ignore_case))
+                .chain(regexes.iter().map(|pattern| {
+                    regex::Regex::new(pattern).map_err(|e| {
+                        Error::InvalidRequest(format!("Invalid regex {:?}: {}", pattern, e))
+                    })
+                }))
+                .collect::<Result<Vec<_>, _>>()
+        };
+
+        Ok((
+            compile(&self.include, &self.include_regex)?,
+            compile(&self.exclude, &self.exclude_regex)?,
+        ))
+    }
+}
+
+/// Batch rename parameter object
+///
+/// Renames every file whose name matches `pattern` to `template`. Used by
+/// [`Api::batch_rename`](crate::Api::batch_rename).
+///
+/// The template is filled with the capture groups of the pattern: `{show}`
+/// for a named group, `{1}` for a numbered one. `{name}` and `{ext}` are the
+/// file name without and with only its extension, unless the pattern has
+/// groups with those names, and `{index}` is the file index. `{season:02}`
+/// pads the value with zeros to two characters, dropping leading zeros of
+/// numbers first.
+///
+/// # Example
+///
+/// ```
+/// use qbit::models::TorrentContent;
+/// use qbit::parameters::BatchRenameBuilder;
+///
+/// let rename = BatchRenameBuilder::default()
+///     .pattern(r"(?i)^(?<show>.+)\.s(?<season>\d+)e(?<episode>\d+).*$")
+///     .template("{show} - S{season:02}E{episode:02}.{ext}")
+///     .build()
+///     .unwrap();
+/// let files = vec![TorrentContent {
+///     name: String::from("Pack/Show.s1e2.720p.mkv"),
+///     ..Default::default()
+/// }];
+///
+/// let plan = rename.plan(&files).unwrap();
+/// assert_eq!(plan.renames[0].new_path, "Pack/Show - S01E02.mkv");
+/// ```
+#[derive(Debug, Builder, Clone, Deserialize, Serialize, PartialEq, Eq)]
+pub struct BatchRename {
+    /// Regular expression the file name must match
+    #[builder(setter(into))]
+    pub pattern: String,
+    /// New name of the matching files
+    #[builder(setter(into))]
+    pub template: String,
+    /// Match and rename the whole `/` separated path instead of the file
+    /// name, to move files between folders.
+    #[builder(default)]
+    pub match_path: bool,
+    /// Only plan the renames, without sending them.
+    #[builder(default)]
+    pub dry_run: bool,
+}
+
+impl BatchRename {
+    /// Checks the pattern, the template and their placeholders.
+    pub fn validate(&self) -> Result<(), Error> {
+        self.compile().map(|_| ())
+    }
+
+    /// Plans the renames of `files`, with the collisions they would cause.
+    pub fn plan(&self, files: &[TorrentContent]) -> Result<RenamePlan, Error> {
+        let (pattern, template) = self.compile()?;
+        let mut renames = Vec::new();
+
+        for file in files {
+            let (folder, file_name) = match file.name.rsplit_once('/') {
+                Some((folder, file_name)) => (Some(folder), file_name),
+                None => (None, file.name.as_str()),
+            };
+            let subject = if self.match_path {
+                file.name.as_str()
+            } else {
+                file_name
+            };
+            let Some(captures) = pattern.captures(subject) else {
+                continue;
+            };
+
+            let (stem, ext) = match file_name.rsplit_once('.') {
+                Some((stem, ext)) if !stem.is_empty() => (stem, ext),
+                _ => (file_name, ""),
+            };
+            let index = file.index.to_string();
+            let renamed = template.render(|name| match captures.name(name) {
+                Some(value) => Some(value.as_str()),
+                None => match name {
+                    "name" => Some(stem),
+                    "ext" => Some(ext),
+                    "index" => Some(index.as_str()),
+                    _ => captures.get(name.parse().ok()?).map(|value| value.as_str()),
+                },
+            });
+
+            let new_path = match folder {
+                Some(folder) if !self.match_path => format!("{}/{}", folder, renamed),
+                _ => renamed,
+            };
+            if new_path
+                .split('/')
+                .any(|part| part.trim().is_empty() || part == "." || part == "..")
+            {
+                return Err(Error::InvalidRequest(format!(
+                    "Invalid new path {:?} for {:?}",
+                    new_path, file.name
+                )));
+            }
+
+            renames.push(FileRename {
+                index: file.index,
+                old_path: file.name.clone(),
+                new_path,
+            });
+        }
+
+        let paths: Vec<String> = files.iter().map(|file| file.name.clone()).collect();
+        Ok(RenamePlan::new(renames, &paths))
+    }
+
+    fn compile(&self) -> Result<(regex::Regex, Template), Error> {
+        let pattern = regex::Regex::new(&self.pattern).map_err(|e| {
+            Error::InvalidRequest(format!("Invalid regex {:?}: {}", self.pattern, e))
+        })?;
+        let template = Template::parse(&self.template)?;
+
+        let names: Vec<&str> = pattern.capture_names().flatten().collect();
+        if let Some(unknown) = template.placeholders().find(|placeholder| {
+            !names.contains(placeholder)
+                && !["name", "ext", "index"].contains(placeholder)
+                && placeholder
+                    .parse::<usize>()
+                    .map_or(true, |group| group >= pattern.captures_len())
+        }) {
+            return Err(Error::InvalidRequest(format!(
+                "Unknown placeholder {{{}}} in template {:?}",
+                unknown, self.template
+            )));
+        }
+
+        Ok((pattern, template))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAGNET: &str = "magnet:?xt=urn:btih:c9e15763f722f23e98a29decdfae341b98d53056";
+
+    fn add_torrent() -> AddTorrentBuilder {
+        let mut builder = AddTorrentBuilder::default();
+        builder.torrents(vec![String::from(MAGNET)]);
+        builder
+    }
+
+    #[test]
+    fn add_torrent_valid() {
+        let params = add_torrent()
+            .auto_tmm(true)
+            .category("linux")
+            .stopped(true)
+            .build()
+            .unwrap();
+
+        assert!(params.validate().is_ok());
+    }
+
+    #[test]
+    fn add_torrent_invalid_magnet() {
+        let params = add_torrent()
+            .torrents(vec![
+                String::from(MAGNET),
+                String::from("magnet:?xt=urn:btih:abc"),
+            ])
+            .build()
+            .unwrap();
+
+        assert!(matches!(params.validate(), Err(Error::InvalidRequest(_))));
+    }
+
+    #[test]
+    fn add_torrent_auto_tmm_with_path() {
+        let params = add_torrent()
+            .auto_tmm(true)
+            .savepath("/downloads")
+            .build()
+            .unwrap();
+
+        assert!(matches!(params.validate(), Err(Error::InvalidRequest(_))));
+    }
+
+    #[test]
+    fn add_torrent_conflicting_state() {
+        let stopped_forced = add_torrent().stopped(true).forced(true).build().unwrap();
+        let stopped_condition = add_torrent()
+            .stopped(true)
+            .stop_condition(StopCondition::FilesChecked)
+            .build()
+            .unwrap();
+        let empty = AddTorrent {
+            torrents: AddTorrentType::Links(Vec::new()),
+            ..Default::default()
+        };
+
+        assert!(stopped_forced.validate().is_err());
+        assert!(stopped_condition.validate().is_err());
+        assert!(empty.validate().is_err());
+    }
+
+    const TORRENT: &[u8] =
+        b"d4:infod6:lengthi1e4:name1:a12:piece lengthi16384e6:pieces20:aaaaaaaaaaaaaaaaaaaaee";
+
+    #[test]
+    fn torrent_file_upload_name() {
+        assert_eq!(
+            TorrentFile::new("debian", TORRENT).upload_name(),
+            "debian.torrent"
+        );
+        assert_eq!(
+            TorrentFile::new("a.torrent", TORRENT).upload_name(),
+            "a.torrent"
+        );
+    }
+
+    #[tokio::test]
+    async fn torrent_file_from_reader_and_stream() {
+        let from_reader = TorrentFile::from_reader("a", TORRENT).await.unwrap();
+        let chunks = futures_util::stream::iter(
+            TORRENT
+                .chunks(7)
+                .map(Ok::<_, std::io::Error>)
+                .collect::<Vec<_>>(),
+        );
+        let from_stream = TorrentFile::from_stream("a", chunks).await.unwrap();
+
+        assert_eq!(from_reader, TorrentFile::new("a", TORRENT));
+        assert_eq!(from_stream, from_reader);
+    }
+
+    #[test]
+    fn add_torrent_invalid_files() {
+        let params = add_torrent()
+            .torrents((
+                vec![
+                    TorrentFile::new("good", TORRENT),
+                    TorrentFile::new("old", "d4:infod1:bi1e1:ai01eee"),
+                    TorrentFile::new("bad", "not bencode"),
+                    TorrentFile::new("no info", "d4:infoi1ee"),
+                ],
+                vec![String::from(MAGNET)],
+            ))
+            .build()
+            .unwrap();
+
+        let Err(Error::InvalidRequest(message)) = params.validate() else {
+            panic!("Expected the bad file to be rejected");
+        };
+        assert!(message.contains("\"bad\""));
+        assert!(message.contains("\"no info\""));
+        assert!(!message.contains("\"good\""));
+        assert!(!message.contains("\"old\""));
+    }
+
+    #[test]
+    fn verify_params_map_path() {
+        let params = VerifyParamsBuilder::default()
+            .path_map(vec![(
+                String::from("/downloads/"),
+                PathBuf::from("/mnt/nas"),
+            )])
+            .build()
+            .unwrap();
+
+        assert_eq!(params.map_path("/downloads"), PathBuf::from("/mnt/nas"));
+        assert_eq!(
+            params.map_path("/downloads/tv"),
+            PathBuf::from("/mnt/nas/tv")
+        );
+        assert_eq!(
+            params.map_path("/downloads2/tv"),
+            PathBuf::from("/downloads2/tv")
+        );
+    }
+
+    #[test]
+    fn file_selection_plan() {
+        let file = |index, name: &str, size, priority| TorrentContent {
+            index,
+            name: name.to_string(),
+            size: Bytes(size),
+            priority,
+            ..Default::default()
+        };
+        let files = vec![
+            file(0, "Show/e01.mkv", 700, FilePriority::Normal),
+            file(1, "Show/e02.mkv", 700, FilePriority::High),
+            file(2, "Show/Sample/e01.SAMPLE.mkv", 700, FilePriority::Normal),
+            file(3, "Show/e01.nfo", 1, FilePriority::DoNotDownload),
+            file(4, "Show/e00.mkv", 50, FilePriority::Normal),
+        ];
+        let selection = FileSelectionBuilder::default()
+            .include(vec![String::from("*.mkv")])
+            .exclude(vec![String::from("*sample*")])
+            .ignore_case(true)
+            .min_size(100)
+            .priority(FilePriority::High)
+            .build()
+            .unwrap();
+
+        let plan = selection.plan(&files).unwrap();
+        assert_eq!(plan.selected, vec![0, 1]);
+        assert_eq!(plan.skipped, vec![2, 3, 4]);
+        assert_eq!(
+            plan.changes,
+            vec![
+                (FilePriority::High, vec![0]),
+                (FilePriority::DoNotDownload, vec![2, 4]),
+            ]
+        );
+
+        let invalid = FileSelectionBuilder::default()
+            .include_regex(vec![String::from("(")])
+            .build()
+            .unwrap();
+        assert!(invalid.plan(&files).is_err());
+    }
+
+    #[test]
+    fn batch_rename_plan() {
+        let file = |index, name: &str| TorrentContent {
+            index,
+            name: name.to_string(),
+            ..Default::default()
+        };
+        let files = vec![
+            file(0, "Pack/show.S01E01.mkv"),
+            file(1, "Pack/show.S01E02.mkv"),
+            file(2, "Pack/readme.txt"),
+        ];
+        let rename = BatchRenameBuilder::default()
+            .pattern(r"S(\d+)E(?<episode>\d+)")
+            .template("Episode {episode:03} ({1}).{ext}")
+            .build()
+            .unwrap();
+
+        let plan = rename.plan(&files).unwrap();
+        assert!(plan.is_valid());
+        assert_eq!(plan.renames[0].new_path, "Pack/Episode 001 (01).mkv");
+        assert_eq!(plan.renames.len(), 2);
+
+        let collide = BatchRenameBuilder::default()
+            .pattern(r"\.mkv$")
+            .template("episode.{ext}")
+            .build()
+            .unwrap();
+        assert_eq!(collide.plan(&files).unwrap().collisions.len(), 1);
+
+        let unknown = BatchRenameBuilder::default()
+            .pattern("(a)")
+            .template("{2}{show}")
+            .build()
+            .unwrap();
+        assert!(unknown.validate().is_err());
+    }
+}
@@ -21,6 +21,33 @@ where
     Ok(value.unwrap_or_default())
 }
 
+/// Deserializes an optional field from a JSON string that is empty when the
+/// value is missing.
+///
+/// This function is intended to be used with the `#[serde(deserialize_with = "")]`
+/// attribute. An empty string is returned as `None`, anything else is
+/// deserialized as `T`.
+///
+/// # Type Parameters
+///
+/// * `T`: The target type to deserialize to. This type must implement
+///   `TryFrom<String>`.
+pub fn from_empty_string_to_none<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: TryFrom<String>,
+    T::Error: std::fmt::Display,
+{
+    let value = Option::<String>::deserialize(deserializer)?;
+
+    match value {
+        Some(value) if !value.is_empty() => T::try_from(value)
+            .map(Some)
+            .map_err(serde::de::Error::custom),
+        _ => Ok(None),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,123 @@
+use regex::{Regex, RegexBuilder};
+
+use crate::error::Error;
+
+/// Compiles a glob pattern matching `/` separated paths.
+///
+/// * `*` matches anything but `/`, `?` one character but `/`.
+/// * `**` matches anything, `**/` any number of folders, none included.
+/// * `[abc]`, `[a-z]` and `[!abc]` match one character of a set.
+/// * `{mkv,mp4}` matches one of the alternatives.
+///
+/// Patterns without `/` match the last path component only, so `*.mkv`
+/// matches every `.mkv` file, in any folder.
+pub fn compile(pattern: &str, ignore_case: bool) -> Result<Regex, Error> {
+    let invalid = |reason: &str| {
+        Error::InvalidRequest(format!("Invalid glob pattern {:?}: {}", pattern, reason))
+    };
+
+    let mut regex = String::from(if pattern.contains('/') {
+        "^"
+    } else {
+        "(?:^|/)"
+    });
+    let mut chars = pattern.chars().peekable();
+    let mut alternation = false;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    regex.push_str("(?:.*/)?");
+                } else {
+                    regex.push_str(".*");
+                }
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            '[' => {
+                let mut class = String::from("[");
+                if chars.peek() == Some(&'!') {
+                    chars.next();
+                    class.push('^');
+                }
+                let mut first = true;
+                loop {
+                    match chars.next() {
+                        Some(']') if !first => break,
+                        Some(c @ ('\\' | '[' | ']' | '&' | '~' | '^')) => {
+                            class.push('\\');
+                            class.push(c);
+                        }
+                        Some(c) => class.push(c),
+                        None => return Err(invalid("unclosed '['")),
+                    }
+                    first = false;
+                }
+                class.push(']');
+                regex.push_str(&class);
+            }
+            '{' if !alternation => {
+                alternation = true;
+                regex.push_str("(?:");
+            }
+            ',' if alternation => regex.push('|'),
+            '}' if alternation => {
+                alternation = false;
+                regex.push(')');
+            }
+            '{' => return Err(invalid("nested '{'")),
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+
+    if alternation {
+        return Err(invalid("unclosed '{'"));
+    }
+    regex.push('$');
+
+    RegexBuilder::new(&regex)
+        .case_insensitive(ignore_case)
+        .build()
+        .map_err(|e| invalid(&e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(pattern: &str, path: &str) -> bool {
+        compile(pattern, false).unwrap().is_match(path)
+    }
+
+    #[test]
+    fn file_name_patterns() {
+        assert!(matches("*.mkv", "Show/Season 1/e01.mkv"));
+        assert!(matches("e0?.{mkv,mp4}", "Show/e02.mp4"));
+        assert!(!matches("*.mkv", "Show/e01.mkv.part"));
+        assert!(matches("[!a-d]*.txt", "x/readme.txt"));
+        assert!(!matches("[!a-d]*.txt", "x/about.txt"));
+    }
+
+    #[test]
+    fn path_patterns() {
+        assert!(matches("Show/**/*.srt", "Show/Subs/en/e01.srt"));
+        assert!(matches("Show/**/*.srt", "Show/e01.srt"));
+        assert!(!matches("Show/*.srt", "Show/Subs/e01.srt"));
+        assert!(matches("**", "a/b/c"));
+        assert!(
+            compile("*sample*", true)
+                .unwrap()
+                .is_match("Show/e01.SAMPLE.mkv")
+        );
+    }
+
+    #[test]
+    fn invalid_patterns() {
+        assert!(compile("[abc", false).is_err());
+        assert!(compile("{a,b", false).is_err());
+        assert!(compile("{a,{b}}", false).is_err());
+    }
+}
@@ -1 +1,4 @@
 pub mod deserializers;
+pub mod glob;
+pub mod template;
+pub mod timestamp;
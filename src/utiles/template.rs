@@ -0,0 +1,156 @@
+use crate::error::Error;
+
+/// A rename template such as `{show} - S{season:02}E{episode:02}.{ext}`.
+///
+/// `{name}` is replaced by the value of `name`, `{name:02}` pads it with
+/// zeros to two characters and `{name:3}` with spaces to three. Leading
+/// zeros of numbers are dropped before padding, so `001` fits in
+/// `{name:02}`. `{{` and `}}` are literal braces.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    Placeholder {
+        name: String,
+        width: usize,
+        zero: bool,
+    },
+}
+
+impl Template {
+    pub fn parse(template: &str) -> Result<Self, Error> {
+        let invalid = |reason: &str| {
+            Error::InvalidRequest(format!("Invalid template {:?}: {}", template, reason))
+        };
+
+        let mut segments = Vec::new();
+        let mut literal = String::new();
+        let mut chars = template.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '}' => return Err(invalid("unmatched '}'")),
+                '{' => {
+                    let mut placeholder = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some('{') | None => return Err(invalid("unclosed '{'")),
+                            Some(c) => placeholder.push(c),
+                        }
+                    }
+
+                    let (name, format) = placeholder
+                        .split_once(':')
+                        .unwrap_or((placeholder.as_str(), ""));
+                    let name = name.trim();
+                    if name.is_empty() {
+                        return Err(invalid("empty placeholder"));
+                    }
+                    let width = match format {
+                        "" => 0,
+                        format => format
+                            .parse()
+                            .map_err(|_| invalid(&format!("invalid format {:?}", format)))?,
+                    };
+
+                    if !literal.is_empty() {
+                        segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                    }
+                    segments.push(Segment::Placeholder {
+                        name: name.to_string(),
+                        width,
+                        zero: format.starts_with('0'),
+                    });
+                }
+                c => literal.push(c),
+            }
+        }
+
+        if !literal.is_empty() {
+            segments.push(Segment::Literal(literal));
+        }
+
+        Ok(Self { segments })
+    }
+
+    /// Names of the placeholders, in order
+    pub fn placeholders(&self) -> impl Iterator<Item = &str> {
+        self.segments.iter().filter_map(|segment| match segment {
+            Segment::Placeholder { name, .. } => Some(name.as_str()),
+            Segment::Literal(_) => None,
+        })
+    }
+
+    /// Fills the template, with `value` giving the value of a placeholder.
+    pub fn render<'a>(&self, value: impl Fn(&str) -> Option<&'a str>) -> String {
+        let mut output = String::new();
+
+        for segment in &self.segments {
+            match segment {
+                Segment::Literal(literal) => output.push_str(literal),
+                Segment::Placeholder { name, width, zero } => {
+                    let mut value = value(name).unwrap_or_default();
+                    if *width > 0 && !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit())
+                    {
+                        value = value.trim_start_matches('0');
+                        if value.is_empty() {
+                            value = "0";
+                        }
+                    }
+
+                    let pad = if *zero { '0' } else { ' ' };
+                    let padding = width.saturating_sub(value.chars().count());
+                    output.extend(std::iter::repeat_n(pad, padding));
+                    output.push_str(value);
+                }
+            }
+        }
+
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_template() {
+        let template =
+            Template::parse("{show} - S{season:02}E{episode:02}.{ext} {{{x:3}}}").unwrap();
+        let value = |name: &str| match name {
+            "show" => Some("Show"),
+            "season" => Some("1"),
+            "episode" => Some("012"),
+            "ext" => Some("mkv"),
+            _ => None,
+        };
+
+        assert_eq!(
+            template.placeholders().collect::<Vec<_>>(),
+            vec!["show", "season", "episode", "ext", "x"]
+        );
+        assert_eq!(template.render(value), "Show - S01E12.mkv {   }");
+    }
+
+    #[test]
+    fn invalid_templates() {
+        assert!(Template::parse("{show").is_err());
+        assert!(Template::parse("show}").is_err());
+        assert!(Template::parse("{}").is_err());
+        assert!(Template::parse("{season:ab}").is_err());
+    }
+}
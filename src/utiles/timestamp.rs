@@ -0,0 +1,210 @@
+use std::time::Duration;
+
+/// ETA reported by qBittorrent when the torrent will never finish (100 days).
+pub const INFINITE_ETA: i64 = 8_640_000;
+
+/// Decodes a timestamp field, where `-1`/`0` (and `u32::MAX` on older
+/// versions) mean "never".
+pub fn instant(timestamp: i64) -> Option<i64> {
+    if timestamp <= 0 || timestamp == i64::from(u32::MAX) {
+        None
+    } else {
+        Some(timestamp)
+    }
+}
+
+/// Decodes a duration field in seconds, where negative values mean "unknown".
+pub fn duration(seconds: i64) -> Option<Duration> {
+    u64::try_from(seconds).ok().map(Duration::from_secs)
+}
+
+/// Decodes an ETA field in seconds, where [`INFINITE_ETA`] means "never".
+pub fn eta(seconds: i64) -> Option<Duration> {
+    if seconds >= INFINITE_ETA {
+        None
+    } else {
+        duration(seconds)
+    }
+}
+
+/// Parses a date as formatted by `QDateTime::toString()` (for example
+/// `Thu Aug 10 14:09:02 2023`) or as ISO 8601 (`2023-08-10T14:09:02`) into
+/// seconds since epoch.
+///
+/// The text is in local time without an offset, so `utc_offset`, the offset
+/// of that local time from UTC in seconds, is subtracted. ISO 8601 dates
+/// ending with `Z` are already in UTC.
+pub fn parse_text_date(value: &str, utc_offset: i64) -> Option<i64> {
+    let value = value.trim();
+    let utc_offset = if value.ends_with('Z') { 0 } else { utc_offset };
+    let iso = value.starts_with(|c: char| c.is_ascii_digit());
+    let (year, month, day, time) = if iso {
+        let (date, time) = value.split_once('T')?;
+        let mut date = date.splitn(3, '-');
+        let year = date.next()?.parse().ok()?;
+        let month = date.next()?.parse().ok()?;
+        let day = date.next()?.parse().ok()?;
+        let time = time.trim_end_matches('Z');
+        (year, month, day, time.split('.').next()?)
+    } else {
+        let parts = value.split_whitespace().collect::<Vec<_>>();
+        let [_, month, day, time, year] = parts.as_slice() else {
+            return None;
+        };
+        let month = [
+            "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+        ]
+        .iter()
+        .position(|m| m == month)? as i64
+            + 1;
+        (year.parse().ok()?, month, day.parse().ok()?, *time)
+    };
+
+    let mut time = time.splitn(3, ':');
+    let hour: i64 = time.next()?.parse().ok()?;
+    let minute: i64 = time.next()?.parse().ok()?;
+    let second: i64 = time.next().unwrap_or("0").parse().ok()?;
+
+    if !(1..=12).contains(&month)
+        || !(1..=31).contains(&day)
+        || hour > 23
+        || minute > 59
+        || second > 60
+    {
+        return None;
+    }
+
+    Some(
+        days_from_civil(year, month, day) * 86_400 + hour * 3600 + minute * 60 + second
+            - utc_offset,
+    )
+}
+
+/// Formats seconds since epoch as an RFC 3339 UTC timestamp.
+///
+/// Example: `1700000000` becomes `2023-11-14T22:13:20Z`
+pub fn to_rfc3339(timestamp: i64) -> String {
+    let days = timestamp.div_euclid(86_400);
+    let seconds = timestamp.rem_euclid(86_400);
+    let (year, month, day) = civil_from_days(days);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    )
+}
+
+/// Converts days since epoch into a (year, month, day) date.
+///
+/// Based on Howard Hinnant's `civil_from_days` algorithm.
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    (year, month, day)
+}
+
+/// Converts a (year, month, day) date into days since epoch.
+///
+/// Based on Howard Hinnant's `days_from_civil` algorithm.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let mp = if month > 2 { month - 3 } else { month + 9 };
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+
+    era * 146_097 + doe - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn epoch() {
+        assert_eq!(to_rfc3339(0), "1970-01-01T00:00:00Z");
+    }
+
+    #[test]
+    fn recent_timestamp() {
+        assert_eq!(to_rfc3339(1_700_000_000), "2023-11-14T22:13:20Z");
+    }
+
+    #[test]
+    fn leap_day() {
+        assert_eq!(to_rfc3339(951_782_400), "2000-02-29T00:00:00Z");
+    }
+
+    #[test]
+    fn before_epoch() {
+        assert_eq!(to_rfc3339(-1), "1969-12-31T23:59:59Z");
+    }
+
+    #[test]
+    fn instant_sentinels() {
+        assert_eq!(instant(-1), None);
+        assert_eq!(instant(0), None);
+        assert_eq!(instant(4_294_967_295), None);
+        assert_eq!(instant(1_700_000_000), Some(1_700_000_000));
+    }
+
+    #[test]
+    fn eta_sentinels() {
+        assert_eq!(eta(INFINITE_ETA), None);
+        assert_eq!(eta(-1), None);
+        assert_eq!(eta(90), Some(Duration::from_secs(90)));
+    }
+
+    #[test]
+    fn parse_qt_text_date() {
+        assert_eq!(
+            parse_text_date("Tue Nov 14 22:13:20 2023", 0),
+            Some(1_700_000_000)
+        );
+        assert_eq!(
+            parse_text_date("Tue Feb 29 00:00:00 2000", 0),
+            Some(951_782_400)
+        );
+        assert_eq!(
+            parse_text_date("Tue Nov 14 23:13:20 2023", 3600),
+            Some(1_700_000_000)
+        );
+    }
+
+    #[test]
+    fn parse_iso_date() {
+        assert_eq!(
+            parse_text_date("2023-11-14T22:13:20", 0),
+            Some(1_700_000_000)
+        );
+        assert_eq!(
+            parse_text_date("2023-11-14T17:13:20", -18_000),
+            Some(1_700_000_000)
+        );
+        assert_eq!(
+            parse_text_date("2023-11-14T22:13:20.500Z", 3600),
+            Some(1_700_000_000)
+        );
+    }
+
+    #[test]
+    fn parse_invalid_date() {
+        assert_eq!(parse_text_date("", 0), None);
+        assert_eq!(parse_text_date("Tue Foo 14 22:13:20 2023", 0), None);
+        assert_eq!(parse_text_date("2023-13-14T22:13:20", 0), None);
+    }
+}
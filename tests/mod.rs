@@ -1,7 +1,7 @@
 use dotenv::dotenv;
 use qbit::{
     Api,
-    models::{Torrent, TorrentCreatorBuilder, TorrentCreatorTask},
+    models::{InfoHash, Torrent, TorrentCreatorBuilder, TorrentCreatorTask},
     parameters::AddTorrentBuilder,
 };
 use rand::{Rng, distr::Alphabetic};
@@ -13,6 +13,10 @@ pub mod sync;
 pub mod torrents;
 
 pub const DEBIAN_HASH: &str = "6f4370df4304609a8793ce2b59178dcc8febf5e2";
+
+pub fn debian_hash() -> InfoHash {
+    InfoHash::new(DEBIAN_HASH).expect("Invalid debian hash")
+}
 pub const DEBIAN_TRACKER: &str = "magnet:?xt=urn:btih:6f4370df4304609a8793ce2b59178dcc8febf5e2&dn=debian-12.11.0-amd64-netinst.iso&xl=702545920&tr=http%3A%2F%2Fbttracker.debian.org%3A6969%2Fannounce&ws=https://cdimage.debian.org/cdimage/archive/12.11.0/amd64/iso-cd/debian-12.11.0-amd64-netinst.iso&ws=https://cdimage.debian.org/cdimage/release/12.11.0/amd64/iso-cd/debian-12.11.0-amd64-netinst.iso";
 
 pub fn get_server_details() -> String {
@@ -54,7 +58,7 @@ pub async fn login_default_client() -> Api {
 pub async fn add_debian_torrent(client: &Api) {
     let param = AddTorrentBuilder::default()
         .torrents(vec![DEBIAN_TRACKER.to_string()])
-        .paused(true)
+        .stopped(true)
         .build()
         .expect("Failed to build AddTorrent");
 
@@ -62,11 +66,6 @@ pub async fn add_debian_torrent(client: &Api) {
         .add_torrent(param)
         .await
         .expect("Failed to add torrent");
-    // Note: Added the stop call since the paused parameter doesn't work for some reason.
-    client
-        .stop(vec![DEBIAN_HASH])
-        .await
-        .expect("Failed to stop torrent");
 }
 
 pub async fn get_debian_torrent(client: &Api) -> Option<Torrent> {
@@ -77,8 +76,7 @@ pub async fn get_debian_torrent(client: &Api) -> Option<Torrent> {
 
     torrents
         .iter()
-        .filter(|t| t.hash == DEBIAN_HASH)
-        .next()
+        .find(|t| t.hash == DEBIAN_HASH)
         .map(|t| t.to_owned())
 }
 
@@ -1,4 +1,4 @@
-use crate::{DEBIAN_HASH, add_debian_torrent, login_default_client};
+use crate::{add_debian_torrent, debian_hash, login_default_client};
 
 /// This test ensures that the API correctly deserialize the torrents field from the response.
 #[tokio::test]
@@ -15,5 +15,5 @@ async fn correctly_deserialize_from_response() {
     assert!(res.torrents.is_some());
     let torrents = res.torrents.unwrap();
     assert!(!torrents.is_empty());
-    assert!(torrents.contains_key(DEBIAN_HASH));
+    assert!(torrents.contains_key(&debian_hash()));
 }
@@ -43,18 +43,8 @@ async fn get_torrent_file() {
 
     // This should hopefully let the torrent finish creating before attempting to do other stuff.
     let mut limit = 10;
-    while list
-        .iter()
-        .filter(|v| v.task_id == task)
-        .next()
-        .unwrap()
-        .status
-        != TaskStatus::Finished
-    {
-        println!(
-            "{:?}",
-            list.iter().filter(|v| v.task_id == task).next().unwrap()
-        );
+    while list.iter().find(|v| v.task_id == task).unwrap().status != TaskStatus::Finished {
+        println!("{:?}", list.iter().find(|v| v.task_id == task).unwrap());
         if limit == 0 {
             panic!("Torrent has not finished creating after ~ 10 seconds of checking.");
         }
@@ -72,6 +62,7 @@ async fn get_torrent_file() {
     let path = format!("{folder}_data/dummy{}.torrent", random_name.unwrap());
     let data = fs::read(&path).unwrap();
 
+    let mut matched = false;
     for item in list.iter() {
         let r = client
             .get_task_file(item.task_id.to_owned())
@@ -79,9 +70,11 @@ async fn get_torrent_file() {
             .unwrap_or_default()
             .to_vec();
         if r == data {
-            assert!(true);
+            matched = true;
+            break;
         }
     }
+    assert!(matched, "No task file matches {path}");
 }
 
 /// Make sure that we can delete the created task.
@@ -119,11 +112,7 @@ async fn make_failed_task() {
     let result = client.get_task_file(id).await;
 
     assert!(result.is_err());
-    if let Err(Error::Http409(_e)) = result {
-        assert!(true);
-        // eprintln!("error: {}", _e);
-        // assert!(false);
-    } else {
+    if !matches!(result, Err(Error::Http409(_))) {
         panic!("Expected Http409 error");
     }
 }
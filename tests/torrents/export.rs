@@ -0,0 +1,53 @@
+use qbit::{
+    metainfo::{Info, Metainfo},
+    parameters::{AddTorrentBuilder, TorrentFile},
+};
+
+use crate::{create_random_name, login_default_client};
+
+/// This test ensures that an exported torrent is the `.torrent` file it was
+/// added from. A torrent added from a `.torrent` file has its metadata right
+/// away, unlike a stopped magnet link.
+#[tokio::test]
+#[ignore = "Test hits api endpoint"]
+async fn export_torrent_file() {
+    let client = login_default_client().await;
+
+    let metainfo = Metainfo {
+        info: Info {
+            name: format!("export{}.txt", create_random_name().unwrap_or_default()),
+            piece_length: 16384,
+            pieces: Some(vec![0; 20]),
+            length: Some(1),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let hash = metainfo.info_hash();
+
+    let param = AddTorrentBuilder::default()
+        .torrents(TorrentFile {
+            filename: String::from("export.torrent"),
+            data: metainfo.to_bytes(),
+        })
+        .stopped(true)
+        .build()
+        .expect("Failed to build AddTorrent");
+    client
+        .add_torrent(param)
+        .await
+        .expect("Failed to add torrent");
+
+    let data = client
+        .export(&hash)
+        .await
+        .expect("Failed to export torrent");
+    let exported = Metainfo::from_bytes(&data).expect("Invalid exported torrent");
+
+    assert_eq!(exported.info_hash(), hash);
+
+    client
+        .delete(&hash, false)
+        .await
+        .expect("Failed to delete torrent");
+}
@@ -1,3 +1,4 @@
 pub mod creator;
+pub mod export;
 pub mod get_torrents;
 pub mod state;